@@ -0,0 +1,54 @@
+//! Compares adding items one by one to a group, and all at once, against the push-then-sort
+//! approach it replaced.
+//!
+//! Run with `cargo bench --bench group_add`.
+
+use std::time::{Duration, Instant};
+use rbp_core::budget_group::BudgetGroup;
+use rbp_core::budget_item::{BudgetItem, Period};
+
+// Items with names in a scrambled order, so that every insertion lands somewhere in the middle.
+fn items(count: usize) -> Vec<BudgetItem> {
+    (0..count)
+        .map(|idx| BudgetItem::with_expense(&format!("Item {:08}", idx * 7_919 % count), 1.0 + idx as f64, Period::Every1Month))
+        .collect()
+}
+
+// The fastest of a few runs, to smooth out noise.
+fn measure<F: FnMut()>(mut run: F) -> Duration {
+    (0..5).map(|_| {
+        let start = Instant::now();
+        run();
+        start.elapsed()
+    }).min().unwrap()
+}
+
+fn main() {
+    for &count in &[1_000, 4_000, 10_000] {
+        let items = items(count);
+
+        let binary_insert = measure(|| {
+            let mut group = BudgetGroup::new("Bench");
+            for item in items.iter().cloned() {
+                group.add(item);
+            }
+            assert_eq!(count, group.enumerate().len());
+        });
+        let add_all = measure(|| {
+            let mut group = BudgetGroup::new("Bench");
+            group.add_all(items.iter().cloned());
+            assert_eq!(count, group.enumerate().len());
+        });
+        let push_and_sort = measure(|| {
+            let mut sorted = Vec::new();
+            for item in items.iter().cloned() {
+                sorted.push(item);
+                sorted.sort_unstable();
+            }
+            assert_eq!(count, sorted.len());
+        });
+
+        println!("{:>6} items: add {:>10.2?}, add all {:>10.2?}, push and sort {:>10.2?} ({:.1}x)", count,
+                 binary_insert, add_all, push_and_sort, push_and_sort.as_secs_f64() / binary_insert.as_secs_f64());
+    }
+}
@@ -0,0 +1,55 @@
+/*
+ * C interface of the Rust Budget Planner core library.
+ *
+ * Available when the crate is built with the `capi` feature, e.g. as a static library with:
+ *
+ *     cargo rustc --lib --release --features capi --crate-type staticlib
+ *
+ * Keep in sync with src/ffi.rs.
+ */
+
+#ifndef RBP_CORE_H
+#define RBP_CORE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes returned by the functions below. */
+#define RBP_OK 0
+#define RBP_NULL_POINTER 1
+#define RBP_INVALID_UTF8 2
+#define RBP_NOT_FOUND 3
+#define RBP_INVALID_AMOUNT 4
+#define RBP_INVALID_PERIOD 5
+
+/* Opaque handle to a budget. */
+typedef struct RbpBudget RbpBudget;
+
+/* Create a new, empty budget. Returns NULL if name is NULL or not valid UTF-8. */
+RbpBudget *rbp_budget_new(const char *name);
+
+/* Release a budget created with rbp_budget_new. Passing NULL does nothing. */
+void rbp_budget_free(RbpBudget *budget);
+
+/* Add an empty group to the end of a budget. */
+int rbp_budget_add_group(RbpBudget *budget, const char *name);
+
+/*
+ * Add an item to the first group with the given name. The amount must be greater than 0, the
+ * period is given in months (1, 2, 3, 6, or 12), and is_income is non-zero for an income.
+ */
+int rbp_budget_add_item(RbpBudget *budget, const char *group, const char *name,
+                        double amount, int period_months, int is_income);
+
+/* The monthly total of a budget, or 0 if budget is NULL. */
+double rbp_budget_total(const RbpBudget *budget);
+
+/* Write the monthly total of the first group with the given name to total. */
+int rbp_budget_group_total(const RbpBudget *budget, const char *group, double *total);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RBP_CORE_H */
@@ -0,0 +1,466 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use crate::budget::{Budget, BudgetDefaults};
+use crate::budget_group::ItemOrder;
+use crate::budget_item::BudgetItem;
+use crate::clock::{Clock, Timestamp};
+use crate::document;
+use crate::journal;
+use crate::json;
+
+/// A change made to a budget.
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// A group was added to the budget. When the group already holds items, this change is
+    /// followed by an [`ItemAdded`](#variant.ItemAdded) for each of them.
+    GroupAdded {
+        /// The name of the added group.
+        group: String,
+        /// The index of the group in the budget after the change.
+        index: usize,
+    },
+    /// A group, including its items, was removed from the budget.
+    GroupRemoved {
+        /// The name of the removed group.
+        group: String,
+    },
+    /// A group was given a new name.
+    GroupRenamed {
+        /// The name of the group before the change.
+        from: String,
+        /// The name of the group after the change.
+        to: String,
+    },
+    /// An item was added to a group.
+    ItemAdded {
+        /// The name of the group the item was added to.
+        group: String,
+        /// The added item.
+        item: BudgetItem,
+    },
+    /// An item was removed from a group.
+    ItemRemoved {
+        /// The name of the group the item was removed from.
+        group: String,
+        /// The removed item.
+        item: BudgetItem,
+    },
+    /// An item in a group was replaced by another.
+    ItemUpdated {
+        /// The name of the group holding the item.
+        group: String,
+        /// The item before the change.
+        from: BudgetItem,
+        /// The item after the change.
+        to: BudgetItem,
+    },
+    /// An item was moved from one group to another.
+    ItemMoved {
+        /// The name of the group the item was moved from.
+        from: String,
+        /// The name of the group the item was moved to.
+        to: String,
+        /// The moved item.
+        item: BudgetItem,
+    },
+    /// An item was moved to another index within its group, which switched the group to
+    /// [manual order](../budget_group/enum.ItemOrder.html#variant.Manual).
+    ItemReordered {
+        /// The name of the group holding the item.
+        group: String,
+        /// The moved item.
+        item: BudgetItem,
+        /// The index of the item in the group after the change.
+        to: usize,
+    },
+    /// A group changed how it orders its items. When a group in manual order is added to the
+    /// budget, this change follows its [`GroupAdded`](#variant.GroupAdded), before its items.
+    OrderChanged {
+        /// The name of the group.
+        group: String,
+        /// The order of the group after the change.
+        order: ItemOrder,
+    },
+    /// The budget-wide defaults were replaced.
+    DefaultsChanged {
+        /// The defaults before the change.
+        from: BudgetDefaults,
+        /// The defaults after the change.
+        to: BudgetDefaults,
+    },
+}
+
+/// A change, and the time it was made.
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    /// The time of the change, according to the budget's audit clock.
+    pub time: Timestamp,
+    /// What was changed.
+    pub change: Change,
+}
+
+/// The history of the structural changes made to a budget, oldest first.
+///
+/// A budget only keeps a log once auditing is enabled with
+/// [`Budget::enable_audit`](../budget/struct.Budget.html#method.enable_audit). Only the changes
+/// made through the methods of the budget itself are recorded; changes made directly on a group
+/// obtained with [`Budget::group_mut`](../budget/struct.Budget.html#method.group_mut) are not.
+///
+/// ```
+/// use std::rc::Rc;
+/// use rbp_core::audit::Change;
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::clock::{FixedClock, Timestamp};
+/// let mut budget = Budget::new("Home");
+/// budget.enable_audit(Rc::new(FixedClock::new(Timestamp(1_000))));
+/// budget.add_group(BudgetGroup::new("Housing"));
+///
+/// let event = &budget.audit_log().unwrap().events()[0];
+/// assert_eq!(Timestamp(1_000), event.time);
+/// assert!(matches!(&event.change, Change::GroupAdded { group, .. } if group == "Housing"));
+/// ```
+pub struct AuditLog {
+    clock: Rc<dyn Clock>,
+    // The document of the budget when the log started, which the events are replayed onto to
+    // reconstruct the budget at a point in time.
+    base: String,
+    started: Timestamp,
+    events: Vec<AuditEvent>,
+}
+
+impl AuditEvent {
+
+    /// Serialize the event as a JSON object.
+    ///
+    /// The object holds the `time` in seconds since the UNIX epoch, the `type` of the change
+    /// (`group_added`, `group_removed`, `group_renamed`, `item_added`, `item_removed`,
+    /// `item_updated`, `item_moved`, `item_reordered`, `group_order_changed` or
+    /// `defaults_changed`), and the names, indices, items, orders (`sorted` or `manual`) and
+    /// defaults involved.
+    /// Items are objects with a `name`, a `kind` (`income` or `expense`), an `amount`, and a
+    /// `period` (`monthly`, `bimonthly`, `quarterly`, `half-yearly` or `yearly`). Defaults are
+    /// objects with a `currency`, a `period` and a `rounding` (`none`, `half-up` or `half-even`).
+    ///
+    /// # Returns
+    /// The JSON text.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_event(&mut out, self);
+        out
+    }
+}
+
+impl AuditLog {
+
+    // Create an empty log of a budget as it is now, timestamping events with the clock.
+    pub(crate) fn new(clock: Rc<dyn Clock>, budget: &Budget) -> AuditLog {
+        let started = clock.now();
+        AuditLog {
+            clock,
+            base: document::to_string(budget),
+            started,
+            events: Vec::new()
+        }
+    }
+
+    // Get the current time of the clock of the log.
+    pub(crate) fn now(&self) -> Timestamp {
+        self.clock.now()
+    }
+
+    // Fold the events recorded before a point in time into the start of the log, so that the log
+    // starts at that time. The log is kept as is if those events cannot be replayed.
+    pub(crate) fn compact(&mut self, before: Timestamp) {
+        if before <= self.started {
+            return;
+        }
+        let count = self.events.iter().take_while(|event| event.time < before).count();
+        let changes: Vec<Change> = self.events[..count].iter().map(|event| event.change.clone()).collect();
+        let mut budget = match document::from_str(&self.base) {
+            Ok(budget) => budget,
+            Err(_) => return,
+        };
+        if journal::replay(&mut budget, &changes).is_err() {
+            return;
+        }
+        self.base = document::to_string(&budget);
+        self.started = before;
+        self.events.drain(..count);
+    }
+
+    // Reconstruct the budget at a point in time, as described on `Budget::at`.
+    pub(crate) fn at(&self, time: Timestamp) -> Option<Budget> {
+        if time < self.started {
+            return None;
+        }
+        let mut budget = document::from_str(&self.base).ok()?;
+        let changes: Vec<Change> = self.events.iter()
+            .take_while(|event| event.time <= time)
+            .map(|event| event.change.clone())
+            .collect();
+        journal::replay(&mut budget, &changes).ok()?;
+        budget.mark_clean();
+        Some(budget)
+    }
+
+    // Record a change as happening now.
+    pub(crate) fn record(&mut self, change: Change) {
+        let time = self.clock.now();
+        self.events.push(AuditEvent { time, change });
+    }
+
+    /// Get the point in time the log starts at, which is when auditing was enabled, or the cutoff of
+    /// the last [`Budget::compact`](../budget/struct.Budget.html#method.compact). The budget can be
+    /// reconstructed with [`Budget::at`](../budget/struct.Budget.html#method.at) from then on.
+    pub fn started(&self) -> Timestamp {
+        self.started
+    }
+
+    /// Get all recorded events, oldest first.
+    pub fn events(&self) -> &[AuditEvent] {
+        &self.events
+    }
+
+    /// Get the events recorded at or after a point in time, oldest first.
+    ///
+    /// # Parameters
+    /// * `time` - the earliest time of the returned events.
+    pub fn since(&self, time: Timestamp) -> impl Iterator<Item = &AuditEvent> {
+        self.events.iter().filter(move |event| event.time >= time)
+    }
+
+    /// Get the events concerning a group, oldest first.
+    ///
+    /// A renamed group matches both its old and its new name, and a moved item matches both of its
+    /// groups.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the group.
+    pub fn for_group<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a AuditEvent> {
+        self.events.iter().filter(move |event| match &event.change {
+            Change::GroupRenamed { from, to } | Change::ItemMoved { from, to, .. } => from == name || to == name,
+            Change::GroupAdded { group, .. } | Change::GroupRemoved { group } | Change::ItemAdded { group, .. }
+                | Change::ItemRemoved { group, .. } | Change::ItemUpdated { group, .. }
+                | Change::ItemReordered { group, .. } | Change::OrderChanged { group, .. } => group == name,
+            Change::DefaultsChanged { .. } => false,
+        })
+    }
+
+    /// Serialize the log as a JSON array of events, oldest first. The events have the format
+    /// described by [`AuditEvent::to_json`].
+    ///
+    /// # Returns
+    /// The JSON text.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (idx, event) in self.events.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            write_event(&mut out, event);
+        }
+        out.push(']');
+        out
+    }
+}
+
+// Append the JSON object of an event.
+fn write_event(out: &mut String, event: &AuditEvent) {
+    // Writing to a String cannot fail.
+    let _ = write!(out, "{{\"time\":{},\"type\":", event.time.seconds());
+    match &event.change {
+        Change::GroupAdded { group, index } => {
+            json::string(out, "group_added");
+            write_field(out, "group", group);
+            let _ = write!(out, ",\"index\":{}", index);
+        }
+        Change::GroupRemoved { group } => {
+            json::string(out, "group_removed");
+            write_field(out, "group", group);
+        }
+        Change::GroupRenamed { from, to } => {
+            json::string(out, "group_renamed");
+            write_field(out, "from", from);
+            write_field(out, "to", to);
+        }
+        Change::ItemAdded { group, item } => {
+            json::string(out, "item_added");
+            write_field(out, "group", group);
+            out.push_str(",\"item\":");
+            json::item(out, item);
+        }
+        Change::ItemRemoved { group, item } => {
+            json::string(out, "item_removed");
+            write_field(out, "group", group);
+            out.push_str(",\"item\":");
+            json::item(out, item);
+        }
+        Change::ItemUpdated { group, from, to } => {
+            json::string(out, "item_updated");
+            write_field(out, "group", group);
+            out.push_str(",\"from\":");
+            json::item(out, from);
+            out.push_str(",\"to\":");
+            json::item(out, to);
+        }
+        Change::ItemMoved { from, to, item } => {
+            json::string(out, "item_moved");
+            write_field(out, "from", from);
+            write_field(out, "to", to);
+            out.push_str(",\"item\":");
+            json::item(out, item);
+        }
+        Change::ItemReordered { group, item, to } => {
+            json::string(out, "item_reordered");
+            write_field(out, "group", group);
+            out.push_str(",\"item\":");
+            json::item(out, item);
+            let _ = write!(out, ",\"to\":{}", to);
+        }
+        Change::OrderChanged { group, order } => {
+            json::string(out, "group_order_changed");
+            write_field(out, "group", group);
+            write_field(out, "order", order.keyword());
+        }
+        Change::DefaultsChanged { from, to } => {
+            json::string(out, "defaults_changed");
+            out.push_str(",\"from\":");
+            json::defaults(out, from);
+            out.push_str(",\"to\":");
+            json::defaults(out, to);
+        }
+    }
+    out.push('}');
+}
+
+// Append a string field to a JSON object that already has at least one field.
+fn write_field(out: &mut String, key: &str, value: &str) {
+    out.push(',');
+    json::string(out, key);
+    out.push(':');
+    json::string(out, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use crate::audit::Change;
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::clock::{FixedClock, Timestamp};
+
+    fn audited() -> (Budget, Rc<FixedClock>) {
+        let clock = Rc::new(FixedClock::new(Timestamp(100)));
+        let mut budget = Budget::new("Home");
+        budget.enable_audit(clock.clone());
+        (budget, clock)
+    }
+
+    #[test]
+    fn records_changes_with_time() {
+        let (mut budget, clock) = audited();
+        budget.add_group(BudgetGroup::new("Housing"));
+        clock.advance(10);
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        clock.advance(10);
+        budget.update_item(0, 0, BudgetItem::with_expense("Rent", 950.0, Period::Every1Month)).unwrap();
+        budget.rename_group(0, "Home").unwrap();
+        budget.remove_item(0, 0).unwrap();
+        budget.remove_group(0).unwrap();
+
+        let log = budget.audit_log().unwrap();
+        let times: Vec<u64> = log.events().iter().map(|event| event.time.seconds()).collect();
+        assert_eq!(vec![100, 110, 120, 120, 120, 120], times);
+        assert!(matches!(&log.events()[2].change,
+                         Change::ItemUpdated { from, to, .. } if from.amount() == 900.0 && to.amount() == 950.0));
+        assert!(matches!(&log.events()[3].change, Change::GroupRenamed { from, to } if from == "Housing" && to == "Home"));
+        assert!(matches!(&log.events()[4].change, Change::ItemRemoved { group, .. } if group == "Home"));
+    }
+
+    #[test]
+    fn failed_changes_are_not_recorded() {
+        let (mut budget, _) = audited();
+        assert!(budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).is_err());
+        assert!(budget.remove_group(0).is_err());
+
+        assert_eq!(0, budget.audit_log().unwrap().events().len());
+    }
+
+    #[test]
+    fn not_recorded_before_enabled() {
+        let mut budget = Budget::new("Home");
+        budget.add_group(BudgetGroup::new("Housing"));
+        assert!(budget.audit_log().is_none());
+
+        budget.enable_audit(Rc::new(FixedClock::new(Timestamp(0))));
+        assert_eq!(0, budget.audit_log().unwrap().events().len());
+    }
+
+    #[test]
+    fn queries() {
+        let (mut budget, clock) = audited();
+        budget.add_group(BudgetGroup::new("Housing"));
+        budget.add_group(BudgetGroup::new("Food"));
+        clock.advance(50);
+        budget.rename_group(0, "Home").unwrap();
+        budget.add_item(1, BudgetItem::with_expense("Groceries", 300.0, Period::Every1Month)).unwrap();
+
+        let log = budget.audit_log().unwrap();
+        assert_eq!(2, log.since(Timestamp(150)).count());
+        assert_eq!(2, log.for_group("Housing").count());
+        assert_eq!(1, log.for_group("Home").count());
+        assert_eq!(2, log.for_group("Food").count());
+    }
+
+    #[test]
+    fn reconstruct_past_budgets() {
+        let mut budget = Budget::new("Home");
+        budget.add_group(BudgetGroup::new("Housing"));
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        let clock = Rc::new(FixedClock::new(Timestamp(100)));
+        budget.enable_audit(clock.clone());
+        clock.advance(10);
+        budget.update_item(0, 0, BudgetItem::with_expense("Rent", 950.0, Period::Every1Month)).unwrap();
+        clock.advance(10);
+        budget.rename_group(0, "Home").unwrap();
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), ..BudgetDefaults::default() });
+
+        assert!(budget.at(Timestamp(99)).is_none());
+        let start = budget.at(Timestamp(100)).unwrap();
+        assert_eq!(-900.0, start.group("Housing").unwrap().total());
+        assert!(!start.is_dirty());
+        assert!(start.audit_log().is_none());
+        assert_eq!(-950.0, budget.at(Timestamp(119)).unwrap().group("Housing").unwrap().total());
+        let now = budget.at(Timestamp(120)).unwrap();
+        assert!(now.group("Housing").is_none());
+        assert_eq!(-950.0, now.group("Home").unwrap().total());
+        assert_eq!("€", now.defaults().currency);
+
+        assert!(Budget::new("Home").at(Timestamp(100)).is_none());
+    }
+
+    #[test]
+    fn serialize() {
+        let (mut budget, _) = audited();
+        budget.add_group(BudgetGroup::new("Housing"));
+        budget.add_item(0, BudgetItem::with_income("Salary", 2_000.5, Period::Every1Month)).unwrap();
+        budget.rename_group(0, "Home \"sweet\" home").unwrap();
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), ..BudgetDefaults::default() });
+
+        let log = budget.audit_log().unwrap();
+        assert_eq!("{\"time\":100,\"type\":\"group_added\",\"group\":\"Housing\",\"index\":0}", log.events()[0].to_json());
+        assert_eq!(concat!(
+            "[{\"time\":100,\"type\":\"group_added\",\"group\":\"Housing\",\"index\":0},",
+            "{\"time\":100,\"type\":\"item_added\",\"group\":\"Housing\",",
+            "\"item\":{\"name\":\"Salary\",\"kind\":\"income\",\"amount\":2000.5,\"period\":\"monthly\"}},",
+            "{\"time\":100,\"type\":\"group_renamed\",\"from\":\"Housing\",\"to\":\"Home \\\"sweet\\\" home\"},",
+            "{\"time\":100,\"type\":\"defaults_changed\",",
+            "\"from\":{\"currency\":\"\",\"period\":\"monthly\",\"rounding\":\"none\",\"locale\":null},",
+            "\"to\":{\"currency\":\"€\",\"period\":\"monthly\",\"rounding\":\"none\",\"locale\":null}}]"
+        ), log.to_json());
+    }
+}
@@ -0,0 +1,1237 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::slice::Iter;
+use core::iter::Enumerate;
+use crate::audit::{AuditLog, Change};
+use crate::budget_group::{BudgetGroup, InvalidIndex, ItemOrder};
+use crate::budget_item::{saturating_sum, BudgetItem, BudgetItemBuilder, ContributionRange, Period, RoundingPolicy};
+use crate::clock::{Clock, Timestamp};
+use crate::delete::{self, DeleteImpact, Selector};
+use crate::diff::{self, BudgetDiff};
+use crate::journal::{self, JournalError};
+use crate::locale::Locale;
+use crate::merge::{self, Conflict, MergePolicy};
+use crate::observer::{ChangeEvent, Observers, Subscription};
+use crate::scenario::Scenario;
+use crate::retention::RetentionPolicy;
+use crate::search::{self, SearchHit};
+use crate::snapshot::{GroupChange, Snapshot};
+use crate::undo::{self, Edit, History};
+
+/// A complete budget, made up of an ordered collection of named budget groups.
+///
+/// The groups are kept in the order they were added, as this is usually the order in which a
+/// user wants them presented (e.g. income first, then housing, then everything else).
+pub struct Budget {
+    name: String,
+    groups: Vec<BudgetGroup>,
+    defaults: BudgetDefaults,
+    audit: Option<AuditLog>,
+    history: History,
+    observers: Observers,
+    snapshots: Vec<Snapshot>,
+    revision: u64,
+    clean_revision: u64
+}
+
+/// Budget-wide settings that apply whenever a value is not specified explicitly.
+///
+/// These are set once per budget, so that frontends do not have to hard-code a currency or a
+/// preferred period, and users do not have to repeat them for every entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetDefaults {
+    /// The currency symbol or code amounts in the budget are expressed in, e.g. `"€"` or `"DKK"`.
+    pub currency: String,
+    /// The period used for new items when none is given.
+    pub period: Period,
+    /// How monthly contributions are rounded in the totals of the budget.
+    pub rounding: RoundingPolicy,
+    /// How amounts are written for and read from the users of the budget, or `None` to leave it
+    /// to the frontend.
+    pub locale: Option<Locale>,
+}
+
+/// The key figures of a budget, returned by [`Budget::summary`](struct.Budget.html#method.summary),
+/// e.g. for the header of a dashboard. All amounts are monthly contributions, rounded according to
+/// the rounding policy of the budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetSummary {
+    /// The total income.
+    pub income: f64,
+    /// The total expenses, which is negative.
+    pub expenses: f64,
+    /// The income minus the expenses, which is negative if the budget has a deficit.
+    pub net: f64,
+    /// The share of the income that is not spent, e.g. `0.2` for 20%, or `None` if there is no
+    /// income. Is negative if the budget has a deficit.
+    pub savings_rate: Option<f64>,
+    /// The name of every group with its share of the total expenses, e.g. `0.5` for 50%, in the
+    /// order of the groups. The shares are `0.0` if there are no expenses.
+    pub expense_shares: Vec<(String, f64)>,
+}
+
+impl Default for BudgetDefaults {
+    fn default() -> Self {
+        BudgetDefaults {
+            currency: String::new(),
+            period: Period::Every1Month,
+            rounding: RoundingPolicy::None,
+            locale: None
+        }
+    }
+}
+
+impl BudgetDefaults {
+
+    /// Create a new income budget item with the default period.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the item.
+    /// * `amount` - the positive amount the item contributes with every default period.
+    ///
+    /// # Panics
+    /// If the amount is not a finite number greater than 0, the method will panic.
+    pub fn income(&self, name: &str, amount: f64) -> BudgetItem {
+        BudgetItem::with_income(name, amount, self.period)
+    }
+
+    /// Create a new expense budget item with the default period.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the item.
+    /// * `amount` - the positive amount the item contributes with every default period.
+    ///
+    /// # Panics
+    /// If the amount is not a finite number greater than 0, the method will panic.
+    pub fn expense(&self, name: &str, amount: f64) -> BudgetItem {
+        BudgetItem::with_expense(name, amount, self.period)
+    }
+}
+
+impl Budget {
+
+    /// Create a new, empty budget with a name.
+    ///
+    /// # Parameters
+    /// * `name` - the desired name of the budget.
+    pub fn new(name: &str) -> Budget {
+        Budget {
+            name: name.to_owned(),
+            groups: Vec::new(),
+            defaults: BudgetDefaults::default(),
+            audit: None,
+            history: History::default(),
+            observers: Observers::default(),
+            snapshots: Vec::new(),
+            revision: 0,
+            clean_revision: 0
+        }
+    }
+
+    /// Restore the edits made to a budget since it was last saved, e.g. after a crash, by replaying
+    /// the changes recorded in its journal onto the saved budget.
+    ///
+    /// The journal is meant to be written while editing, by appending every change passed to a
+    /// [subscribed](#method.subscribe) callback, e.g. with
+    /// [`BudgetRepository::append_journal`](../storage/trait.BudgetRepository.html#tymethod.append_journal).
+    /// Groups are found by name and items by their exact values, so changes made through
+    /// [`group_mut`](#method.group_mut), which do not reach the subscribers, must not be mixed in.
+    ///
+    /// The groups, their items and the defaults are restored. The external references of items,
+    /// the undo history, the snapshots and the audit log are not.
+    ///
+    /// # Parameters
+    /// * `base` - the budget as it was last saved.
+    /// * `log` - the changes made since, in order.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the recovered budget, which is [dirty](#method.is_dirty) if any change
+    /// was replayed, or `Result::Err` with the first change that cannot be applied.
+    pub fn recover(mut base: Budget, log: &[Change]) -> Result<Budget, JournalError> {
+        journal::replay(&mut base, log)?;
+        Ok(base)
+    }
+
+    /// Get a reference to the budget's name.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Get a reference to the budget-wide defaults.
+    pub fn defaults(&self) -> &BudgetDefaults {
+        &self.defaults
+    }
+
+    /// Start building an item with the defaults of the budget, as with
+    /// [`BudgetItemBuilder::with_defaults`](../budget_item/struct.BudgetItemBuilder.html#method.with_defaults).
+    ///
+    /// ```
+    /// use rbp_core::budget::{Budget, BudgetDefaults};
+    /// use rbp_core::budget_item::Period;
+    /// let mut budget = Budget::new("Home");
+    /// budget.set_defaults(BudgetDefaults { period: Period::Every12Months, ..BudgetDefaults::default() });
+    ///
+    /// let insurance = budget.item_builder().name("Insurance").expense(240.0).build().unwrap();
+    /// assert_eq!(Period::Every12Months, insurance.period());
+    /// ```
+    pub fn item_builder(&self) -> BudgetItemBuilder {
+        BudgetItemBuilder::with_defaults(&self.defaults)
+    }
+
+    /// Replace the budget-wide defaults.
+    ///
+    /// Changing the defaults does not affect existing items; it only applies to values that are
+    /// created or presented afterwards.
+    ///
+    /// # Parameters
+    /// * `defaults` - the new defaults.
+    pub fn set_defaults(&mut self, defaults: BudgetDefaults) {
+        let from = core::mem::replace(&mut self.defaults, defaults);
+        self.revision += 1;
+        // The edits in the undo history do not depend on the defaults, so it is kept.
+        self.announce(Change::DefaultsChanged { from, to: self.defaults.clone() });
+    }
+
+    /// Get the revision of the budget, a counter that is increased by every change.
+    ///
+    /// The revision never decreases, not even when a change is undone, so two equal revisions
+    /// always mean that nothing happened in between. Getting a group with
+    /// [`group_mut`](#method.group_mut) counts as a change, as the budget cannot tell what is done
+    /// with it.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Check whether the budget has changed since it was created, or since it was last marked
+    /// clean with [`mark_clean`](#method.mark_clean), e.g. to prompt for unsaved changes.
+    ///
+    /// ```
+    /// use rbp_core::budget::Budget;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// let mut budget = Budget::new("Home");
+    /// assert!(!budget.is_dirty());
+    ///
+    /// budget.add_group(BudgetGroup::new("Housing"));
+    /// assert!(budget.is_dirty());
+    /// budget.mark_clean();
+    /// assert!(!budget.is_dirty());
+    /// ```
+    pub fn is_dirty(&self) -> bool {
+        self.revision != self.clean_revision
+    }
+
+    /// Mark the current revision of the budget as clean, typically after it has been saved.
+    pub fn mark_clean(&mut self) {
+        self.clean_revision = self.revision;
+    }
+
+    /// Start recording the structural changes made to the budget in an audit log.
+    ///
+    /// Changes made before auditing is enabled are not recorded. Enabling auditing again starts a
+    /// new, empty log.
+    ///
+    /// # Parameters
+    /// * `clock` - the clock used to timestamp the recorded changes.
+    pub fn enable_audit(&mut self, clock: Rc<dyn Clock>) {
+        self.audit = Some(AuditLog::new(clock, self));
+    }
+
+    /// Reconstruct the budget as it was at a point in time, e.g. for a frontend to show the budget
+    /// as of last March, by replaying the audit log onto the budget as it was when auditing was
+    /// enabled.
+    ///
+    /// The groups, their items and the defaults are reconstructed like with
+    /// [`recover`](#method.recover); the external references of items are as they were when
+    /// auditing was enabled. Changes made through [`group_mut`](#method.group_mut) are not in the
+    /// audit log, so once a group has been edited that way the replay may no longer apply.
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use rbp_core::budget::Budget;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::clock::{FixedClock, Timestamp};
+    /// let clock = Rc::new(FixedClock::new(Timestamp(1_000)));
+    /// let mut budget = Budget::new("Home");
+    /// budget.enable_audit(clock.clone());
+    /// budget.add_group(BudgetGroup::new("Housing"));
+    /// clock.set(Timestamp(2_000));
+    /// budget.add_group(BudgetGroup::new("Food"));
+    ///
+    /// let past = budget.at(Timestamp(1_500)).unwrap();
+    /// assert!(past.group("Housing").is_some());
+    /// assert!(past.group("Food").is_none());
+    /// ```
+    ///
+    /// # Parameters
+    /// * `time` - the point in time. Changes made at that time are included.
+    ///
+    /// # Returns
+    /// The budget as it was, which is not [dirty](#method.is_dirty) and has no audit log of its
+    /// own, or `None` if auditing is not enabled, the time is before the
+    /// [start](../audit/struct.AuditLog.html#method.started) of the log, or the log cannot be
+    /// replayed.
+    pub fn at(&self, time: Timestamp) -> Option<Budget> {
+        self.audit.as_ref()?.at(time)
+    }
+
+    /// Get the audit log of the budget.
+    ///
+    /// # Returns
+    /// The log if auditing is enabled with [`enable_audit`](#method.enable_audit), otherwise `None`.
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit.as_ref()
+    }
+
+    /// Subscribe a callback to the structural changes made to the budget, e.g. to refresh a view
+    /// bound to it.
+    ///
+    /// The callback receives the changes made through the methods of the budget itself, the same
+    /// ones that are recorded in its audit log. Callbacks subscribed to a single group with
+    /// [`BudgetGroup::subscribe`](../budget_group/struct.BudgetGroup.html#method.subscribe) also
+    /// receive the changes made through [`group_mut`](#method.group_mut).
+    ///
+    /// # Parameters
+    /// * `callback` - the function called with every change, after it is made.
+    ///
+    /// # Returns
+    /// The handle needed to [`unsubscribe`](#method.unsubscribe) the callback again.
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use rbp_core::budget::Budget;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::observer::ChangeEvent;
+    /// let mut budget = Budget::new("Home");
+    /// let added = Rc::new(RefCell::new(Vec::new()));
+    /// let names = added.clone();
+    /// budget.subscribe(move |event: &ChangeEvent| if let ChangeEvent::GroupAdded { group, .. } = event {
+    ///     names.borrow_mut().push(group.clone());
+    /// });
+    ///
+    /// budget.add_group(BudgetGroup::new("Housing"));
+    /// assert_eq!(vec!["Housing"], *added.borrow());
+    /// ```
+    pub fn subscribe<F: FnMut(&ChangeEvent) + 'static>(&mut self, callback: F) -> Subscription {
+        self.observers.subscribe(Box::new(callback))
+    }
+
+    /// Unsubscribe a callback from the changes made to the budget.
+    ///
+    /// # Parameters
+    /// * `subscription` - the handle returned when the callback was subscribed.
+    ///
+    /// # Returns
+    /// `true` if the callback was unsubscribed, or `false` if it was not subscribed.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.observers.unsubscribe(subscription)
+    }
+
+    /// Get an enumeration iterator to the groups in the budget.
+    ///
+    /// The index of each group is the one expected by [`remove_group`](#method.remove_group).
+    pub fn enumerate(&self) -> Enumerate<Iter<'_, BudgetGroup>> {
+        self.groups.iter().enumerate()
+    }
+
+    /// Add a group to the end of the budget.
+    ///
+    /// # Parameters
+    /// * `group` - the budget group that should be added to the budget.
+    pub fn add_group(&mut self, group: BudgetGroup) {
+        self.groups.push(group);
+        self.group_added(self.groups.len() - 1);
+    }
+
+    /// Insert a group into the budget at an index, shifting the later groups.
+    ///
+    /// # Parameters
+    /// * `idx` - the index the group gets, which may be one past the last group.
+    /// * `group` - the budget group that should be added to the budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the index is valid, or `Result::Err` if the index is invalid.
+    pub fn insert_group(&mut self, idx: usize, group: BudgetGroup) -> Result<(), InvalidIndex> {
+        if idx > self.groups.len() {
+            return Err(InvalidIndex { });
+        }
+        self.groups.insert(idx, group);
+        self.group_added(idx);
+        Ok(())
+    }
+
+    /// Get a reference to the first group with the given name.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the group to look for.
+    ///
+    /// # Returns
+    /// The group if one exists with the name, otherwise `None`.
+    pub fn group(&self, name: &str) -> Option<&BudgetGroup> {
+        self.group_index(name).map(|idx| &self.groups[idx])
+    }
+
+    /// Get the index of the first group with the given name, e.g. to pass it to
+    /// [`add_item`](#method.add_item) or an [`Edit`](../undo/enum.Edit.html).
+    ///
+    /// # Parameters
+    /// * `name` - the name of the group to look for.
+    ///
+    /// # Returns
+    /// The index if a group exists with the name, otherwise `None`.
+    pub fn group_index(&self, name: &str) -> Option<usize> {
+        self.groups.iter().position(|group| group.name() == name)
+    }
+
+    /// Get a mutable reference to the first group with the given name.
+    ///
+    /// Changes made directly on the group are not observed: subscribers are not notified, the audit
+    /// log does not record them, and so a journal cannot replay them. They also cannot be undone,
+    /// so getting the group clears the undo history. Use the item methods of the budget, e.g.
+    /// [`add_item`](#method.add_item), or [`apply`](#method.apply) for changes that should be
+    /// observed, recorded or undone.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the group to look for.
+    ///
+    /// # Returns
+    /// The group if one exists with the name, otherwise `None`, in which case the budget is left
+    /// unchanged.
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut BudgetGroup> {
+        let idx = self.group_index(name)?;
+        self.history.clear();
+        self.revision += 1;
+        Some(&mut self.groups[idx])
+    }
+
+    /// Remove a group from the budget, based on its index.
+    ///
+    /// # Parameters
+    /// * `idx` - the index of the group to be removed, as discovered using [`enumerate`](#method.enumerate).
+    ///
+    /// # Returns
+    /// `Result::Ok` with the removed group if the index is valid, or `Result::Err` if the index is
+    /// invalid.
+    pub fn remove_group(&mut self, idx: usize) -> Result<BudgetGroup, InvalidIndex> {
+        if idx >= self.groups.len() {
+            return Err(InvalidIndex { });
+        }
+        let group = self.groups.remove(idx);
+        self.changed(Change::GroupRemoved { group: group.name().clone() });
+        Ok(group)
+    }
+
+    /// Give a group a new name, based on its index.
+    ///
+    /// # Parameters
+    /// * `idx` - the index of the group, as discovered using [`enumerate`](#method.enumerate).
+    /// * `name` - the new name of the group.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the index is valid, or `Result::Err` if the index is invalid.
+    pub fn rename_group(&mut self, idx: usize, name: &str) -> Result<(), InvalidIndex> {
+        let group = self.groups.get_mut(idx).ok_or(InvalidIndex { })?;
+        let from = group.name().clone();
+        group.set_name(name);
+        self.changed(Change::GroupRenamed { from, to: name.to_owned() });
+        Ok(())
+    }
+
+    /// Add an item to a group, based on the group's index.
+    ///
+    /// # Parameters
+    /// * `group_idx` - the index of the group, as discovered using [`enumerate`](#method.enumerate).
+    /// * `item` - the item that should be added to the group.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the index is valid, or `Result::Err` if the index is invalid.
+    pub fn add_item(&mut self, group_idx: usize, item: BudgetItem) -> Result<(), InvalidIndex> {
+        let group = self.groups.get_mut(group_idx).ok_or(InvalidIndex { })?;
+        let change = Change::ItemAdded { group: group.name().clone(), item: item.clone() };
+        group.add(item);
+        self.changed(change);
+        Ok(())
+    }
+
+    /// Remove an item from a group, based on the indices of the group and the item.
+    ///
+    /// # Parameters
+    /// * `group_idx` - the index of the group, as discovered using [`enumerate`](#method.enumerate).
+    /// * `item_idx` - the index of the item in the group, as discovered using
+    ///   [`BudgetGroup::enumerate`](../budget_group/struct.BudgetGroup.html#method.enumerate).
+    ///
+    /// # Returns
+    /// `Result::Ok` with the removed item if both indices are valid, or `Result::Err` if either
+    /// index is invalid.
+    pub fn remove_item(&mut self, group_idx: usize, item_idx: usize) -> Result<BudgetItem, InvalidIndex> {
+        let group = self.groups.get_mut(group_idx).ok_or(InvalidIndex { })?;
+        let item = group.enumerate().nth(item_idx).ok_or(InvalidIndex { })?.1.clone();
+        group.remove(item_idx)?;
+        let change = Change::ItemRemoved { group: group.name().clone(), item: item.clone() };
+        self.changed(change);
+        Ok(item)
+    }
+
+    /// Replace an item in a group with another, based on the indices of the group and the item.
+    ///
+    /// # Parameters
+    /// * `group_idx` - the index of the group, as discovered using [`enumerate`](#method.enumerate).
+    /// * `item_idx` - the index of the item in the group, as discovered using
+    ///   [`BudgetGroup::enumerate`](../budget_group/struct.BudgetGroup.html#method.enumerate).
+    /// * `item` - the item taking the place of the old one.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the replaced item if both indices are valid, or `Result::Err` if either
+    /// index is invalid.
+    pub fn update_item(&mut self, group_idx: usize, item_idx: usize, item: BudgetItem) -> Result<BudgetItem, InvalidIndex> {
+        let group = self.groups.get_mut(group_idx).ok_or(InvalidIndex { })?;
+        let to = item.clone();
+        let from = group.replace(item_idx, item)?;
+        let change = Change::ItemUpdated { group: group.name().clone(), from: from.clone(), to };
+        self.changed(change);
+        Ok(from)
+    }
+
+    /// Move an item from one group to another, e.g. when it is dragged to another category.
+    ///
+    /// The item is moved as a whole, including its external references, and the move is reported
+    /// as a single change. Moving an item to its own group changes nothing. To move an item so that
+    /// the move can be undone, apply an [`Edit::MoveItem`](../undo/enum.Edit.html#variant.MoveItem)
+    /// instead.
+    ///
+    /// # Parameters
+    /// * `from_group` - the index of the group holding the item, as discovered using
+    ///   [`enumerate`](#method.enumerate).
+    /// * `item_idx` - the index of the item in the group, as discovered using
+    ///   [`BudgetGroup::enumerate`](../budget_group/struct.BudgetGroup.html#method.enumerate).
+    /// * `to_group` - the index of the group the item should be moved to.
+    ///
+    /// # Returns
+    /// `Result::Ok` if all indices are valid, or `Result::Err` if any index is invalid, in which
+    /// case the budget is unchanged.
+    ///
+    /// ```
+    /// use rbp_core::budget::Budget;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut housing = BudgetGroup::new("Housing");
+    /// housing.add(BudgetItem::with_expense("Parking", 40.0, Period::Every1Month));
+    /// let mut budget = Budget::new("Home");
+    /// budget.add_group(housing);
+    /// budget.add_group(BudgetGroup::new("Car"));
+    ///
+    /// budget.move_item(0, 0, 1).unwrap();
+    /// assert!(budget.group("Housing").unwrap().is_empty());
+    /// assert_eq!(-40.0, budget.group("Car").unwrap().total());
+    /// ```
+    pub fn move_item(&mut self, from_group: usize, item_idx: usize, to_group: usize) -> Result<(), InvalidIndex> {
+        if to_group >= self.groups.len() || self.groups.get(from_group).and_then(|group| group.get(item_idx)).is_none() {
+            return Err(InvalidIndex { });
+        }
+        if from_group == to_group {
+            return Ok(());
+        }
+        let from = &mut self.groups[from_group];
+        let item = from[item_idx].clone();
+        from.remove(item_idx)?;
+        let from = from.name().clone();
+        let to = &mut self.groups[to_group];
+        to.add(item.clone());
+        let change = Change::ItemMoved { from, to: to.name().clone(), item };
+        self.changed(change);
+        Ok(())
+    }
+
+    /// Move an item to another index within its group, which switches the group to
+    /// [manual order](../budget_group/enum.ItemOrder.html#variant.Manual), as
+    /// [`BudgetGroup::move_item`](../budget_group/struct.BudgetGroup.html#method.move_item) does.
+    ///
+    /// # Parameters
+    /// * `group_idx` - the index of the group, as discovered using [`enumerate`](#method.enumerate).
+    /// * `from` - the index of the item to move.
+    /// * `to` - the index the item should end up at.
+    ///
+    /// # Returns
+    /// `Result::Ok` if all indices are valid, or `Result::Err` if any index is invalid, in which
+    /// case the budget is unchanged.
+    pub fn reorder_item(&mut self, group_idx: usize, from: usize, to: usize) -> Result<(), InvalidIndex> {
+        let group = self.groups.get_mut(group_idx).ok_or(InvalidIndex { })?;
+        group.move_item(from, to)?;
+        let change = Change::ItemReordered { group: group.name().clone(), item: group[to].clone(), to };
+        self.changed(change);
+        Ok(())
+    }
+
+    /// Change how a group orders its items, as
+    /// [`BudgetGroup::set_order`](../budget_group/struct.BudgetGroup.html#method.set_order) does.
+    /// Setting the order the group already has changes nothing.
+    ///
+    /// # Parameters
+    /// * `group_idx` - the index of the group, as discovered using [`enumerate`](#method.enumerate).
+    /// * `order` - the new ordering.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the index is valid, or `Result::Err` if the index is invalid.
+    pub fn set_group_order(&mut self, group_idx: usize, order: ItemOrder) -> Result<(), InvalidIndex> {
+        let group = self.groups.get_mut(group_idx).ok_or(InvalidIndex { })?;
+        if group.order() != order {
+            group.set_order(order);
+            let change = Change::OrderChanged { group: group.name().clone(), order };
+            self.changed(change);
+        }
+        Ok(())
+    }
+
+    /// Apply a reversible edit to the budget, so that it can be undone with [`undo`](#method.undo).
+    ///
+    /// Applying an edit discards the edits that were undone, so they can no longer be redone. The
+    /// other methods changing the budget, e.g. [`add_item`](#method.add_item), clear the undo
+    /// history altogether, so frontends offering undo should make all their changes with edits.
+    ///
+    /// # Parameters
+    /// * `edit` - the change to make.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the edit was applied, or `Result::Err` if an index of the edit is invalid.
+    /// An invalid edit leaves the budget and its undo history unchanged.
+    pub fn apply(&mut self, edit: Edit) -> Result<(), InvalidIndex> {
+        let mut history = core::mem::take(&mut self.history);
+        let result = undo::run(self, edit).map(|reverse| history.applied(reverse));
+        self.history = history;
+        result
+    }
+
+    /// Undo the last applied edit that has not been undone yet.
+    ///
+    /// # Returns
+    /// `true` if an edit was undone, or `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let mut history = core::mem::take(&mut self.history);
+        let undone = history.undo(self);
+        self.history = history;
+        undone
+    }
+
+    /// Redo the last undone edit.
+    ///
+    /// # Returns
+    /// `true` if an edit was redone, or `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let mut history = core::mem::take(&mut self.history);
+        let redone = history.redo(self);
+        self.history = history;
+        redone
+    }
+
+    /// Check whether there is an edit to undo, e.g. to enable an undo button.
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Check whether there is an undone edit to redo, e.g. to enable a redo button.
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Find everything that [`delete`](#method.delete) would remove, without changing the budget,
+    /// e.g. to ask the user for confirmation before removing a group with all of its items.
+    ///
+    /// # Parameters
+    /// * `selector` - what to delete.
+    ///
+    /// # Returns
+    /// The groups and items that match the selector.
+    pub fn preview_delete(&self, selector: &Selector) -> DeleteImpact {
+        delete::preview(self, selector)
+    }
+
+    /// Delete every group or item matching a selector. Each removal is made as with
+    /// [`remove_group`](#method.remove_group) and [`remove_item`](#method.remove_item).
+    ///
+    /// # Parameters
+    /// * `selector` - what to delete.
+    ///
+    /// # Returns
+    /// The groups and items that were removed, the same as reported by
+    /// [`preview_delete`](#method.preview_delete) beforehand.
+    pub fn delete(&mut self, selector: &Selector) -> DeleteImpact {
+        delete::delete(self, selector)
+    }
+
+    /// Store a frozen copy of the groups and items of the budget, e.g. at the start of every month,
+    /// to track how the plan evolves. Like the audit log, snapshots are kept in memory only, and
+    /// are not part of the [`document`](../document/index.html) of the budget.
+    ///
+    /// # Parameters
+    /// * `label` - the label to find the snapshot by, e.g. `"January"`. Taking another snapshot
+    ///   with the same label does not replace the earlier one, but hides it from
+    ///   [`find_snapshot`](#method.find_snapshot).
+    pub fn snapshot(&mut self, label: &str) {
+        self.snapshots.push(Snapshot::of(self, label));
+    }
+
+    /// Remove the history the retention policy no longer keeps, so that a long-lived budget does
+    /// not grow without bound: the snapshots are thinned out, and the old events of the audit log
+    /// are folded into its start, as described on
+    /// [`RetentionPolicy`](../retention/struct.RetentionPolicy.html).
+    ///
+    /// The ages are measured with the clock of the audit log, so nothing is removed unless
+    /// auditing is enabled, and snapshots taken before it was are always kept. The events of the
+    /// audit log are kept if they cannot be replayed, e.g. after edits through
+    /// [`group_mut`](#method.group_mut).
+    ///
+    /// # Parameters
+    /// * `policy` - what to keep.
+    pub fn compact(&mut self, policy: &RetentionPolicy) {
+        if let Some(audit) = &mut self.audit {
+            let now = audit.now();
+            policy.thin(&mut self.snapshots, now);
+            audit.compact(policy.audit_cutoff(now));
+        }
+    }
+
+    /// Get the snapshots, in the order they were taken.
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    /// Find the latest snapshot with a label.
+    ///
+    /// # Parameters
+    /// * `label` - the label of the snapshot.
+    ///
+    /// # Returns
+    /// The snapshot, or `None` if no snapshot has the label.
+    pub fn find_snapshot(&self, label: &str) -> Option<&Snapshot> {
+        self.snapshots.iter().rev().find(|snapshot| snapshot.label() == label)
+    }
+
+    /// Compare the group totals of two snapshots, as described on
+    /// [`Snapshot::compare`](../snapshot/struct.Snapshot.html#method.compare).
+    ///
+    /// # Parameters
+    /// * `earlier` - the label of the snapshot to compare from.
+    /// * `later` - the label of the snapshot to compare to.
+    ///
+    /// # Returns
+    /// The change of every group, or `None` if either label has no snapshot.
+    pub fn compare_snapshots(&self, earlier: &str, later: &str) -> Option<Vec<GroupChange>> {
+        Some(self.find_snapshot(earlier)?.compare(self.find_snapshot(later)?))
+    }
+
+    /// Search the names of the groups and items, e.g. for the search box of a frontend. The
+    /// search ignores case, and matches names containing the query.
+    ///
+    /// # Parameters
+    /// * `query` - the text to search for. Surrounding whitespace is ignored.
+    ///
+    /// # Returns
+    /// The matching groups and items, in the order of the budget, with every group followed by
+    /// its matching items.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        search::search(self, |name| search::contains(query, name))
+    }
+
+    /// Search the names of the groups and items like [`search`](#method.search), but tolerating
+    /// typos, as described on `fuzzy::matches`. Only available with the `fuzzy` feature.
+    ///
+    /// # Parameters
+    /// * `query` - the text to search for, e.g. `"Netflx"`.
+    ///
+    /// # Returns
+    /// The matching groups and items, in the order of the budget.
+    #[cfg(feature = "fuzzy")]
+    pub fn search_fuzzy(&self, query: &str) -> Vec<SearchHit> {
+        search::search(self, |name| crate::fuzzy::matches(query, name))
+    }
+
+    /// Branch a what-if scenario off the budget, e.g. to see what a rent increase would do to the
+    /// plan without changing it.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the scenario, e.g. `"Rent increase"`.
+    ///
+    /// # Returns
+    /// The scenario, which borrows the budget and starts out without any overrides.
+    pub fn branch_scenario(&self, name: &str) -> Scenario<'_> {
+        Scenario::new(self, name)
+    }
+
+    /// Compare the budget with another one, e.g. the same budget a month ago, or an import that is
+    /// about to be applied.
+    ///
+    /// # Parameters
+    /// * `other` - the budget to compare with.
+    ///
+    /// # Returns
+    /// The changes that turn this budget into the other one, as described on [`BudgetDiff`].
+    pub fn diff(&self, other: &Budget) -> BudgetDiff {
+        diff::diff(self, other)
+    }
+
+    /// Merge another budget into this one, e.g. the same budget maintained on another device.
+    ///
+    /// The merge is a union: groups and items that only exist in the other budget are added, and
+    /// those that only exist in this budget are kept. Groups are matched by name, and items within
+    /// a group by name and kind, as in [`diff`](#method.diff). Items that exist in both budgets
+    /// with a different amount or period are conflicts, which are resolved by the policy. A
+    /// resolved conflict keeps the external references of both items.
+    ///
+    /// # Parameters
+    /// * `other` - the budget to merge from.
+    /// * `policy` - how every conflict is resolved.
+    ///
+    /// # Returns
+    /// The conflicts that were found and resolved.
+    pub fn merge(&mut self, other: &Budget, policy: MergePolicy) -> Vec<Conflict> {
+        merge::merge(self, other, |_| policy)
+    }
+
+    /// Merge another budget into this one, resolving each conflict separately, e.g. by asking the
+    /// user. Otherwise the same as [`merge`](#method.merge).
+    ///
+    /// # Parameters
+    /// * `other` - the budget to merge from.
+    /// * `resolve` - the function choosing the policy for a conflict.
+    ///
+    /// # Returns
+    /// The conflicts that were found and resolved.
+    pub fn merge_with<F: FnMut(&Conflict) -> MergePolicy>(&mut self, other: &Budget, resolve: F) -> Vec<Conflict> {
+        merge::merge(self, other, resolve)
+    }
+
+    /// Calculate the total budget across all groups, based on a monthly recurring cycle.
+    ///
+    /// The monthly contributions are rounded according to the rounding policy of the budget's
+    /// [defaults](#method.defaults). Sums too large for an `f64` are the largest finite value
+    /// instead, so the total is always finite.
+    ///
+    /// # Returns
+    /// The sum of the totals of every group. Will be a negative number if the budget contains
+    /// more total expenses than income.
+    pub fn total(&self) -> f64 {
+        let rounding = self.defaults.rounding;
+        rounding.round(saturating_sum(self.groups.iter().map(|group| group.rounded_total(rounding))))
+    }
+
+    /// Calculate what the budget contributes over a number of months, e.g. to check whether a
+    /// purchase is affordable over the next 3 months, as described on
+    /// [`BudgetItem::contribution_over`](../budget_item/struct.BudgetItem.html#method.contribution_over).
+    ///
+    /// # Parameters
+    /// * `months` - the number of months.
+    ///
+    /// # Returns
+    /// The range of contributions, from the worst case of every item to the best case of every
+    /// item.
+    ///
+    /// ```
+    /// use rbp_core::budget::Budget;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut home = BudgetGroup::new("Home");
+    /// home.add(BudgetItem::with_income("Salary", 1_000.0, Period::Every1Month));
+    /// home.add(BudgetItem::with_expense("Insurance", 1_500.0, Period::Every12Months));
+    /// let mut budget = Budget::new("Home");
+    /// budget.add_group(home);
+    ///
+    /// // The insurance may or may not be due within the next 3 months.
+    /// let range = budget.contribution_over(3);
+    /// assert_eq!(1_500.0, range.lowest);
+    /// assert_eq!(3_000.0, range.highest);
+    /// ```
+    pub fn contribution_over(&self, months: u16) -> ContributionRange {
+        ContributionRange::sum(self.groups.iter().map(|group| group.contribution_over(months)))
+    }
+
+    /// Summarize the budget in its key figures.
+    ///
+    /// # Returns
+    /// The totals, the savings rate, and the share of the expenses of every group.
+    ///
+    /// ```
+    /// use rbp_core::budget::Budget;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut income = BudgetGroup::new("Income");
+    /// income.add(BudgetItem::with_income("Salary", 1_000.0, Period::Every1Month));
+    /// let mut housing = BudgetGroup::new("Housing");
+    /// housing.add(BudgetItem::with_expense("Rent", 800.0, Period::Every1Month));
+    /// let mut budget = Budget::new("Home");
+    /// budget.add_group(income);
+    /// budget.add_group(housing);
+    ///
+    /// let summary = budget.summary();
+    /// assert_eq!(200.0, summary.net);
+    /// assert_eq!(Some(0.2), summary.savings_rate);
+    /// assert_eq!(("Housing".to_owned(), 1.0), summary.expense_shares[1]);
+    /// ```
+    pub fn summary(&self) -> BudgetSummary {
+        let rounding = self.defaults.rounding;
+        // Every contribution is rounded before it is summed, as in `total`, so the net matches it.
+        let sum = |group: &BudgetGroup, income: bool| -> f64 {
+            saturating_sum(group.iter()
+                .filter(|item| item.is_income() == income)
+                .map(|item| item.rounded_monthly_contribution(rounding)))
+        };
+        let group_expenses: Vec<f64> = self.groups.iter().map(|group| sum(group, false)).collect();
+        let income = rounding.round(saturating_sum(self.groups.iter().map(|group| sum(group, true))));
+        let expenses = rounding.round(saturating_sum(group_expenses.iter().copied()));
+        let net = rounding.round(income + expenses);
+        let savings_rate = if income > 0.0 { Some(net / income) } else { None };
+        let expense_shares = self.groups.iter().zip(group_expenses)
+            .map(|(group, group_expenses)| {
+                let share = if expenses < 0.0 { group_expenses / expenses } else { 0.0 };
+                (group.name().clone(), share)
+            })
+            .collect();
+        BudgetSummary { income, expenses, net, savings_rate, expense_shares }
+    }
+
+    // Bookkeeping after every structural change: bump the revision, forget the undo history, which
+    // no longer matches the budget, notify the subscribers, and record the change in the audit
+    // log, if auditing is enabled. Changes made by undo edits are made with the history taken out
+    // of the budget, so they do not clear it.
+    fn changed(&mut self, change: Change) {
+        self.revision += 1;
+        self.history.clear();
+        self.announce(change);
+    }
+
+    // Notify the subscribers of a change, and record it in the audit log, if auditing is enabled.
+    fn announce(&mut self, change: Change) {
+        self.observers.notify(&change);
+        if let Some(audit) = &mut self.audit {
+            audit.record(change);
+        }
+    }
+
+    // Bookkeeping after a group was added at an index. The order of a manually ordered group, and
+    // the items of the group are announced after the group, as in a diff, so that a journal can
+    // restore them.
+    fn group_added(&mut self, idx: usize) {
+        let group = &self.groups[idx];
+        let name = group.name().clone();
+        let order = group.order();
+        let items: Vec<BudgetItem> = group.iter().cloned().collect();
+        self.changed(Change::GroupAdded { group: name.clone(), index: idx });
+        if order == ItemOrder::Manual {
+            self.announce(Change::OrderChanged { group: name.clone(), order });
+        }
+        for item in items {
+            self.announce(Change::ItemAdded { group: name.clone(), item });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::budget::{Budget, BudgetDefaults, BudgetSummary};
+    use crate::budget_group::{BudgetGroup, ItemOrder};
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::undo::Edit;
+
+    #[test]
+    fn new() {
+        let budget = Budget::new("foo");
+        assert_eq!("foo", budget.name());
+        assert_eq!(0, budget.enumerate().len());
+    }
+
+    #[test]
+    fn defaults() {
+        let mut budget = Budget::new("foo");
+        assert_eq!(&BudgetDefaults::default(), budget.defaults());
+
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), period: Period::Every3Months, ..BudgetDefaults::default() });
+        assert_eq!("€", budget.defaults().currency);
+
+        let item = budget.defaults().expense("Water", 30.0);
+        assert_eq!(Period::Every3Months, item.period());
+        assert_eq!(-10.0, item.monthly_contribution());
+    }
+
+    #[test]
+    fn groups_keep_insertion_order() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("zz"));
+        budget.add_group(BudgetGroup::new("aa"));
+
+        let names: Vec<&String> = budget.enumerate().map(|(_, group)| group.name()).collect();
+        assert_eq!(vec!["zz", "aa"], names);
+    }
+
+    #[test]
+    fn group_by_name() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("Housing"));
+
+        budget.group_mut("Housing").unwrap().add(BudgetItem::with_expense("Rent", 10.0, Period::Every1Month));
+        assert_eq!(-10.0, budget.group("Housing").unwrap().total());
+        assert!(budget.group("Food").is_none());
+
+        budget.add_group(BudgetGroup::new("Food"));
+        budget.add_group(BudgetGroup::new("Housing"));
+        assert_eq!(Some(0), budget.group_index("Housing"));
+        assert_eq!(Some(1), budget.group_index("Food"));
+        assert_eq!(None, budget.group_index("Car"));
+    }
+
+    #[test]
+    fn remove_group() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("bar"));
+
+        assert!(budget.remove_group(1).is_err());
+        assert_eq!("bar", budget.remove_group(0).unwrap().name());
+        assert_eq!(0, budget.enumerate().len());
+    }
+
+    #[test]
+    fn insert_group() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("bb"));
+        budget.insert_group(0, BudgetGroup::new("aa")).unwrap();
+        budget.insert_group(2, BudgetGroup::new("cc")).unwrap();
+
+        assert!(budget.insert_group(4, BudgetGroup::new("dd")).is_err());
+        let names: Vec<&String> = budget.enumerate().map(|(_, group)| group.name()).collect();
+        assert_eq!(vec!["aa", "bb", "cc"], names);
+    }
+
+    #[test]
+    fn rename_group() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("bar"));
+
+        assert!(budget.rename_group(1, "baz").is_err());
+        budget.rename_group(0, "baz").unwrap();
+        assert!(budget.group("baz").is_some());
+    }
+
+    #[test]
+    fn item_changes() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("Housing"));
+
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        assert_eq!(-900.0, budget.total());
+        let old = budget.update_item(0, 0, BudgetItem::with_expense("Rent", 950.0, Period::Every1Month)).unwrap();
+        assert_eq!(900.0, old.amount());
+        assert_eq!(-950.0, budget.total());
+        assert_eq!(950.0, budget.remove_item(0, 0).unwrap().amount());
+        assert_eq!(0.0, budget.total());
+    }
+
+    #[test]
+    fn item_changes_invalid_index() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("Housing"));
+        let rent = || BudgetItem::with_expense("Rent", 900.0, Period::Every1Month);
+
+        assert!(budget.add_item(1, rent()).is_err());
+        assert!(budget.remove_item(0, 0).is_err());
+        assert!(budget.update_item(0, 0, rent()).is_err());
+    }
+
+    #[test]
+    fn move_item() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("Housing"));
+        budget.add_group(BudgetGroup::new("Car"));
+        budget.add_item(0, BudgetItem::with_expense("Parking", 40.0, Period::Every1Month)).unwrap();
+        let log = events.clone();
+        budget.subscribe(move |event| log.borrow_mut().push(format!("{:?}", event)));
+
+        assert!(budget.move_item(0, 1, 1).is_err());
+        assert!(budget.move_item(0, 0, 2).is_err());
+        assert!(budget.move_item(0, 0, 0).is_ok());
+        assert!(events.borrow().is_empty());
+
+        budget.move_item(0, 0, 1).unwrap();
+        assert!(budget.group("Housing").unwrap().is_empty());
+        assert_eq!("Parking", budget.group("Car").unwrap()[0].name());
+        assert_eq!(-40.0, budget.total());
+        let events = events.borrow();
+        assert_eq!(1, events.len());
+        assert!(events[0].starts_with("ItemMoved { from: \"Housing\", to: \"Car\""));
+    }
+
+    #[test]
+    fn subscribe() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut budget = Budget::new("foo");
+        let log = events.clone();
+        let subscription = budget.subscribe(move |event| log.borrow_mut().push(format!("{:?}", event)));
+
+        budget.add_group(BudgetGroup::new("bar"));
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        budget.group_mut("bar").unwrap().add(BudgetItem::with_expense("Water", 30.0, Period::Every1Month));
+        assert!(budget.apply(Edit::RemoveGroup(0)).is_ok());
+        assert!(budget.undo());
+        assert!(budget.unsubscribe(subscription));
+        budget.add_group(BudgetGroup::new("baz"));
+
+        let events = events.borrow();
+        assert_eq!(6, events.len());
+        assert_eq!("GroupAdded { group: \"bar\", index: 0 }", events[0]);
+        assert!(events[1].starts_with("ItemAdded { group: \"bar\""));
+        assert_eq!("GroupRemoved { group: \"bar\" }", events[2]);
+
+        // Undoing the removal adds the group back with its items, including the unobserved one.
+        assert_eq!("GroupAdded { group: \"bar\", index: 0 }", events[3]);
+        assert!(events[4].contains("Rent"));
+        assert!(events[5].contains("Water"));
+    }
+
+    #[test]
+    fn reorder_events() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut budget = Budget::new("foo");
+        let log = events.clone();
+        budget.subscribe(move |event| log.borrow_mut().push(format!("{:?}", event)));
+
+        let mut group = BudgetGroup::new("bar");
+        group.set_order(ItemOrder::Manual);
+        group.add(BudgetItem::with_expense("Water", 30.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        budget.add_group(group);
+        budget.reorder_item(0, 1, 0).unwrap();
+        assert!(budget.reorder_item(0, 2, 0).is_err());
+        budget.set_group_order(0, ItemOrder::Manual).unwrap();
+        budget.set_group_order(0, ItemOrder::Sorted).unwrap();
+
+        let events = events.borrow();
+        assert_eq!(6, events.len());
+        assert_eq!("OrderChanged { group: \"bar\", order: Manual }", events[1]);
+        assert!(events[2].contains("Water"));
+        assert!(events[4].starts_with("ItemReordered { group: \"bar\", item: BudgetItem { name: \"Rent\""));
+        assert_eq!("OrderChanged { group: \"bar\", order: Sorted }", events[5]);
+    }
+
+    #[test]
+    fn revisions() {
+        let mut budget = Budget::new("foo");
+        assert_eq!(0, budget.revision());
+
+        budget.add_group(BudgetGroup::new("bar"));
+        budget.set_defaults(BudgetDefaults::default());
+        assert!(budget.remove_group(1).is_err());
+        assert_eq!(2, budget.revision());
+
+        budget.apply(Edit::RenameGroup(0, "baz".to_owned())).unwrap();
+        budget.undo();
+        budget.group_mut("bar");
+        assert_eq!(5, budget.revision());
+
+        // Looking up a missing group changes nothing, and keeps the undo history.
+        budget.apply(Edit::RenameGroup(0, "baz".to_owned())).unwrap();
+        assert!(budget.group_mut("qux").is_none());
+        assert_eq!(6, budget.revision());
+        assert!(budget.can_undo());
+    }
+
+    #[test]
+    fn dirty_until_marked_clean() {
+        let mut budget = Budget::new("foo");
+        budget.add_group(BudgetGroup::new("bar"));
+        budget.mark_clean();
+        assert!(!budget.is_dirty());
+
+        budget.apply(Edit::RenameGroup(0, "baz".to_owned())).unwrap();
+        assert!(budget.is_dirty());
+        budget.undo();
+        assert!(budget.is_dirty());
+        budget.mark_clean();
+        assert!(!budget.is_dirty());
+    }
+
+    #[test]
+    fn total() {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 100.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Insurance", 120.0, Period::Every12Months));
+
+        let mut budget = Budget::new("foo");
+        budget.add_group(income);
+        budget.add_group(housing);
+
+        assert_eq!(90.0, budget.total());
+    }
+
+    #[test]
+    fn huge_totals_saturate() {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", f64::MAX, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Water", f64::MAX, Period::Every1Month));
+        let mut car = BudgetGroup::new("Car");
+        car.add(BudgetItem::with_expense("Fuel", f64::MAX, Period::Every1Month));
+        let mut budget = Budget::new("foo");
+        budget.add_group(housing);
+        budget.add_group(car);
+
+        assert_eq!(-f64::MAX, budget.total());
+        let summary = budget.summary();
+        assert_eq!(-f64::MAX, summary.expenses);
+        assert_eq!(budget.total(), summary.net);
+        assert!(summary.expense_shares.iter().all(|(_, share)| share.is_finite()));
+        assert_eq!(-f64::MAX, budget.contribution_over(3).lowest);
+    }
+
+    #[test]
+    fn rounded_total() {
+        let mut water = BudgetGroup::new("Water");
+        water.add(BudgetItem::with_expense("Water", 100.0, Period::Every3Months));
+        water.add(BudgetItem::with_expense("Sewage", 0.25, Period::Every2Months));
+        let mut budget = Budget::new("foo");
+        budget.add_group(water);
+        assert_eq!(-100.0 / 3.0 - 0.125, budget.total());
+
+        for (rounding, total) in &[(RoundingPolicy::HalfUp, -33.46), (RoundingPolicy::HalfEven, -33.45)] {
+            budget.set_defaults(BudgetDefaults { rounding: *rounding, ..BudgetDefaults::default() });
+            assert_eq!(*total, budget.total());
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let mut budget = Budget::new("foo");
+        assert_eq!(BudgetSummary { income: 0.0, expenses: 0.0, net: 0.0, savings_rate: None, expense_shares: vec![] }, budget.summary());
+
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 100.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 60.0, Period::Every1Month));
+        housing.add(BudgetItem::with_income("Room", 20.0, Period::Every1Month));
+        let mut food = BudgetGroup::new("Food");
+        food.add(BudgetItem::with_expense("Groceries", 20.0, Period::Every1Month));
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget.add_group(food);
+
+        let summary = budget.summary();
+        assert_eq!(120.0, summary.income);
+        assert_eq!(-80.0, summary.expenses);
+        assert_eq!(40.0, summary.net);
+        assert_eq!(Some(40.0 / 120.0), summary.savings_rate);
+        let shares: Vec<f64> = summary.expense_shares.iter().map(|(_, share)| *share).collect();
+        assert_eq!(vec![0.0, 0.75, 0.25], shares);
+        assert_eq!("Food", summary.expense_shares[2].0);
+        assert_eq!(budget.total(), summary.net);
+
+        budget.add_item(2, BudgetItem::with_expense("Lunch", 70.0, Period::Every3Months)).unwrap();
+        budget.add_item(2, BudgetItem::with_expense("Snacks", 70.0, Period::Every3Months)).unwrap();
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+        let summary = budget.summary();
+        assert_eq!(-126.66, summary.expenses);
+        assert_eq!(-6.66, summary.net);
+        assert_eq!(budget.total(), summary.net);
+    }
+}
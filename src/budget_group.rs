@@ -1,17 +1,127 @@
-use std::slice::Iter;
-use std::iter::Enumerate;
-use crate::budget_item::BudgetItem;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Deref, DerefMut, Index};
+use core::slice::{Iter, IterMut};
+use core::iter::{Enumerate, FromIterator};
+use crate::audit::Change;
+use crate::budget_item::{saturate, saturating_sum, BudgetItem, ContributionRange, ItemId, Period, RoundingPolicy};
+use crate::format::{FormatOptions, Formatted};
+use crate::item_index::ItemIndex;
+use crate::merge::{self, MergePolicy};
+use crate::observer::{ChangeEvent, Observers, Subscription};
+
+/// The order of the items in a view returned by
+/// [`BudgetGroup::sorted_by`](struct.BudgetGroup.html#method.sorted_by). Items that are equal by the
+/// key are ordered by name, then period, then kind, whether or not the group is ordered manually.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum SortKey {
+    /// By name, then period, then kind, which is the order of an [`ItemOrder::Sorted`] group.
+    Name,
+    /// By amount, smallest first, regardless of the period and kind.
+    Amount,
+    /// By monthly contribution, i.e. the largest expense first and the largest income last.
+    MonthlyContribution,
+    /// Income before expenses, and by name within each.
+    KindThenName,
+    /// By period, shortest first.
+    Period,
+}
+
+/// How a group orders its items.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum ItemOrder {
+    /// The items are kept sorted by name, then period, then kind. This is the default.
+    Sorted,
+    /// The items are kept in the order they were added, and can be rearranged with
+    /// [`BudgetGroup::move_item`](struct.BudgetGroup.html#method.move_item).
+    Manual,
+}
+
+impl ItemOrder {
+
+    // The single word name of the order, as used by journals and payloads.
+    pub(crate) fn keyword(self) -> &'static str {
+        match self {
+            ItemOrder::Sorted => "sorted",
+            ItemOrder::Manual => "manual",
+        }
+    }
+
+    // The order with a single word name, as returned by `keyword`.
+    pub(crate) fn from_keyword(keyword: &str) -> Option<ItemOrder> {
+        [ItemOrder::Sorted, ItemOrder::Manual].iter().copied().find(|order| order.keyword() == keyword)
+    }
+}
+
+/// How [`BudgetGroup::dedup`](struct.BudgetGroup.html#method.dedup) resolves duplicate items.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum DedupPolicy {
+    /// Keep the first of the duplicates, and drop the others.
+    KeepFirst,
+    /// Keep the first of the duplicates, with the amounts of the others added to it. Duplicates
+    /// whose amounts are too large to add up are all kept.
+    SumAmounts,
+}
 
 /// An ordered collection of budget items gathered under a common name.
 ///
 /// Note that the group does not impose a unique restriction on its entries, so there is no checks
-/// for duplicate entries.
+/// for duplicate entries. They can be found with [`find_duplicates`](#method.find_duplicates).
+///
+/// Every item added to the group is given an [`ItemId`], which stays with the item while the group
+/// is sorted or reordered. Items can be looked up by identifier and by name without scanning the
+/// group.
 pub struct BudgetGroup {
     name: String,
-    items: Vec<BudgetItem>
+    items: Vec<BudgetItem>,
+    order: ItemOrder,
+    // The positions of the items by identifier and name, kept up to date by every change to the
+    // items, and the last identifier given to an item.
+    index: ItemIndex,
+    last_id: u64,
+    // The running sums of the monthly contributions of the income and expense items, kept up to
+    // date by every change to the items, so totals do not have to visit them.
+    income: f64,
+    expenses: f64,
+    observers: Observers
+}
+
+/// The counts and monthly subtotals of the income and expense items of a group, returned by
+/// [`BudgetGroup::summary`](struct.BudgetGroup.html#method.summary), e.g. for a line like
+/// "12 expenses totalling €2,340".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroupSummary {
+    /// The number of income items.
+    pub income_count: usize,
+    /// The monthly contribution of the income items.
+    pub income_total: f64,
+    /// The number of expense items.
+    pub expense_count: usize,
+    /// The monthly contribution of the expense items, which is negative.
+    pub expense_total: f64,
+}
+
+/// Mutable access to the items of a group, returned by
+/// [`BudgetGroup::iter_mut`](struct.BudgetGroup.html#method.iter_mut).
+///
+/// The guard dereferences to the slice of items. When it is dropped, the group is sorted again,
+/// its totals are recomputed and its subscribers are notified of the changed items, so the group
+/// stays consistent whatever was changed.
+pub struct ItemsMut<'a> {
+    group: &'a mut BudgetGroup,
+    // The items before any changes, to notify the subscribers of the changed items. Only kept if
+    // there are any subscribers.
+    before: Vec<BudgetItem>,
 }
 
 /// Error thrown when trying to index the group of budget items with an invalid index.
+#[derive(Debug)]
 pub struct InvalidIndex { }
 
 impl BudgetGroup {
@@ -23,42 +133,473 @@ impl BudgetGroup {
     pub fn new(name: &str) -> BudgetGroup {
         BudgetGroup {
             name: name.to_owned(),
-            items: Vec::new()
+            items: Vec::new(),
+            order: ItemOrder::Sorted,
+            index: ItemIndex::default(),
+            last_id: 0,
+            income: 0.0,
+            expenses: 0.0,
+            observers: Observers::default()
         }
     }
 
+    /// Create a new budget group with a name, with room for a number of items, to avoid
+    /// reallocations when adding many.
+    ///
+    /// # Parameters
+    /// * `name` - the desired name of the group.
+    /// * `capacity` - the number of items the group can hold without reallocating.
+    pub fn with_capacity(name: &str, capacity: usize) -> BudgetGroup {
+        let mut group = BudgetGroup::new(name);
+        group.items.reserve_exact(capacity);
+        group
+    }
+
+    /// Create a new budget group with a name, holding some items.
+    ///
+    /// # Parameters
+    /// * `name` - the desired name of the group.
+    /// * `items` - the budget items of the group, which are sorted once.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let items = vec![
+    ///     BudgetItem::with_expense("Rent", 900.0, Period::Every1Month),
+    ///     BudgetItem::with_income("Rent out", 300.0, Period::Every1Month),
+    /// ];
+    /// let group = BudgetGroup::with_items("Housing", items.into_iter().filter(|item| !item.is_income()));
+    /// assert_eq!(-900.0, group.total());
+    /// ```
+    pub fn with_items<I: IntoIterator<Item = BudgetItem>>(name: &str, items: I) -> BudgetGroup {
+        let mut group = BudgetGroup::new(name);
+        group.add_all(items);
+        group
+    }
+
     /// Get a reference to the group's name.
     pub fn name(&self) -> &String {
         &self.name
     }
 
+    /// Give the group a new name.
+    ///
+    /// # Parameters
+    /// * `name` - the new name of the group.
+    pub fn set_name(&mut self, name: &str) {
+        let from = core::mem::replace(&mut self.name, name.to_owned());
+        self.notify(|_| Change::GroupRenamed { from, to: name.to_owned() });
+    }
+
+    /// Get how the group orders its items.
+    pub fn order(&self) -> ItemOrder {
+        self.order
+    }
+
+    /// Change how the group orders its items. Switching to [`ItemOrder::Sorted`] sorts the items;
+    /// switching to [`ItemOrder::Manual`] keeps them in their current order.
+    ///
+    /// # Parameters
+    /// * `order` - the new ordering.
+    pub fn set_order(&mut self, order: ItemOrder) {
+        if self.order == order {
+            return;
+        }
+        self.order = order;
+        self.sort();
+        self.notify(|group| Change::OrderChanged { group, order });
+    }
+
+    /// Move an item to another index, shifting the items in between, e.g. for drag-to-reorder.
+    /// Moving an item switches the group to [`ItemOrder::Manual`].
+    ///
+    /// # Parameters
+    /// * `from` - the index of the item to move, as discovered using [`enumerate`](#method.enumerate).
+    /// * `to` - the index the item should end up at.
+    ///
+    /// # Returns
+    /// `Result::Ok` if both indices are valid, or `Result::Err` if either index is invalid.
+    pub fn move_item(&mut self, from: usize, to: usize) -> Result<(), InvalidIndex> {
+        if from >= self.items.len() || to >= self.items.len() {
+            return Err(InvalidIndex { });
+        }
+        self.order = ItemOrder::Manual;
+        let item = self.take(from);
+        self.index.inserted(to, &item);
+        self.items.insert(to, item);
+        let item = self.items[to].clone();
+        self.notify(|group| Change::ItemReordered { group, item, to });
+        Ok(())
+    }
+
+    /// Move an item one place towards the start of the group, as with [`move_item`](#method.move_item).
+    ///
+    /// # Parameters
+    /// * `idx` - the index of the item to move.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the index is valid and the item is not first already, or `Result::Err`
+    /// otherwise.
+    pub fn move_up(&mut self, idx: usize) -> Result<(), InvalidIndex> {
+        self.move_item(idx, idx.checked_sub(1).ok_or(InvalidIndex { })?)
+    }
+
+    /// Move an item one place towards the end of the group, as with [`move_item`](#method.move_item).
+    ///
+    /// # Parameters
+    /// * `idx` - the index of the item to move.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the index is valid and the item is not last already, or `Result::Err`
+    /// otherwise.
+    pub fn move_down(&mut self, idx: usize) -> Result<(), InvalidIndex> {
+        self.move_item(idx, idx.checked_add(1).ok_or(InvalidIndex { })?)
+    }
+
+    /// Subscribe a callback to the changes made to the group: items being added, removed,
+    /// replaced, or moved within the group, the group being renamed, and its order changing.
+    ///
+    /// # Parameters
+    /// * `callback` - the function called with every change, after it is made.
+    ///
+    /// # Returns
+    /// The handle needed to [`unsubscribe`](#method.unsubscribe) the callback again.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Housing");
+    /// let changes = Rc::new(Cell::new(0));
+    /// let counter = changes.clone();
+    /// group.subscribe(move |_| counter.set(counter.get() + 1));
+    ///
+    /// group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+    /// assert_eq!(1, changes.get());
+    /// ```
+    pub fn subscribe<F: FnMut(&ChangeEvent) + 'static>(&mut self, callback: F) -> Subscription {
+        self.observers.subscribe(Box::new(callback))
+    }
+
+    /// Unsubscribe a callback from the changes made to the group.
+    ///
+    /// # Parameters
+    /// * `subscription` - the handle returned when the callback was subscribed.
+    ///
+    /// # Returns
+    /// `true` if the callback was unsubscribed, or `false` if it was not subscribed.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.observers.unsubscribe(subscription)
+    }
+
     /// Get an enumeration iterator to the items in the group.
     ///
     /// This is useful for two reasons:
     /// 1. To iterate over all the elements in the group, since the underlying container is not
-    /// exposed through the public API.
+    ///    exposed through the public API.
     /// 2. To index the elements as they are iterated. This is needed for removing elements using
-    /// [`remove`](#method.remove), as the group is ordered.
-    pub fn enumerate(&self) -> Enumerate<Iter<BudgetItem>> {
+    ///    [`remove`](#method.remove), as the group is ordered.
+    pub fn enumerate(&self) -> Enumerate<Iter<'_, BudgetItem>> {
         self.items.iter().enumerate()
     }
 
+    /// Get the number of items in the group.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check whether the group has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Make room for at least a number of additional items, to avoid reallocations when adding
+    /// them.
+    ///
+    /// # Parameters
+    /// * `additional` - the number of items that are about to be added.
+    pub fn reserve(&mut self, additional: usize) {
+        self.items.reserve(additional);
+    }
+
+    /// Get an item based on its index, e.g. for a row of a table.
+    ///
+    /// # Parameters
+    /// * `idx` - the index of the item, as discovered using [`enumerate`](#method.enumerate).
+    ///
+    /// # Returns
+    /// The item, or `None` if the index is invalid.
+    pub fn get(&self, idx: usize) -> Option<&BudgetItem> {
+        self.items.get(idx)
+    }
+
+    /// Get an item based on its identifier, which unlike its index does not change when the group
+    /// is sorted or reordered.
+    ///
+    /// # Parameters
+    /// * `id` - the identifier of the item, as returned by [`BudgetItem::id`].
+    ///
+    /// # Returns
+    /// The item, or `None` if the group holds no item with the identifier.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Housing");
+    /// group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+    /// let id = group[0].id().unwrap();
+    ///
+    /// group.add(BudgetItem::with_expense("Insurance", 20.0, Period::Every1Month));
+    /// assert_eq!("Rent", group.get_by_id(id).unwrap().name());
+    /// ```
+    pub fn get_by_id(&self, id: ItemId) -> Option<&BudgetItem> {
+        self.index_of(id).map(|idx| &self.items[idx])
+    }
+
+    /// Get the index of an item based on its identifier, e.g. to pass it to the methods taking
+    /// indices.
+    ///
+    /// # Parameters
+    /// * `id` - the identifier of the item, as returned by [`BudgetItem::id`].
+    ///
+    /// # Returns
+    /// The index, or `None` if the group holds no item with the identifier.
+    pub fn index_of(&self, id: ItemId) -> Option<usize> {
+        self.index.position(id)
+    }
+
+    /// Display the group with options for its amounts, e.g. a currency symbol. The `Display`
+    /// implementation of the group uses the default options.
+    ///
+    /// # Parameters
+    /// * `options` - how the amounts are formatted.
+    ///
+    /// # Returns
+    /// The group, ready to be written as described on
+    /// [`Formatted`](../format/struct.Formatted.html).
+    pub fn display<'a>(&'a self, options: &'a FormatOptions) -> Formatted<'a, BudgetGroup> {
+        Formatted::new(self, options)
+    }
+
+    /// Get an iterator to the items in the group, in the order of the group.
+    pub fn iter(&self) -> Iter<'_, BudgetItem> {
+        self.items.iter()
+    }
+
+    /// Get mutable access to the items in the group, e.g. to update their external references.
+    ///
+    /// # Returns
+    /// A guard dereferencing to the items, which sorts the group again and updates its totals when
+    /// dropped.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Housing");
+    /// group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+    ///
+    /// for item in &mut group.iter_mut() {
+    ///     *item = BudgetItem::with_expense(item.name(), 950.0, item.period());
+    /// }
+    /// assert_eq!(-950.0, group.total());
+    /// ```
+    pub fn iter_mut(&mut self) -> ItemsMut<'_> {
+        let before = if self.observers.is_empty() { Vec::new() } else { self.items.clone() };
+        ItemsMut { group: self, before }
+    }
+
+    /// Find the first item with a name. Names are matched exactly, including case.
+    ///
+    /// The items are looked up in the index of the group, so the lookup stays fast in large
+    /// groups.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the item.
+    ///
+    /// # Returns
+    /// The item, or `None` if the group has no item with the name.
+    pub fn find(&self, name: &str) -> Option<&BudgetItem> {
+        self.index.positions(name).first().map(|idx| &self.items[*idx])
+    }
+
+    /// Find all items with a name, as a group may hold several, e.g. an income and an expense.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the items.
+    ///
+    /// # Returns
+    /// The items in the order of [`enumerate`](#method.enumerate), which is empty if the group has
+    /// no item with the name.
+    pub fn find_all(&self, name: &str) -> Vec<&BudgetItem> {
+        self.index.positions(name).iter().map(|idx| &self.items[*idx]).collect()
+    }
+
+    /// Get an iterator over the items accepted by a predicate, without copying them.
+    ///
+    /// # Parameters
+    /// * `predicate` - the function deciding whether an item is included.
+    ///
+    /// # Returns
+    /// The iterator, in the order of [`enumerate`](#method.enumerate).
+    pub fn iter_filtered<P: FnMut(&BudgetItem) -> bool>(&self, mut predicate: P) -> impl Iterator<Item = &BudgetItem> {
+        self.items.iter().filter(move |item| predicate(item))
+    }
+
+    /// Summarize the income and expense items of the group.
+    ///
+    /// # Returns
+    /// The counts and monthly subtotals of each kind.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Housing");
+    /// group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+    /// group.add(BudgetItem::with_expense("Insurance", 1_200.0, Period::Every12Months));
+    /// group.add(BudgetItem::with_income("Room", 300.0, Period::Every1Month));
+    ///
+    /// let summary = group.summary();
+    /// assert_eq!(2, summary.expense_count);
+    /// assert_eq!(-1_000.0, summary.expense_total);
+    /// ```
+    pub fn summary(&self) -> GroupSummary {
+        let income_count = self.incomes().count();
+        GroupSummary {
+            income_count,
+            income_total: self.income_total(),
+            expense_count: self.items.len() - income_count,
+            expense_total: self.expense_total(),
+        }
+    }
+
+    /// Get an iterator over the income items of the group.
+    pub fn incomes(&self) -> impl Iterator<Item = &BudgetItem> {
+        self.iter_filtered(|item| item.is_income())
+    }
+
+    /// Get an iterator over the expense items of the group.
+    pub fn expenses(&self) -> impl Iterator<Item = &BudgetItem> {
+        self.iter_filtered(|item| !item.is_income())
+    }
+
+    /// Get an iterator over the items of the group with a period.
+    ///
+    /// # Parameters
+    /// * `period` - the period of the items.
+    pub fn with_period(&self, period: Period) -> impl Iterator<Item = &BudgetItem> {
+        self.iter_filtered(move |item| item.period() == period)
+    }
+
     /// Add a budget item to the group.
     ///
-    /// Since the group is ordered, adding an item to will trigger a re-ordering of the items in
-    /// the group.
+    /// In a sorted group, adding an item will trigger a re-ordering of the items in the group. In
+    /// a manually ordered group, the item is added to the end.
     ///
     /// # Parameters
-    /// * `item` - the budget item that should be added to the group.
-    pub fn add(&mut self, item: BudgetItem) {
-        self.items.push(item);
-        self.items.sort_unstable();
+    /// * `item` - the budget item that should be added to the group, which is given a new
+    ///   identifier.
+    pub fn add(&mut self, mut item: BudgetItem) {
+        self.assign_id(&mut item);
+        let added = if self.observers.is_empty() { None } else { Some(item.clone()) };
+        self.count(&item, 1.0);
+        self.insert(item);
+        if let Some(item) = added {
+            self.notify(|group| Change::ItemAdded { group, item });
+        }
     }
 
-    /// Remove an item from the group, based on its index.
+    /// Add several budget items to the group at once.
+    ///
+    /// In a sorted group, the items are appended and the group is sorted once, which is much faster
+    /// than [`add`](#method.add) in a loop when loading or importing many items. In a manually
+    /// ordered group, the items are added to the end in the order given.
     ///
-    /// As the budget items do not contain a notion of identity, the removal has to be done based
-    /// on the item's position in the group.
+    /// # Parameters
+    /// * `items` - the budget items that should be added to the group.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Housing");
+    /// group.add_all(vec![
+    ///     BudgetItem::with_expense("Rent", 900.0, Period::Every1Month),
+    ///     BudgetItem::with_expense("Insurance", 240.0, Period::Every12Months),
+    /// ]);
+    /// assert_eq!("Insurance", group.enumerate().next().unwrap().1.name());
+    /// assert_eq!(-920.0, group.total());
+    /// ```
+    pub fn add_all<I: IntoIterator<Item = BudgetItem>>(&mut self, items: I) {
+        let items = items.into_iter();
+        self.items.reserve(items.size_hint().0);
+        let mut added = Vec::new();
+        for mut item in items {
+            self.assign_id(&mut item);
+            if !self.observers.is_empty() {
+                added.push(item.clone());
+            }
+            self.count(&item, 1.0);
+            self.items.push(item);
+        }
+        self.sort();
+        for item in added {
+            self.notify(|group| Change::ItemAdded { group, item });
+        }
+    }
+
+    /// Merge another group into this one, e.g. when consolidating two categories. The items of the
+    /// other group are added, keeping the order of this group.
+    ///
+    /// # Parameters
+    /// * `other` - the group to merge, whose name and subscribers are dropped.
+    /// * `policy` - how an item of the other group with the same name and kind as an item of this
+    ///   group is resolved, or `None` to keep both.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// use rbp_core::merge::MergePolicy;
+    /// let mut utilities = BudgetGroup::new("Utilities");
+    /// utilities.add(BudgetItem::with_expense("Electricity", 60.0, Period::Every1Month));
+    /// let mut household = BudgetGroup::new("Household");
+    /// household.add(BudgetItem::with_expense("Electricity", 30.0, Period::Every1Month));
+    /// household.add(BudgetItem::with_expense("Cleaning", 20.0, Period::Every1Month));
+    ///
+    /// utilities.merge(household, Some(MergePolicy::SumAmounts));
+    /// assert_eq!(2, utilities.len());
+    /// assert_eq!(90.0, utilities.find("Electricity").unwrap().amount());
+    /// ```
+    pub fn merge(&mut self, other: BudgetGroup, policy: Option<MergePolicy>) {
+        let policy = match policy {
+            Some(policy) => policy,
+            None => return self.add_all(other),
+        };
+        for item in other {
+            let existing = self.items.iter()
+                .position(|mine| mine.name() == item.name() && mine.is_income() == item.is_income());
+            let idx = match existing {
+                Some(idx) => idx,
+                None => {
+                    self.add(item);
+                    continue;
+                }
+            };
+            let merged = match policy {
+                MergePolicy::KeepMine => continue,
+                MergePolicy::KeepTheirs => merge::with_refs(item, &self.items[idx]),
+                MergePolicy::SumAmounts => match merge::sum(&self.items[idx], &item) {
+                    Some(summed) => merge::with_refs(summed, &item),
+                    // Amounts too large to add up leave the item of this group as it is.
+                    None => continue,
+                },
+            };
+            // The index was just found, so it is valid.
+            let _ = self.replace(idx, merged);
+        }
+    }
+
+    /// Remove an item from the group, based on its index. Use
+    /// [`remove_by_id`](#method.remove_by_id) to remove an item based on its identifier.
     ///
     /// # Parameters
     /// * `idx` - the index of the item to be removed, as discovered using [`enumerate`](#method.enumerate).
@@ -66,29 +607,546 @@ impl BudgetGroup {
     /// # Returns
     /// `Result::Ok()` if the index is valid and an item was therefore removed, or `Result::Err` if
     /// the index is invalid.
-    pub fn remove(&mut self, idx: usize) -> Result<(), ()> {
-        if idx > self.items.len() {
-            return Err(());
+    pub fn remove(&mut self, idx: usize) -> Result<(), InvalidIndex> {
+        if idx >= self.items.len() {
+            return Err(InvalidIndex { });
         }
-        self.items.remove(idx);
-        self.items.sort_unstable();
+        let item = self.take(idx);
+        self.count(&item, -1.0);
+        self.notify(|group| Change::ItemRemoved { group, item });
         Ok(())
     }
 
+    /// Remove an item from the group, based on its identifier.
+    ///
+    /// # Parameters
+    /// * `id` - the identifier of the item to be removed, as returned by [`BudgetItem::id`].
+    ///
+    /// # Returns
+    /// `Result::Ok()` if the group holds an item with the identifier, which was therefore removed,
+    /// or `Result::Err` if it does not.
+    pub fn remove_by_id(&mut self, id: ItemId) -> Result<(), InvalidIndex> {
+        self.remove(self.index_of(id).ok_or(InvalidIndex { })?)
+    }
+
+    /// Find the items that are duplicates of each other, i.e. have the same name, period and kind,
+    /// e.g. to warn about a bill that was entered twice.
+    ///
+    /// # Returns
+    /// The clusters of duplicates, as indices in the order of [`enumerate`](#method.enumerate),
+    /// ordered by their first index. Items without duplicates are not included.
+    pub fn find_duplicates(&self) -> Vec<Vec<usize>> {
+        let mut indices: Vec<usize> = (0..self.items.len()).collect();
+        // The sort is stable, so the indices of equal items stay in ascending order.
+        indices.sort_by(|a, b| self.items[*a].cmp(&self.items[*b]));
+        let mut clusters: Vec<Vec<usize>> = indices
+            .chunk_by(|a, b| self.items[*a] == self.items[*b])
+            .filter(|cluster| cluster.len() > 1)
+            .map(|cluster| cluster.to_vec())
+            .collect();
+        clusters.sort_unstable_by_key(|cluster| cluster[0]);
+        clusters
+    }
+
+    /// Remove the duplicates found by [`find_duplicates`](#method.find_duplicates), keeping the
+    /// first item of each cluster.
+    ///
+    /// # Parameters
+    /// * `policy` - whether the amounts of the removed duplicates are added to the kept item.
+    ///
+    /// # Returns
+    /// The removed items, in the order they had in the group.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::{BudgetGroup, DedupPolicy};
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Utilities");
+    /// group.add(BudgetItem::with_expense("Water", 30.0, Period::Every1Month));
+    /// group.add(BudgetItem::with_expense("Water", 30.0, Period::Every1Month));
+    /// assert_eq!(vec![vec![0, 1]], group.find_duplicates());
+    ///
+    /// group.dedup(DedupPolicy::KeepFirst);
+    /// assert_eq!(-30.0, group.total());
+    /// ```
+    pub fn dedup(&mut self, policy: DedupPolicy) -> Vec<BudgetItem> {
+        let clusters = self.find_duplicates();
+        let mut duplicate = vec![false; self.items.len()];
+        let mut kept = Vec::new();
+        for cluster in &clusters {
+            if policy == DedupPolicy::SumAmounts {
+                let first = self.items[cluster[0]].clone();
+                let summed = cluster[1..].iter().try_fold(first, |summed, idx| {
+                    let other = &self.items[*idx];
+                    merge::sum(&summed, other).map(|summed| merge::with_refs(summed, other))
+                });
+                // Duplicates whose amounts are too large to add up are all kept.
+                let summed = match summed {
+                    Some(summed) => summed,
+                    None => continue,
+                };
+                // Only duplicates before the kept item are removed, which shift it down. Those
+                // belong to the clusters before this one, which are already marked.
+                let shift = duplicate[..cluster[0]].iter().filter(|duplicate| **duplicate).count();
+                kept.push((cluster[0] - shift, summed));
+            }
+            for idx in &cluster[1..] {
+                duplicate[*idx] = true;
+            }
+        }
+        // The predicate is called for the items in order.
+        let mut idx = 0;
+        let removed = self.remove_if(|_| {
+            idx += 1;
+            duplicate[idx - 1]
+        });
+        for (idx, summed) in kept {
+            // The index is valid, and the summed item sorts at the same place, as only its amount
+            // differs.
+            let _ = self.replace(idx, summed);
+        }
+        removed
+    }
+
+    /// Keep only the items matching a predicate, removing all others in one pass.
+    ///
+    /// # Parameters
+    /// * `keep` - the predicate returning `true` for the items to keep.
+    pub fn retain<P: FnMut(&BudgetItem) -> bool>(&mut self, mut keep: P) {
+        self.remove_if(|item| !keep(item));
+    }
+
+    /// Remove all items matching a predicate in one pass, instead of removing them one index at a
+    /// time. The remaining items keep their order.
+    ///
+    /// # Parameters
+    /// * `predicate` - the predicate returning `true` for the items to remove.
+    ///
+    /// # Returns
+    /// The removed items, in the order they had in the group.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut group = BudgetGroup::new("Subscriptions");
+    /// group.add(BudgetItem::with_expense("Music", 10.0, Period::Every1Month));
+    /// group.add(BudgetItem::with_expense("Newspaper", 120.0, Period::Every12Months));
+    /// group.add(BudgetItem::with_expense("Video", 12.0, Period::Every1Month));
+    ///
+    /// let removed = group.remove_if(|item| item.period() == Period::Every1Month);
+    /// assert_eq!(2, removed.len());
+    /// assert_eq!(-10.0, group.total());
+    /// ```
+    pub fn remove_if<P: FnMut(&BudgetItem) -> bool>(&mut self, mut predicate: P) -> Vec<BudgetItem> {
+        let (removed, kept) = core::mem::take(&mut self.items).into_iter().partition(|item| predicate(item));
+        self.items = kept;
+        self.index.rebuild(&self.items);
+        // Subtracting from the sums would reset them on the first item if none are left.
+        if self.items.is_empty() {
+            self.recompute();
+        } else {
+            for item in &removed {
+                self.count(item, -1.0);
+            }
+        }
+        if !self.observers.is_empty() {
+            for item in removed.iter().cloned() {
+                self.notify(|group| Change::ItemRemoved { group, item });
+            }
+        }
+        removed
+    }
+
+    /// Move the items matching a predicate into a new group, e.g. to pull all streaming
+    /// subscriptions out of an entertainment group into their own.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the new group.
+    /// * `predicate` - the predicate returning `true` for the items to move.
+    ///
+    /// # Returns
+    /// The new group, with the same ordering as this one and the moved items in their order.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut entertainment = BudgetGroup::new("Entertainment");
+    /// entertainment.add(BudgetItem::with_expense("Cinema", 30.0, Period::Every1Month));
+    /// entertainment.add(BudgetItem::with_expense("Streaming music", 10.0, Period::Every1Month));
+    /// entertainment.add(BudgetItem::with_expense("Streaming video", 12.0, Period::Every1Month));
+    ///
+    /// let streaming = entertainment.split_off_where("Streaming", |item| item.name().starts_with("Streaming"));
+    /// assert_eq!(2, streaming.len());
+    /// assert_eq!(-30.0, entertainment.total());
+    /// ```
+    pub fn split_off_where<P: FnMut(&BudgetItem) -> bool>(&mut self, name: &str, predicate: P) -> BudgetGroup {
+        let mut group = BudgetGroup::new(name);
+        group.order = self.order;
+        group.add_all(self.remove_if(predicate));
+        group
+    }
+
+    /// Remove all items from the group.
+    pub fn clear(&mut self) {
+        self.remove_if(|_| true);
+    }
+
+    /// Remove all items from the group and hand them over, e.g. to move them to another group.
+    ///
+    /// The items are removed right away, even if the returned iterator is not consumed.
+    ///
+    /// # Returns
+    /// An iterator over the removed items, in the order they had in the group.
+    ///
+    /// ```
+    /// use rbp_core::budget_group::BudgetGroup;
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let mut old = BudgetGroup::new("Car");
+    /// old.add(BudgetItem::with_expense("Fuel", 80.0, Period::Every1Month));
+    /// let mut new = BudgetGroup::new("Transport");
+    ///
+    /// new.extend(old.drain());
+    /// assert_eq!(0, old.enumerate().len());
+    /// assert_eq!(-80.0, new.total());
+    /// ```
+    pub fn drain(&mut self) -> impl Iterator<Item = BudgetItem> {
+        self.remove_if(|_| true).into_iter()
+    }
+
+    /// Replace an item in the group with another, based on its index.
+    ///
+    /// In a sorted group, the new item may end up at a different index than the one it replaced. In
+    /// a manually ordered group, it takes the place of the old one. Either way, it takes over the
+    /// identifier of the old one.
+    ///
+    /// # Parameters
+    /// * `idx` - the index of the item to be replaced, as discovered using [`enumerate`](#method.enumerate).
+    /// * `item` - the item taking the place of the old one.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the replaced item if the index is valid, or `Result::Err` if the index is
+    /// invalid.
+    pub fn replace(&mut self, idx: usize, mut item: BudgetItem) -> Result<BudgetItem, InvalidIndex> {
+        if idx >= self.items.len() {
+            return Err(InvalidIndex { });
+        }
+        item.set_id(self.items[idx].id());
+        let to = if self.observers.is_empty() { None } else { Some(item.clone()) };
+        self.count(&item, 1.0);
+        let old = match self.order {
+            ItemOrder::Sorted => {
+                let old = self.take(idx);
+                self.insert(item);
+                old
+            }
+            ItemOrder::Manual => {
+                let old = core::mem::replace(&mut self.items[idx], item);
+                // The old item goes first, as the new one has the same identifier.
+                self.index.removed(idx, &old);
+                self.index.inserted(idx, &self.items[idx]);
+                old
+            }
+        };
+        self.count(&old, -1.0);
+        if let Some(to) = to {
+            let from = old.clone();
+            self.notify(|group| Change::ItemUpdated { group, from, to });
+        }
+        Ok(old)
+    }
+
+    /// Calculate the total budget for this group over a year, e.g. for planning annual expenses
+    /// like insurance and taxes.
+    ///
+    /// # Returns
+    /// The sum of the yearly contributions of the items, which avoids the rounding errors of
+    /// multiplying the monthly [`total`](#method.total) by 12.
+    pub fn total_yearly_contribution(&self) -> f64 {
+        saturating_sum(self.items.iter().map(|item| item.yearly_contribution()))
+    }
+
+    /// Calculate what the items of this group contribute over a number of months, as described on
+    /// [`BudgetItem::contribution_over`](../budget_item/struct.BudgetItem.html#method.contribution_over).
+    ///
+    /// # Parameters
+    /// * `months` - the number of months.
+    ///
+    /// # Returns
+    /// The range of contributions, from the worst case of every item to the best case of every
+    /// item.
+    pub fn contribution_over(&self, months: u16) -> ContributionRange {
+        ContributionRange::sum(self.items.iter().map(|item| item.contribution_over(months)))
+    }
+
+    /// Calculate the total budget for this group, based on a monthly recurring cycle, with every
+    /// monthly contribution rounded according to a policy, so the total is the sum of the rounded
+    /// contributions.
+    ///
+    /// # Parameters
+    /// * `rounding` - how the contributions are rounded.
+    ///
+    /// # Returns
+    /// The rounded total, which is [`total`](#method.total) if nothing is rounded.
+    pub fn rounded_total(&self, rounding: RoundingPolicy) -> f64 {
+        if rounding == RoundingPolicy::None {
+            return self.total();
+        }
+        // Summing whole cents can still leave binary noise, which the final rounding removes.
+        rounding.round(saturating_sum(self.items.iter().map(|item| item.rounded_monthly_contribution(rounding))))
+    }
+
     /// Calculate the total budget for this group, based on a monthly recurring cycle.
     ///
+    /// The total is maintained as items are added, removed and replaced, so this does not visit
+    /// the items.
+    ///
     /// # Returns
     /// The total of all the budget items in this group. Will be a negative number if the group
-    /// contains more total expenses than income.
+    /// contains more total expenses than income. Income and expenses too large for an `f64` are
+    /// counted as the largest finite value instead. The contributions are not rounded; a group in a
+    /// budget adds up to the budget total with [`rounded_total`](#method.rounded_total) and the
+    /// rounding policy of the budget.
     pub fn total(&self) -> f64 {
-        self.items.iter().map(|item| item.monthly_contribution()).sum()
+        self.income + self.expenses
+    }
+
+    /// Get the monthly contribution of the income items in this group.
+    pub fn income_total(&self) -> f64 {
+        self.income
+    }
+
+    /// Get the monthly cost of the expense items in this group, as a negative number.
+    pub fn expense_total(&self) -> f64 {
+        self.expenses
+    }
+
+    /// Recalculate the maintained totals from the items.
+    ///
+    /// Adding and subtracting amounts in floating point can leave tiny rounding errors behind, so
+    /// after a very long series of changes the maintained totals can drift from the sum of the
+    /// items by a fraction of a cent. This sums the items again from scratch.
+    pub fn recompute(&mut self) {
+        self.income = saturating_sum(self.incomes().map(|item| item.monthly_contribution()));
+        self.expenses = saturating_sum(self.expenses().map(|item| item.monthly_contribution()));
+    }
+
+    /// Get a view of the items in another order than that of the group, e.g. for sortable table
+    /// columns. The group itself keeps its order, whether sorted or manual.
+    ///
+    /// # Parameters
+    /// * `key` - the order of the view.
+    ///
+    /// # Returns
+    /// References to all items of the group, in the requested order.
+    pub fn sorted_by(&self, key: SortKey) -> Vec<&BudgetItem> {
+        let mut view: Vec<&BudgetItem> = self.items.iter().collect();
+        // Items that are equal by the key fall back to the name order, as a manually ordered group
+        // is not in that order itself.
+        view.sort_by(|a, b| match key {
+            SortKey::Name => Ordering::Equal,
+            SortKey::Amount => a.amount().partial_cmp(&b.amount()).unwrap_or(Ordering::Equal),
+            SortKey::MonthlyContribution => a.monthly_contribution().partial_cmp(&b.monthly_contribution()).unwrap_or(Ordering::Equal),
+            SortKey::KindThenName => b.is_income().cmp(&a.is_income()),
+            SortKey::Period => a.period().cmp(&b.period()),
+        }.then_with(|| a.cmp(b)));
+        view
+    }
+
+    /// Get the items of the group with their amounts converted to one period, e.g. to show a table
+    /// comparing all items as yearly amounts. The items themselves are not changed.
+    ///
+    /// # Parameters
+    /// * `period` - the period to convert every amount to.
+    ///
+    /// # Returns
+    /// Every item in the order of [`enumerate`](#method.enumerate), with its amount per period.
+    /// Like the amount of the item, the converted amount is positive for both income and expenses.
+    pub fn normalized_view(&self, period: Period) -> Vec<(&BudgetItem, f64)> {
+        self.items.iter()
+            .map(|item| (item, saturate(item.amount() * period.months() as f64 / item.period().months() as f64)))
+            .collect()
+    }
+
+    // Add the monthly contribution of an item to the running sums (sign 1.0), or subtract it (sign
+    // -1.0). Removing the last item resets the sums to exact zeroes, so drift never outlives the
+    // items. A saturated sum no longer knows how much it overflowed, so it is summed again when an
+    // item is subtracted from it.
+    fn count(&mut self, item: &BudgetItem, sign: f64) {
+        let contribution = sign * item.monthly_contribution();
+        let sum = if item.is_income() { &mut self.income } else { &mut self.expenses };
+        let saturated = sum.abs() == f64::MAX;
+        *sum = saturate(*sum + contribution);
+        if sign < 0.0 && saturated {
+            self.recompute();
+        }
+        if sign < 0.0 && self.items.is_empty() {
+            self.income = 0.0;
+            self.expenses = 0.0;
+        }
+    }
+
+    // Insert an item at its place: found with a binary search in a sorted group, which keeps adding
+    // linear instead of sorting on every insertion, or at the end in a manual group.
+    fn insert(&mut self, item: BudgetItem) {
+        let idx = match self.order {
+            ItemOrder::Sorted => self.items.binary_search(&item).unwrap_or_else(|idx| idx),
+            ItemOrder::Manual => self.items.len(),
+        };
+        self.index.inserted(idx, &item);
+        self.items.insert(idx, item);
+    }
+
+    // Remove the item at a valid index, keeping the index of the group up to date.
+    fn take(&mut self, idx: usize) -> BudgetItem {
+        let item = self.items.remove(idx);
+        self.index.removed(idx, &item);
+        item
+    }
+
+    // Sort the items, unless they are ordered manually, and index them again as they may have
+    // been rearranged.
+    fn sort(&mut self) {
+        if self.order == ItemOrder::Sorted {
+            self.items.sort_unstable();
+        }
+        self.index.rebuild(&self.items);
+    }
+
+    // Give an item the next identifier of the group.
+    fn assign_id(&mut self, item: &mut BudgetItem) {
+        self.last_id += 1;
+        item.set_id(Some(ItemId(self.last_id)));
+    }
+
+    // Give a new identifier to the items replaced through `iter_mut`, whose identifier is missing,
+    // was given by another group, or is held by an earlier item too.
+    fn assign_missing_ids(&mut self) {
+        let mut seen = BTreeSet::new();
+        let last_id = &mut self.last_id;
+        for item in &mut self.items {
+            let given = item.id().filter(|id| id.0 <= *last_id && seen.insert(*id));
+            if given.is_none() {
+                *last_id += 1;
+                item.set_id(Some(ItemId(*last_id)));
+                seen.insert(ItemId(*last_id));
+            }
+        }
+    }
+
+    // Notify the subscribed callbacks of a change. The event is only built if there are any, from
+    // the name of the group.
+    fn notify<F: FnOnce(String) -> Change>(&mut self, event: F) {
+        if !self.observers.is_empty() {
+            let event = event(self.name.clone());
+            self.observers.notify(&event);
+        }
+    }
+}
+
+impl fmt::Display for BudgetGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(&FormatOptions::default()), f)
+    }
+}
+
+/// An empty group without a name, in sorted order.
+impl Default for BudgetGroup {
+    fn default() -> Self {
+        BudgetGroup::new("")
+    }
+}
+
+/// Index the items of a group, like [`BudgetGroup::get`](struct.BudgetGroup.html#method.get).
+///
+/// # Panics
+/// If the index is invalid.
+impl Index<usize> for BudgetGroup {
+    type Output = BudgetItem;
+
+    fn index(&self, idx: usize) -> &BudgetItem {
+        &self.items[idx]
+    }
+}
+
+impl<'a> IntoIterator for &'a BudgetGroup {
+    type Item = &'a BudgetItem;
+    type IntoIter = Iter<'a, BudgetItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl IntoIterator for BudgetGroup {
+    type Item = BudgetItem;
+    type IntoIter = alloc::vec::IntoIter<BudgetItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl Deref for ItemsMut<'_> {
+    type Target = [BudgetItem];
+
+    fn deref(&self) -> &[BudgetItem] {
+        &self.group.items
+    }
+}
+
+impl DerefMut for ItemsMut<'_> {
+    fn deref_mut(&mut self) -> &mut [BudgetItem] {
+        &mut self.group.items
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ItemsMut<'_> {
+    type Item = &'a mut BudgetItem;
+    type IntoIter = IterMut<'a, BudgetItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.group.items.iter_mut()
+    }
+}
+
+impl Drop for ItemsMut<'_> {
+    fn drop(&mut self) {
+        let group = &mut *self.group;
+        let changed: Vec<(BudgetItem, BudgetItem)> = self.before.drain(..)
+            .zip(group.items.iter())
+            .filter(|(from, to)| !from.identical(to) || from.external_refs() != to.external_refs())
+            .map(|(from, to)| (from, to.clone()))
+            .collect();
+        group.recompute();
+        group.assign_missing_ids();
+        group.sort();
+        for (from, to) in changed {
+            group.notify(|group| Change::ItemUpdated { group, from, to });
+        }
+    }
+}
+
+/// Collect items into a group without a name, which can be given with
+/// [`set_name`](struct.BudgetGroup.html#method.set_name), or use
+/// [`BudgetGroup::with_items`](struct.BudgetGroup.html#method.with_items) to name it right away.
+impl FromIterator<BudgetItem> for BudgetGroup {
+    fn from_iter<I: IntoIterator<Item = BudgetItem>>(items: I) -> Self {
+        BudgetGroup::with_items("", items)
+    }
+}
+
+impl Extend<BudgetItem> for BudgetGroup {
+    fn extend<I: IntoIterator<Item = BudgetItem>>(&mut self, items: I) {
+        self.add_all(items);
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::budget_group::BudgetGroup;
-    use crate::budget_item::{BudgetItem, Period};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::budget_group::{BudgetGroup, DedupPolicy, GroupSummary, ItemOrder, SortKey};
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::merge::MergePolicy;
 
     #[test]
     fn new() {
@@ -96,6 +1154,17 @@ mod test {
         assert_eq!("foo", group.name());
     }
 
+    #[test]
+    fn default() {
+        let mut group = BudgetGroup::default();
+        assert_eq!("", group.name());
+        assert!(group.is_empty());
+        assert_eq!(ItemOrder::Sorted, group.order());
+
+        group.set_name("foo");
+        assert_eq!("foo", group.name());
+    }
+
     #[test]
     fn add() {
         let mut item_group = BudgetGroup::new("foo");
@@ -131,12 +1200,554 @@ mod test {
         assert_eq!(0, item_group.enumerate().len())
     }
 
+    #[test]
+    fn remove_invalid_index() {
+        let mut item_group = BudgetGroup::new("foo");
+        item_group.add(BudgetItem::with_income("bar", 10.0, Period::Every1Month));
+
+        assert!(item_group.remove(1).is_err());
+        assert_eq!(1, item_group.enumerate().len())
+    }
+
+    #[test]
+    fn set_name() {
+        let mut group = BudgetGroup::new("foo");
+        group.set_name("bar");
+        assert_eq!("bar", group.name());
+    }
+
+    #[test]
+    fn replace() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("aa", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("bb", 10.0, Period::Every1Month));
+
+        let old = group.replace(0, BudgetItem::with_expense("cc", 20.0, Period::Every1Month)).unwrap();
+        assert_eq!("aa", old.name());
+        assert_eq!("bb", group.enumerate().next().unwrap().1.name());
+        assert_eq!(-30.0, group.total());
+        assert!(group.replace(2, old).is_err());
+    }
+
+    #[test]
+    fn subscribe() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut group = BudgetGroup::new("foo");
+        let log = events.clone();
+        let subscription = group.subscribe(move |event| log.borrow_mut().push(format!("{:?}", event)));
+
+        group.add(BudgetItem::with_expense("bar", 10.0, Period::Every1Month));
+        group.replace(0, BudgetItem::with_expense("baz", 10.0, Period::Every1Month)).unwrap();
+        group.remove(0).unwrap();
+        group.set_name("qux");
+        assert!(group.unsubscribe(subscription));
+        group.set_name("quux");
+
+        let events = events.borrow();
+        assert_eq!(4, events.len());
+        assert!(events[0].starts_with("ItemAdded { group: \"foo\""));
+        assert!(events[1].starts_with("ItemUpdated { group: \"foo\""));
+        assert!(events[2].starts_with("ItemRemoved { group: \"foo\""));
+        assert_eq!("GroupRenamed { from: \"foo\", to: \"qux\" }", events[3]);
+    }
+
+    #[test]
+    fn add_all() {
+        let events = Rc::new(RefCell::new(0));
+        let mut group = BudgetGroup::new("foo");
+        let log = events.clone();
+        group.subscribe(move |_| *log.borrow_mut() += 1);
+        group.add(BudgetItem::with_expense("b", 10.0, Period::Every1Month));
+        group.add_all(vec![
+            BudgetItem::with_expense("c", 20.0, Period::Every1Month),
+            BudgetItem::with_income("a", 40.0, Period::Every1Month),
+        ]);
+        let names: Vec<&str> = group.enumerate().map(|(_, item)| item.name().as_str()).collect();
+        assert_eq!(vec!["a", "b", "c"], names);
+        assert_eq!(10.0, group.total());
+        assert_eq!(3, *events.borrow());
+
+        group.set_order(ItemOrder::Manual);
+        group.extend(vec![BudgetItem::with_expense("e", 1.0, Period::Every1Month),
+                          BudgetItem::with_expense("d", 1.0, Period::Every1Month)]);
+        let names: Vec<&str> = group.enumerate().map(|(_, item)| item.name().as_str()).collect();
+        assert_eq!(vec!["a", "b", "c", "e", "d"], names);
+        assert_eq!(8.0, group.total());
+    }
+
+    #[test]
+    fn merge() {
+        let group = || {
+            let mut group = BudgetGroup::new("foo");
+            group.add(BudgetItem::with_expense("a", 10.0, Period::Every1Month));
+            group.add(BudgetItem::with_income("b", 20.0, Period::Every1Month));
+            group
+        };
+        let other = || {
+            let mut other = BudgetGroup::new("bar");
+            other.add(BudgetItem::with_expense("a", 120.0, Period::Every12Months));
+            other.add(BudgetItem::with_expense("b", 5.0, Period::Every1Month));
+            other
+        };
+        let amounts = |group: &BudgetGroup| group.iter().map(|item| item.amount()).collect::<Vec<_>>();
+
+        let mut merged = group();
+        merged.merge(other(), None);
+        assert_eq!("foo", merged.name());
+        assert_eq!(4, merged.len());
+        assert_eq!(-5.0, merged.total());
+
+        let mut merged = group();
+        merged.merge(other(), Some(MergePolicy::KeepMine));
+        assert_eq!(vec![10.0, 20.0, 5.0], amounts(&merged));
+
+        let mut merged = group();
+        merged.merge(other(), Some(MergePolicy::KeepTheirs));
+        assert_eq!(vec![120.0, 20.0, 5.0], amounts(&merged));
+        assert_eq!(Period::Every12Months, merged[0].period());
+
+        let mut merged = group();
+        merged.set_order(ItemOrder::Manual);
+        merged.merge(other(), Some(MergePolicy::SumAmounts));
+        assert_eq!(vec![20.0, 20.0, 5.0], amounts(&merged));
+        assert_eq!(-5.0, merged.total());
+    }
+
+    #[test]
+    fn dedup() {
+        let mut group = BudgetGroup::new("foo");
+        group.set_order(ItemOrder::Manual);
+        group.add(BudgetItem::with_expense("a", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("b", 5.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("a", 20.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("a", 20.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("b", 5.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("a", 30.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("b", 5.0, Period::Every2Months));
+        assert_eq!(vec![vec![0, 2, 5], vec![1, 4]], group.find_duplicates());
+
+        let mut kept = BudgetGroup::with_items("foo", group.iter().cloned());
+        assert!(BudgetGroup::new("bar").find_duplicates().is_empty());
+        assert_eq!(3, kept.dedup(DedupPolicy::KeepFirst).len());
+        assert!(kept.find_duplicates().is_empty());
+        assert_eq!(4, kept.len());
+        assert_eq!(2.5, kept.total());
+
+        let removed = group.dedup(DedupPolicy::SumAmounts);
+        assert_eq!(vec![20.0, 5.0, 30.0], removed.iter().map(|item| item.amount()).collect::<Vec<_>>());
+        assert_eq!(vec![60.0, 10.0, 20.0, 5.0], group.iter().map(|item| item.amount()).collect::<Vec<_>>());
+        assert_eq!(-52.5, group.total());
+
+        // Amounts too large to add up are neither merged nor removed.
+        let mut huge = BudgetGroup::new("foo");
+        huge.add(BudgetItem::with_expense("a", f64::MAX, Period::Every1Month));
+        huge.add(BudgetItem::with_expense("a", f64::MAX, Period::Every1Month));
+        huge.add(BudgetItem::with_expense("b", 5.0, Period::Every1Month));
+        huge.add(BudgetItem::with_expense("b", 5.0, Period::Every1Month));
+        assert_eq!(1, huge.dedup(DedupPolicy::SumAmounts).len());
+        assert_eq!(vec![f64::MAX, f64::MAX, 10.0], huge.iter().map(|item| item.amount()).collect::<Vec<_>>());
+
+        let mut merged = BudgetGroup::new("foo");
+        merged.add(BudgetItem::with_expense("a", f64::MAX, Period::Every1Month));
+        merged.merge(huge, Some(MergePolicy::SumAmounts));
+        assert_eq!(vec![f64::MAX, 10.0], merged.iter().map(|item| item.amount()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn remove_if() {
+        let events = Rc::new(RefCell::new(0));
+        let mut group = BudgetGroup::new("foo");
+        let log = events.clone();
+        group.add(BudgetItem::with_expense("a", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("b", 20.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("c", 30.0, Period::Every1Month));
+        group.subscribe(move |_| *log.borrow_mut() += 1);
+
+        let removed = group.remove_if(|item| !item.is_income());
+        assert_eq!(vec!["a", "c"], removed.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        assert_eq!(20.0, group.total());
+        assert_eq!(2, *events.borrow());
+        assert!(group.remove_if(|_| false).is_empty());
+
+        group.retain(|item| item.name() != "b");
+        assert_eq!(0, group.enumerate().len());
+        assert_eq!(0.0, group.total());
+        assert_eq!(3, *events.borrow());
+
+        group.add(BudgetItem::with_expense("a", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("b", 20.0, Period::Every1Month));
+        assert_eq!(2, group.remove_if(|_| true).len());
+        assert_eq!(0.0, group.expense_total());
+    }
+
+    #[test]
+    fn split_off_where() {
+        let mut group = BudgetGroup::new("foo");
+        group.set_order(ItemOrder::Manual);
+        group.add(BudgetItem::with_expense("c", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("a", 20.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("b", 30.0, Period::Every1Month));
+
+        let split = group.split_off_where("bar", |item| !item.is_income());
+        assert_eq!("bar", split.name());
+        assert_eq!(ItemOrder::Manual, split.order());
+        assert_eq!(vec!["c", "b"], split.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        assert_eq!(-40.0, split.total());
+        assert_eq!(1, group.len());
+        assert_eq!(20.0, group.total());
+
+        assert!(group.split_off_where("baz", |_| false).is_empty());
+    }
+
+    #[test]
+    fn drain() {
+        let mut group = BudgetGroup::new("foo");
+        group.set_order(ItemOrder::Manual);
+        group.add(BudgetItem::with_expense("b", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("a", 20.0, Period::Every1Month));
+
+        let drained: Vec<BudgetItem> = group.drain().collect();
+        assert_eq!(vec!["b", "a"], drained.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        assert_eq!(0, group.enumerate().len());
+        assert_eq!(0.0, group.total());
+        assert_eq!(ItemOrder::Manual, group.order());
+
+        group.add_all(drained);
+        group.clear();
+        assert_eq!(0, group.enumerate().len());
+        assert_eq!(0.0, group.income_total());
+    }
+
+    #[test]
+    fn iter() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("b", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("a", 20.0, Period::Every1Month));
+
+        assert_eq!(vec!["a", "b"], group.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        let mut count = 0;
+        for _ in &group {
+            count += 1;
+        }
+        assert_eq!(2, count);
+        assert_eq!(vec!["a", "b"], group.into_iter().map(|item| item.name().clone()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iter_mut() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("b", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("a", 20.0, Period::Every1Month));
+        let log = events.clone();
+        group.subscribe(move |event| log.borrow_mut().push(format!("{:?}", event)));
+
+        for item in &mut group.iter_mut() {
+            if item.name() == "a" {
+                *item = BudgetItem::with_expense("c", 5.0, Period::Every1Month);
+            }
+        }
+        assert_eq!(vec!["b", "c"], group.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        assert_eq!(-15.0, group.total());
+        assert_eq!(0.0, group.income_total());
+        assert_eq!(1, events.borrow().len());
+        assert!(events.borrow()[0].starts_with("ItemUpdated { group: \"foo\""));
+    }
+
+    #[test]
+    fn from_iter() {
+        let items = vec![
+            BudgetItem::with_expense("b", 10.0, Period::Every1Month),
+            BudgetItem::with_income("a", 20.0, Period::Every1Month),
+        ];
+        let mut group: BudgetGroup = items.clone().into_iter().collect();
+        assert_eq!("", group.name());
+        assert_eq!(vec!["a", "b"], group.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        assert_eq!(10.0, group.total());
+        group.set_name("foo");
+        assert_eq!("foo", group.name());
+
+        let group = BudgetGroup::with_items("bar", items);
+        assert_eq!("bar", group.name());
+        assert_eq!(2, group.iter().len());
+    }
+
+    #[test]
+    fn len() {
+        let mut group = BudgetGroup::with_capacity("foo", 10);
+        assert!(group.is_empty());
+        assert_eq!(0, group.len());
+
+        group.reserve(20);
+        group.add(BudgetItem::with_expense("b", 10.0, Period::Every1Month));
+        assert!(!group.is_empty());
+        assert_eq!(1, group.len());
+    }
+
+    #[test]
+    fn get() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("b", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("a", 20.0, Period::Every1Month));
+
+        assert_eq!("a", group.get(0).unwrap().name());
+        assert_eq!("b", group[1].name());
+        assert!(group.get(2).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        let group = BudgetGroup::new("foo");
+        let _ = &group[0];
+    }
+
     #[test]
     fn total() {
         let mut group = BudgetGroup::new("A group");
         group.add(BudgetItem::with_income("Income item", 100.0, Period::Every1Month));
         group.add(BudgetItem::with_expense("Expense item", 100.0, Period::Every2Months));
 
-        assert_eq!(50.0, group.total())
+        assert_eq!(50.0, group.total());
+        assert_eq!(100.0, group.income_total());
+        assert_eq!(-50.0, group.expense_total());
+    }
+
+    #[test]
+    fn huge_totals_saturate() {
+        let mut group = BudgetGroup::new("A group");
+        group.add(BudgetItem::with_expense("Expense item", f64::MAX, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Other expense item", f64::MAX, Period::Every1Month));
+        group.add(BudgetItem::with_income("Salary", 100.0, Period::Every1Month));
+
+        assert_eq!(-f64::MAX, group.expense_total());
+        assert_eq!(-f64::MAX + 100.0, group.total());
+        assert_eq!(-f64::MAX, group.total_yearly_contribution());
+        assert_eq!(-f64::MAX, group.contribution_over(12).lowest);
+        assert_eq!(-f64::MAX, group.rounded_total(RoundingPolicy::HalfUp));
+
+        group.remove(0).unwrap();
+        assert_eq!(-f64::MAX, group.expense_total());
+        group.remove(0).unwrap();
+        assert_eq!(100.0, group.total());
+    }
+
+    #[test]
+    fn total_yearly_contribution() {
+        let mut group = BudgetGroup::new("A group");
+        assert_eq!(0.0, group.total_yearly_contribution());
+        group.add(BudgetItem::with_income("Income item", 100.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Expense item", 100.0, Period::Every3Months));
+        group.add(BudgetItem::with_expense("Other item", 0.1, Period::Every6Months));
+
+        assert_eq!(799.8, group.total_yearly_contribution());
+    }
+
+    #[test]
+    fn contribution_over() {
+        let mut group = BudgetGroup::new("A group");
+        group.add(BudgetItem::with_income("Income item", 100.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Expense item", 100.0, Period::Every3Months));
+        group.add(BudgetItem::with_expense("Other item", 10.0, Period::Every2Months));
+
+        let range = group.contribution_over(4);
+        assert_eq!(180.0, range.lowest);
+        assert_eq!(280.0, range.highest);
+    }
+
+    #[test]
+    fn summary() {
+        let mut group = BudgetGroup::new("A group");
+        assert_eq!(GroupSummary { income_count: 0, income_total: 0.0, expense_count: 0, expense_total: 0.0 }, group.summary());
+
+        group.add(BudgetItem::with_income("Income item", 100.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Expense item", 100.0, Period::Every2Months));
+        group.add(BudgetItem::with_expense("Other item", 10.0, Period::Every1Month));
+        assert_eq!(GroupSummary { income_count: 1, income_total: 100.0, expense_count: 2, expense_total: -60.0 }, group.summary());
+    }
+
+    #[test]
+    fn maintained_totals() {
+        let mut group = BudgetGroup::new("A group");
+        for idx in 1..=100 {
+            group.add(BudgetItem::with_expense(&format!("{}", idx), 0.1 * idx as f64, Period::Every3Months));
+        }
+        group.add(BudgetItem::with_income("Income", 1_000.0, Period::Every1Month));
+        group.replace(0, BudgetItem::with_expense("Replaced", 7.0, Period::Every1Month)).unwrap();
+        while group.enumerate().len() > 50 {
+            group.remove(0).unwrap();
+        }
+
+        let sum: f64 = group.enumerate().map(|(_, item)| item.monthly_contribution()).sum();
+        assert!((group.total() - sum).abs() < 1e-9);
+        group.recompute();
+        assert_eq!(sum, group.total());
+
+        while group.remove(0).is_ok() { }
+        assert_eq!(0.0, group.total());
+    }
+
+    #[test]
+    fn find() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("bar", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_income("bar", 20.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("baz", 30.0, Period::Every1Month));
+
+        assert_eq!(30.0, group.find("baz").unwrap().amount());
+        assert!(group.find("Baz").is_none());
+        assert_eq!(2, group.find_all("bar").len());
+        assert!(group.find_all("qux").is_empty());
+        assert!(group.find("a").is_none());
+        assert!(group.find("zzz").is_none());
+
+        group.set_order(ItemOrder::Manual);
+        group.move_item(2, 0).unwrap();
+        assert_eq!(30.0, group.find("baz").unwrap().amount());
+        assert_eq!(2, group.find_all("bar").len());
+    }
+
+    #[test]
+    fn ids() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("c", 10.0, Period::Every1Month));
+        let c = group[0].id().unwrap();
+        group.add(BudgetItem::with_expense("a", 20.0, Period::Every1Month));
+        group.add_all(vec![BudgetItem::with_expense("b", 30.0, Period::Every1Month)]);
+        let b = group.find("b").unwrap().id().unwrap();
+        assert_eq!(Some(2), group.index_of(c));
+        assert_ne!(b, c);
+
+        // Identifiers stay with their items when the group is rearranged.
+        group.set_order(ItemOrder::Manual);
+        group.move_item(2, 0).unwrap();
+        assert_eq!(Some(0), group.index_of(c));
+        assert_eq!(10.0, group.get_by_id(c).unwrap().amount());
+        group.replace(0, BudgetItem::with_expense("z", 15.0, Period::Every1Month)).unwrap();
+        assert_eq!("z", group.get_by_id(c).unwrap().name());
+        assert!(group.find("c").is_none());
+        group.set_order(ItemOrder::Sorted);
+        assert_eq!(Some(2), group.index_of(c));
+
+        // Items replaced through the guard, or taken from another group, get new identifiers.
+        for item in &mut group.iter_mut() {
+            if item.name() == "a" {
+                *item = BudgetItem::with_expense("y", 20.0, Period::Every1Month);
+            }
+        }
+        let y = group.find("y").unwrap().id().unwrap();
+        assert_ne!(Some(y), group.find("b").unwrap().id());
+        let mut other = BudgetGroup::new("bar");
+        other.add(group[0].clone());
+        other.add(group[0].clone());
+        assert_ne!(other[0].id(), other[1].id());
+
+        group.remove_by_id(b).unwrap();
+        assert!(group.get_by_id(b).is_none());
+        assert!(group.remove_by_id(b).is_err());
+        assert_eq!(vec!["y", "z"], group.iter().map(|item| item.name().as_str()).collect::<Vec<_>>());
+        assert_eq!(Some(1), group.index_of(c));
+        assert_eq!(Some(0), group.index_of(y));
+        group.retain(|item| item.name() == "z");
+        assert_eq!(Some(0), group.index_of(c));
+        assert!(group.find("y").is_none());
+    }
+
+    #[test]
+    fn filters() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_income("aa", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("bb", 20.0, Period::Every12Months));
+        group.add(BudgetItem::with_expense("cc", 30.0, Period::Every1Month));
+
+        let names = |items: &mut dyn Iterator<Item = &BudgetItem>| items.map(|item| item.name().clone()).collect::<Vec<_>>();
+        assert_eq!(vec!["aa"], names(&mut group.incomes()));
+        assert_eq!(vec!["bb", "cc"], names(&mut group.expenses()));
+        assert_eq!(vec!["aa", "cc"], names(&mut group.with_period(Period::Every1Month)));
+        assert_eq!(vec!["cc"], names(&mut group.iter_filtered(|item| item.amount() > 25.0)));
+    }
+
+    #[test]
+    fn manual_order() {
+        let mut group = BudgetGroup::new("foo");
+        group.set_order(ItemOrder::Manual);
+        group.add(BudgetItem::with_expense("cc", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("aa", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("bb", 10.0, Period::Every1Month));
+        group.replace(0, BudgetItem::with_expense("dd", 10.0, Period::Every1Month)).unwrap();
+
+        let names = |group: &BudgetGroup| group.enumerate().map(|(_, item)| item.name().as_str()).collect::<Vec<_>>().join(",");
+        assert_eq!("dd,aa,bb", names(&group));
+        group.move_item(2, 0).unwrap();
+        assert_eq!("bb,dd,aa", names(&group));
+        group.move_down(0).unwrap();
+        group.move_up(2).unwrap();
+        assert_eq!("dd,aa,bb", names(&group));
+        assert!(group.move_up(0).is_err());
+        assert!(group.move_down(2).is_err());
+        assert!(group.move_down(usize::MAX).is_err());
+        group.remove(0).unwrap();
+        assert_eq!("aa,bb", names(&group));
+
+        group.set_order(ItemOrder::Sorted);
+        group.add(BudgetItem::with_expense("ab", 10.0, Period::Every1Month));
+        assert_eq!("aa,ab,bb", names(&group));
+        group.move_item(0, 2).unwrap();
+        assert_eq!(ItemOrder::Manual, group.order());
+    }
+
+    #[test]
+    fn reorder_events() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("aa", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("bb", 10.0, Period::Every1Month));
+        let log = events.clone();
+        group.subscribe(move |event| log.borrow_mut().push(format!("{:?}", event)));
+
+        group.set_order(ItemOrder::Sorted);
+        group.move_down(0).unwrap();
+        group.set_order(ItemOrder::Manual);
+        group.set_order(ItemOrder::Sorted);
+
+        let events = events.borrow();
+        assert_eq!(2, events.len());
+        assert!(events[0].starts_with("ItemReordered { group: \"foo\", item: BudgetItem { name: \"aa\""));
+        assert!(events[0].ends_with("to: 1 }"));
+        assert_eq!("OrderChanged { group: \"foo\", order: Sorted }", events[1]);
+    }
+
+    #[test]
+    fn sorted_by() {
+        let mut group = BudgetGroup::new("foo");
+        group.add(BudgetItem::with_expense("aa", 30.0, Period::Every12Months));
+        group.add(BudgetItem::with_income("bb", 20.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("cc", 10.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("dd", 10.0, Period::Every3Months));
+
+        let names = |key| group.sorted_by(key).iter().map(|item| item.name().as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["aa", "bb", "cc", "dd"], names(SortKey::Name));
+        assert_eq!(vec!["cc", "dd", "bb", "aa"], names(SortKey::Amount));
+        assert_eq!(vec!["cc", "dd", "aa", "bb"], names(SortKey::MonthlyContribution));
+        assert_eq!(vec!["bb", "aa", "cc", "dd"], names(SortKey::KindThenName));
+        assert_eq!(vec!["bb", "cc", "dd", "aa"], names(SortKey::Period));
+        assert_eq!("aa", group.enumerate().next().unwrap().1.name());
+
+        group.move_item(0, 3).unwrap();
+        let names = |key| group.sorted_by(key).iter().map(|item| item.name().as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["aa", "bb", "cc", "dd"], names(SortKey::Name));
+        assert_eq!(vec!["bb", "aa", "cc", "dd"], names(SortKey::KindThenName));
+        assert_eq!("bb", group.enumerate().next().unwrap().1.name());
+    }
+
+    #[test]
+    fn normalized_view() {
+        let mut group = BudgetGroup::new("A group");
+        group.add(BudgetItem::with_income("Income item", 100.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Expense item", 300.0, Period::Every3Months));
+
+        let amounts: Vec<f64> = group.normalized_view(Period::Every12Months).iter().map(|(_, amount)| *amount).collect();
+        assert_eq!(vec![1_200.0, 1_200.0], amounts);
+        assert_eq!(300.0, group.enumerate().next().unwrap().1.amount());
     }
 }
\ No newline at end of file
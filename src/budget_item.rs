@@ -1,9 +1,18 @@
-use std::cmp::Ordering;
-use std::cmp::Ordering::Equal;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::cmp::Ordering::Equal;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use crate::budget::BudgetDefaults;
+use crate::format::{FormatOptions, Formatted};
 
 /// The repeating period of a budget item, e.g. [`Every3Months`] means in item whose amount is
 /// repeated every 3 months.
-#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Copy, Clone)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Copy, Clone)]
 pub enum Period {
     /// The amount of the budget item is based on a 1-month recurring period.
     Every1Month,
@@ -17,6 +26,94 @@ pub enum Period {
     Every12Months,
 }
 
+impl Period {
+
+    /// All periods, shortest first, e.g. to fill a dropdown.
+    pub const ALL: [Period; 5] = [
+        Period::Every1Month,
+        Period::Every2Months,
+        Period::Every3Months,
+        Period::Every6Months,
+        Period::Every12Months,
+    ];
+
+    /// Get an iterator over all periods, shortest first, as listed in [`ALL`](#associatedconstant.ALL).
+    pub fn iter() -> impl Iterator<Item = Period> {
+        Period::ALL.iter().copied()
+    }
+
+    /// Get the number of months in the period, e.g. 3 for [`Every3Months`](#variant.Every3Months).
+    pub fn months(self) -> u8 {
+        match self {
+            Period::Every1Month => 1,
+            Period::Every2Months => 2,
+            Period::Every3Months => 3,
+            Period::Every6Months => 6,
+            Period::Every12Months => 12,
+        }
+    }
+
+    /// Get the number of times the amount of an item with the period occurs in a year, e.g. 4 for
+    /// [`Every3Months`](#variant.Every3Months).
+    pub fn occurrences_per_year(self) -> u8 {
+        12 / self.months()
+    }
+
+    // The single word name of the period, as used by the CSV export, the command parser, and the
+    // JSON of change events.
+    pub(crate) fn keyword(self) -> &'static str {
+        match self {
+            Period::Every1Month => "monthly",
+            Period::Every2Months => "bimonthly",
+            Period::Every3Months => "quarterly",
+            Period::Every6Months => "half-yearly",
+            Period::Every12Months => "yearly",
+        }
+    }
+
+    // A human readable label of the period, as written by its `Display` implementation and used in
+    // the reports.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Period::Every1Month => "Monthly",
+            Period::Every2Months => "Every 2 months",
+            Period::Every3Months => "Quarterly",
+            Period::Every6Months => "Every 6 months",
+            Period::Every12Months => "Yearly",
+        }
+    }
+
+    // The period with a number of months, as returned by `months`.
+    fn from_months(months: u32) -> Option<Period> {
+        Period::iter().find(|period| u32::from(period.months()) == months)
+    }
+
+    // The period with a single word name, as returned by `keyword`.
+    pub(crate) fn from_keyword(keyword: &str) -> Option<Period> {
+        Period::iter().find(|period| period.keyword() == keyword)
+    }
+}
+
+/// A reference from a budget item to the matching record in an external system, e.g. a bank
+/// aggregator or an invoicing tool, so that integrations can correlate the two both ways.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ExternalRef {
+    /// The name of the external system, e.g. `"nordigen"`. An item holds at most one reference
+    /// per provider.
+    pub provider: String,
+    /// The identifier of the record in the external system.
+    pub external_id: String,
+    /// A link opening the record in the external system, if it has one.
+    pub url: Option<String>,
+}
+
+/// Identifier of an item within its group, assigned by the group when the item is added, e.g. to
+/// refer to a row of a table that keeps its identity while the group is sorted or reordered.
+///
+/// Identifiers are only unique within a group, and are not saved with the budget.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Copy, Clone)]
+pub struct ItemId(pub(crate) u64);
+
 /// A singular entry item in a budget.
 ///
 /// This type encompass the basic necessary data for a budget entry: a simple name, the amount the
@@ -40,17 +137,275 @@ pub enum Period {
 pub struct BudgetItem {
     name: String,
     period: Period,
-    item_type: Type,
+    item_type: Kind,
     amount: f64,
+    external_refs: Vec<ExternalRef>,
+    // The identifier given by the group holding the item. It is not part of the comparisons of
+    // items, as it says where an item is rather than what it is.
+    id: Option<ItemId>,
+}
+
+/// Writes the human readable label of the period, e.g. `Quarterly`.
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// How monthly contributions are rounded, as a budget is planned in whole cents while e.g. a
+/// quarterly amount divided by 3 rarely is.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Default)]
+pub enum RoundingPolicy {
+    /// Contributions are not rounded. This is the default.
+    #[default]
+    None,
+    /// Contributions are rounded to whole cents, with halves rounded away from zero.
+    HalfUp,
+    /// Contributions are rounded to whole cents, with halves rounded to the even cent, i.e.
+    /// banker's rounding, which does not skew sums of many halves upwards.
+    HalfEven,
+}
+
+impl RoundingPolicy {
+
+    /// Round a value according to the policy.
+    ///
+    /// # Parameters
+    /// * `value` - the value to round.
+    ///
+    /// # Returns
+    /// The rounded value.
+    ///
+    /// ```
+    /// use rbp_core::budget_item::RoundingPolicy;
+    /// assert_eq!(33.33, RoundingPolicy::HalfUp.round(100.0 / 3.0));
+    /// assert_eq!(-0.13, RoundingPolicy::HalfUp.round(-0.125));
+    /// assert_eq!(-0.12, RoundingPolicy::HalfEven.round(-0.125));
+    /// ```
+    pub fn round(self, value: f64) -> f64 {
+        let cents = value.abs() * 100.0;
+        // Values this large already are whole cents, and would not fit the integer below. NaN is
+        // left as is, too.
+        if self == RoundingPolicy::None || cents.is_nan() || cents >= 9_007_199_254_740_992.0 {
+            return value;
+        }
+        // Rounded by truncation, as `f64::round` needs the standard library.
+        let whole = cents as u64;
+        let fraction = cents - whole as f64;
+        let up = match self {
+            RoundingPolicy::HalfUp => fraction >= 0.5,
+            _ => fraction > 0.5 || fraction == 0.5 && whole % 2 == 1,
+        };
+        let rounded = (whole + up as u64) as f64 / 100.0;
+        if value < 0.0 { -rounded } else { rounded }
+    }
+
+    // The single word name of the policy, as used by the document format.
+    pub(crate) fn keyword(self) -> &'static str {
+        match self {
+            RoundingPolicy::None => "none",
+            RoundingPolicy::HalfUp => "half-up",
+            RoundingPolicy::HalfEven => "half-even",
+        }
+    }
+
+    // The policy with a single word name, as returned by `keyword`.
+    pub(crate) fn from_keyword(keyword: &str) -> Option<RoundingPolicy> {
+        [RoundingPolicy::None, RoundingPolicy::HalfUp, RoundingPolicy::HalfEven]
+            .iter()
+            .find(|policy| policy.keyword() == keyword)
+            .copied()
+    }
+}
+
+/// The range of what items contribute over a number of months, returned by
+/// [`BudgetItem::contribution_over`](struct.BudgetItem.html#method.contribution_over).
+///
+/// Items do not know in which month their amount is due, so e.g. a quarterly bill is paid either
+/// once or twice in any 4 months. The range spans both cases.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContributionRange {
+    /// The contribution in the worst case for the budget, i.e. with the most expense payments and
+    /// the fewest income payments falling within the months.
+    pub lowest: f64,
+    /// The contribution in the best case for the budget, i.e. with the fewest expense payments and
+    /// the most income payments falling within the months.
+    pub highest: f64,
+}
+
+impl ContributionRange {
+
+    // The range of several items, whose payments can fall in the months independently.
+    pub(crate) fn sum<I: Iterator<Item = ContributionRange>>(ranges: I) -> ContributionRange {
+        ranges.fold(ContributionRange { lowest: 0.0, highest: 0.0 }, |sum, range| ContributionRange {
+            lowest: saturate(sum.lowest + range.lowest),
+            highest: saturate(sum.highest + range.highest),
+        })
+    }
+}
+
+// Clamp a value that overflowed to infinity to the largest finite value of its sign. Every amount
+// is finite, but sums and multiples of huge amounts are not, and must not turn totals infinite.
+pub(crate) fn saturate(value: f64) -> f64 {
+    value.clamp(-f64::MAX, f64::MAX)
+}
+
+// Sum values, saturating instead of overflowing to infinity.
+pub(crate) fn saturating_sum<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    values.into_iter().fold(0.0, |sum, value| saturate(sum + value))
 }
 
-// Local type denoting the type of the budget item.
-#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Copy, Clone)]
-enum Type {
+/// A fluent constructor of budget items, returned by
+/// [`BudgetItem::builder`](struct.BudgetItem.html#method.builder), or by
+/// [`with_defaults`](#method.with_defaults) to apply the defaults of a budget.
+///
+/// Unlike the constructors of [`BudgetItem`], the builder reports invalid input as an error
+/// instead of panicking.
+///
+/// ```
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// let rent = BudgetItem::builder()
+///     .name("Rent")
+///     .expense(1_200.0)
+///     .period(Period::Every1Month)
+///     .build()
+///     .unwrap();
+/// assert_eq!(-1_200.0, rent.monthly_contribution());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BudgetItemBuilder {
+    name: Option<String>,
+    amount: Option<(Kind, f64)>,
+    period: Period,
+    external_refs: Vec<ExternalRef>,
+}
+
+/// Error thrown when a [`BudgetItemBuilder`] cannot build an item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// No name was given.
+    MissingName,
+    /// Neither an income nor an expense amount was given.
+    MissingAmount,
+    /// The amount is not a finite number greater than 0. Holds the amount.
+    InvalidAmount(f64),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingName => write!(f, "the item has no name"),
+            BuildError::MissingAmount => write!(f, "the item has no amount"),
+            BuildError::InvalidAmount(amount) => write!(f, "invalid amount {}, must be a finite number greater than 0", amount),
+        }
+    }
+}
+
+impl core::error::Error for BuildError { }
+
+/// Whether a budget item is an income or an expense.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Copy, Clone)]
+pub enum Kind {
+    /// The item contributes positively to a budget.
     Income,
+    /// The item contributes negatively to a budget.
     Expense,
 }
 
+/// Error thrown when a text does not name a [`Period`] or a [`Kind`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownName { }
+
+impl fmt::Display for UnknownName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown name")
+    }
+}
+
+impl core::error::Error for UnknownName { }
+
+/// Read a period, ignoring case and surrounding whitespace. Accepted are the keywords `monthly`,
+/// `bimonthly`, `quarterly`, `half-yearly` and `yearly`, phrases like `every 3 months`,
+/// `6 months` or `every year`, and short forms like `3m` or `1y`.
+///
+/// ```
+/// use rbp_core::budget_item::Period;
+/// assert_eq!(Ok(Period::Every3Months), "Quarterly".parse());
+/// assert_eq!(Ok(Period::Every3Months), "every 3 months".parse());
+/// assert_eq!(Ok(Period::Every3Months), "3m".parse::<Period>());
+/// assert!("every 5 months".parse::<Period>().is_err());
+/// ```
+impl FromStr for Period {
+    type Err = UnknownName;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let words: Vec<String> = text.split_whitespace().map(|word| word.to_ascii_lowercase()).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let words = match words.as_slice() {
+            ["every", rest @ ..] if !rest.is_empty() => rest,
+            words => words,
+        };
+        let months = match words {
+            [keyword] => match Period::from_keyword(keyword) {
+                Some(period) => return Ok(period),
+                None => match *keyword {
+                    "month" => Some(1),
+                    "annually" | "annual" | "year" => Some(12),
+                    "half-year" | "semiannually" => Some(6),
+                    short => short.strip_suffix('m').and_then(|months| months.parse().ok())
+                        .or_else(|| short.strip_suffix('y').and_then(|years| years.parse::<u32>().ok()).and_then(|years| years.checked_mul(12))),
+                },
+            },
+            [count, "month"] | [count, "months"] => count.parse().ok(),
+            [count, "year"] | [count, "years"] => count.parse::<u32>().ok().and_then(|years| years.checked_mul(12)),
+            _ => None,
+        };
+        months.and_then(Period::from_months).ok_or(UnknownName { })
+    }
+}
+
+impl TryFrom<&str> for Period {
+    type Error = UnknownName;
+
+    fn try_from(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+/// Writes `income` or `expense`.
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Income => "income",
+            Kind::Expense => "expense",
+        })
+    }
+}
+
+/// Read `income` or `expense`, ignoring case and surrounding whitespace.
+impl FromStr for Kind {
+    type Err = UnknownName;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if text.eq_ignore_ascii_case("income") {
+            Ok(Kind::Income)
+        } else if text.eq_ignore_ascii_case("expense") {
+            Ok(Kind::Expense)
+        } else {
+            Err(UnknownName { })
+        }
+    }
+}
+
+impl TryFrom<&str> for Kind {
+    type Error = UnknownName;
+
+    fn try_from(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
 impl BudgetItem {
 
     /// Create a new income budget item.
@@ -60,22 +415,24 @@ impl BudgetItem {
     /// # Parameters
     /// * `name` - the name of the item.
     /// * `amount` - the amount the entry contributes to an overall budget. This must always be
-    /// a positive number.
+    ///   a positive number.
     /// * `period` - the recurring period of how often the amount contribute to the overall budget.
     ///
     /// # Returns
     /// Always returns a valid budget item.
     ///
     /// # Panics
-    /// If the amount is less than 0, the method will panic.
+    /// If the amount is not a finite number greater than 0, the method will panic.
     pub fn with_income(name: &str, amount: f64, period: Period) -> BudgetItem {
         Self::check_amount(&amount);
 
         BudgetItem{
             name: name.to_owned(),
             period,
-            item_type: Type::Income,
-            amount
+            item_type: Kind::Income,
+            amount,
+            external_refs: Vec::new(),
+            id: None
         }
     }
 
@@ -86,25 +443,172 @@ impl BudgetItem {
     /// # Parameters
     /// * `name` - the name of the item.
     /// * `amount` - the amount the entry contributes to an overall budget. This must always be
-    /// a positive number.
+    ///   a positive number.
     /// * `period` - the recurring period of how often the amount contribute to the overall budget.
     ///
     /// # Returns
     /// Always returns a valid budget item.
     ///
     /// # Panics
-    /// If the amount is less than 0, the method will panic.
+    /// If the amount is not a finite number greater than 0, the method will panic.
     pub fn with_expense(name: &str, amount: f64, period: Period) -> BudgetItem {
         Self::check_amount(&amount);
 
         BudgetItem{
             name: name.to_owned(),
             period,
-            item_type: Type::Expense,
-            amount
+            item_type: Kind::Expense,
+            amount,
+            external_refs: Vec::new(),
+            id: None
+        }
+    }
+
+    /// Display the item with options for its amount, e.g. a currency symbol. The `Display`
+    /// implementation of the item uses the default options.
+    ///
+    /// # Parameters
+    /// * `options` - how the amount is formatted.
+    ///
+    /// # Returns
+    /// The item, ready to be written as described on [`Formatted`].
+    pub fn display<'a>(&'a self, options: &'a FormatOptions) -> Formatted<'a, BudgetItem> {
+        Formatted::new(self, options)
+    }
+
+    /// Create a new budget item, without panicking on an invalid amount, e.g. for amounts entered
+    /// by a user.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the item.
+    /// * `kind` - whether the item is an income or an expense.
+    /// * `amount` - the amount the entry contributes to an overall budget.
+    /// * `period` - the recurring period of how often the amount contribute to the overall budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the item, or `Result::Err` with [`BuildError::InvalidAmount`] if the
+    /// amount is not a finite number greater than 0.
+    ///
+    /// ```
+    /// use rbp_core::budget_item::{BudgetItem, BuildError, Kind, Period};
+    /// assert!(BudgetItem::try_new("Rent", Kind::Expense, 900.0, Period::Every1Month).is_ok());
+    /// assert!(BudgetItem::try_new("Rent", Kind::Expense, f64::INFINITY, Period::Every1Month).is_err());
+    /// ```
+    pub fn try_new(name: &str, kind: Kind, amount: f64, period: Period) -> Result<BudgetItem, BuildError> {
+        if !Self::valid_amount(amount) {
+            return Err(BuildError::InvalidAmount(amount));
+        }
+        Ok(BudgetItem {
+            name: name.to_owned(),
+            period,
+            item_type: kind,
+            amount,
+            external_refs: Vec::new(),
+            id: None
+        })
+    }
+
+    /// Start building an item with a [`BudgetItemBuilder`]. The period is monthly unless another
+    /// one is given; use [`BudgetItemBuilder::with_defaults`] to start from the default period of a
+    /// budget instead.
+    pub fn builder() -> BudgetItemBuilder {
+        BudgetItemBuilder {
+            name: None,
+            amount: None,
+            period: Period::Every1Month,
+            external_refs: Vec::new(),
+        }
+    }
+
+    /// Get a reference to the item's name.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Get the identifier the group holding the item gave it.
+    ///
+    /// # Returns
+    /// The identifier, or `None` if the item was never added to a group.
+    pub fn id(&self) -> Option<ItemId> {
+        self.id
+    }
+
+    // Give the item an identifier, or take it away when the item leaves its group.
+    pub(crate) fn set_id(&mut self, id: Option<ItemId>) {
+        self.id = id;
+    }
+
+    /// Get the item's amount, which is always a positive number regardless of the item's type.
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    /// Get the recurring period of the item's amount.
+    pub fn period(&self) -> Period {
+        self.period
+    }
+
+    /// Get whether the item is an income or an expense.
+    pub fn kind(&self) -> Kind {
+        self.item_type
+    }
+
+    /// Whether the item is an income, i.e. contributes positively to a budget. If not, the item is
+    /// an expense.
+    pub fn is_income(&self) -> bool {
+        self.item_type == Kind::Income
+    }
+
+    /// Compare two items including their amounts, unlike `==`, which only compares the name,
+    /// period and kind that determine the order of items. External references are not compared.
+    ///
+    /// # Parameters
+    /// * `other` - the item to compare with.
+    ///
+    /// ```
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let rent = BudgetItem::with_expense("Rent", 900.0, Period::Every1Month);
+    /// let raised = BudgetItem::with_expense("Rent", 950.0, Period::Every1Month);
+    /// assert!(rent == raised);
+    /// assert!(!rent.identical(&raised));
+    /// ```
+    pub fn identical(&self, other: &BudgetItem) -> bool {
+        self == other && self.amount == other.amount
+    }
+
+    /// Get the references to the records in external systems matching the item, in the order
+    /// they were added.
+    pub fn external_refs(&self) -> &[ExternalRef] {
+        &self.external_refs
+    }
+
+    /// Add a reference to a record in an external system, replacing the reference to the same
+    /// provider if the item has one.
+    ///
+    /// The references are not part of the item's identity, so they do not affect comparisons or
+    /// ordering.
+    ///
+    /// # Parameters
+    /// * `external_ref` - the reference to add.
+    pub fn add_external_ref(&mut self, external_ref: ExternalRef) {
+        match self.external_refs.iter_mut().find(|other| other.provider == external_ref.provider) {
+            Some(existing) => *existing = external_ref,
+            None => self.external_refs.push(external_ref),
         }
     }
 
+    /// Remove the reference to an external system.
+    ///
+    /// # Parameters
+    /// * `provider` - the name of the external system.
+    ///
+    /// # Returns
+    /// The removed reference, or `None` if the item has no reference to the provider.
+    pub fn remove_external_ref(&mut self, provider: &str) -> Option<ExternalRef> {
+        let idx = self.external_refs.iter().position(|other| other.provider == provider)?;
+        Some(self.external_refs.remove(idx))
+    }
+
     /// Calculate the monthly contributions for this item.
     ///
     /// # Returns
@@ -119,29 +623,147 @@ impl BudgetItem {
         };
 
         match self.item_type {
-            Type::Income => num,
-            Type::Expense => -num,
+            Kind::Income => num,
+            Kind::Expense => -num,
+        }
+    }
+
+    /// Calculate the yearly contribution of this item.
+    ///
+    /// The amount is multiplied by the number of times it occurs in a year, rather than the
+    /// monthly contribution by 12, so e.g. a quarterly 100 is exactly 400 a year.
+    ///
+    /// # Returns
+    /// The yearly contribution, which is negative for expenses. A contribution too large for an
+    /// `f64` is the largest finite value instead.
+    pub fn yearly_contribution(&self) -> f64 {
+        let num = saturate(self.amount * f64::from(self.period.occurrences_per_year()));
+        match self.item_type {
+            Kind::Income => num,
+            Kind::Expense => -num,
+        }
+    }
+
+    /// Calculate what this item contributes over a number of months, e.g. to check whether
+    /// something is affordable over the next 3 months.
+    ///
+    /// # Parameters
+    /// * `months` - the number of months.
+    ///
+    /// # Returns
+    /// The range of contributions, depending on how many payments of the item fall within the
+    /// months.
+    ///
+    /// ```
+    /// use rbp_core::budget_item::{BudgetItem, Period};
+    /// let water = BudgetItem::with_expense("Water", 100.0, Period::Every3Months);
+    /// let range = water.contribution_over(4);
+    /// assert_eq!(-200.0, range.lowest);
+    /// assert_eq!(-100.0, range.highest);
+    /// ```
+    pub fn contribution_over(&self, months: u16) -> ContributionRange {
+        let period = u16::from(self.period.months());
+        let fewest = saturate(f64::from(months / period) * self.amount);
+        let most = saturate(f64::from(months.div_ceil(period)) * self.amount);
+        match self.item_type {
+            Kind::Income => ContributionRange { lowest: fewest, highest: most },
+            Kind::Expense => ContributionRange { lowest: -most, highest: -fewest },
         }
     }
 
+    /// Calculate the monthly contributions for this item, rounded according to a policy.
+    ///
+    /// # Parameters
+    /// * `rounding` - how the contribution is rounded.
+    ///
+    /// # Returns
+    /// The rounded monthly contribution.
+    pub fn rounded_monthly_contribution(&self, rounding: RoundingPolicy) -> f64 {
+        rounding.round(self.monthly_contribution())
+    }
+
     fn check_amount(amount: &f64) {
-        assert!(*amount > 0.0, "Amount must be greater than 0");
+        assert!(Self::valid_amount(*amount), "Amount must be a finite number greater than 0");
+    }
+
+    // NaN fails the comparison, but infinity has to be checked separately.
+    fn valid_amount(amount: f64) -> bool {
+        amount.is_finite() && amount > 0.0
     }
 }
 
-impl PartialOrd for BudgetItem {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let name_cmp = self.name.partial_cmp(&other.name)?;
-        if name_cmp != Equal {
-            return Option::Some(name_cmp);
-        }
+impl BudgetItemBuilder {
 
-        let period_cmp = self.period.partial_cmp(&other.period)?;
-        if period_cmp != Equal {
-            return Option::Some(period_cmp);
-        }
+    /// Start building an item with the defaults of a budget, so that the period is the default
+    /// period unless another one is given.
+    ///
+    /// # Parameters
+    /// * `defaults` - the defaults of the budget the item is meant for.
+    pub fn with_defaults(defaults: &BudgetDefaults) -> BudgetItemBuilder {
+        BudgetItemBuilder { period: defaults.period, ..BudgetItem::builder() }
+    }
 
-        self.item_type.partial_cmp(&other.item_type)
+    /// Set the name of the item.
+    pub fn name(mut self, name: &str) -> BudgetItemBuilder {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Make the item an income, replacing an amount given before.
+    ///
+    /// # Parameters
+    /// * `amount` - the amount, which must be a finite number greater than 0.
+    pub fn income(mut self, amount: f64) -> BudgetItemBuilder {
+        self.amount = Some((Kind::Income, amount));
+        self
+    }
+
+    /// Make the item an expense, replacing an amount given before.
+    ///
+    /// # Parameters
+    /// * `amount` - the amount, which must be a finite number greater than 0.
+    pub fn expense(mut self, amount: f64) -> BudgetItemBuilder {
+        self.amount = Some((Kind::Expense, amount));
+        self
+    }
+
+    /// Set the recurring period of the item.
+    pub fn period(mut self, period: Period) -> BudgetItemBuilder {
+        self.period = period;
+        self
+    }
+
+    /// Add a reference to an external system, replacing one with the same provider, as
+    /// [`BudgetItem::add_external_ref`](struct.BudgetItem.html#method.add_external_ref) does.
+    pub fn external_ref(mut self, external_ref: ExternalRef) -> BudgetItemBuilder {
+        self.external_refs.retain(|existing| existing.provider != external_ref.provider);
+        self.external_refs.push(external_ref);
+        self
+    }
+
+    /// Build the item.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the item, or `Result::Err` if the name or amount is missing, or the amount
+    /// is invalid.
+    pub fn build(self) -> Result<BudgetItem, BuildError> {
+        let name = self.name.ok_or(BuildError::MissingName)?;
+        let (kind, amount) = self.amount.ok_or(BuildError::MissingAmount)?;
+        let mut item = BudgetItem::try_new(&name, kind, amount, self.period)?;
+        item.external_refs = self.external_refs;
+        Ok(item)
+    }
+}
+
+impl fmt::Display for BudgetItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(&FormatOptions::default()), f)
+    }
+}
+
+impl PartialOrd for BudgetItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Option::Some(self.cmp(other))
     }
 }
 
@@ -161,6 +783,8 @@ impl Ord for BudgetItem {
     }
 }
 
+/// Compares the name, period and kind of the items, which determine their order, but not the
+/// amount. Use [`BudgetItem::identical`] to compare the amounts as well.
 impl PartialEq for BudgetItem {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name && self.period == other.period && self.item_type == other.item_type
@@ -169,20 +793,34 @@ impl PartialEq for BudgetItem {
 
 impl Eq for BudgetItem { }
 
+/// Hashes the fields compared by `PartialEq`, i.e. not the amount, so equal items hash equally.
+impl Hash for BudgetItem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.period.hash(state);
+        self.item_type.hash(state);
+    }
+}
+
 impl Clone for BudgetItem {
     fn clone(&self) -> Self {
         BudgetItem{
             name: self.name.clone(),
             period: self.period,
             item_type: self.item_type,
-            amount: self.amount
+            amount: self.amount,
+            external_refs: self.external_refs.clone(),
+            id: self.id
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::budget_item::{BudgetItem, Period};
+    use core::convert::TryFrom;
+    use std::collections::HashSet;
+    use crate::budget::BudgetDefaults;
+    use crate::budget_item::{BudgetItem, BudgetItemBuilder, BuildError, ExternalRef, Kind, Period, RoundingPolicy, UnknownName};
     use crate::budget_item::Period::{Every1Month, Every12Months, Every2Months, Every3Months, Every6Months};
 
     #[test]
@@ -197,6 +835,18 @@ mod tests {
         BudgetItem::with_expense("Foo", -100.0, Period::Every1Month);
     }
 
+    #[test]
+    #[should_panic]
+    fn infinite_amount() {
+        BudgetItem::with_income("Foo", f64::INFINITY, Period::Every1Month);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_amount() {
+        BudgetItem::with_expense("Foo", f64::NAN, Period::Every1Month);
+    }
+
     #[test]
     fn monthly_contribution_for_income() {
         let mut amount = 42.0;
@@ -252,4 +902,154 @@ mod tests {
         let yearly_expense = BudgetItem::with_expense("12m", amount, Every12Months);
         assert_eq!(-amount / months, yearly_expense.monthly_contribution(), "Unexpected monthly contribution from yearly expense");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_period() {
+        let parse = |text: &str| text.parse::<Period>();
+        assert_eq!(Ok(Every1Month), parse("monthly"));
+        assert_eq!(Ok(Every1Month), parse(" Every Month "));
+        assert_eq!(Ok(Every2Months), parse("bimonthly"));
+        assert_eq!(Ok(Every2Months), parse("every 2 months"));
+        assert_eq!(Ok(Every3Months), parse("3M"));
+        assert_eq!(Ok(Every6Months), parse("half-yearly"));
+        assert_eq!(Ok(Every6Months), parse("6 months"));
+        assert_eq!(Ok(Every12Months), parse("every year"));
+        assert_eq!(Ok(Every12Months), parse("1y"));
+        assert_eq!(Ok(Every12Months), parse("12m"));
+        assert_eq!(Ok(Every12Months), Period::try_from("annually"));
+        for text in &["", "every", "weekly", "5m", "0m", "2y", "every 4 months", "m", "3 weeks",
+                      "400000000y", "400000000 years", "every 4294967295 years"] {
+            assert_eq!(Err(UnknownName { }), parse(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn period_utilities() {
+        assert_eq!(vec![1, 2, 3, 6, 12], Period::iter().map(Period::months).collect::<Vec<_>>());
+        assert_eq!(vec![12, 6, 4, 2, 1], Period::ALL.iter().map(|period| period.occurrences_per_year()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parse_kind() {
+        assert_eq!(Ok(Kind::Income), "Income".parse());
+        assert_eq!(Ok(Kind::Expense), Kind::try_from(" expense "));
+        assert_eq!(Err(UnknownName { }), "gift".parse::<Kind>());
+        assert_eq!("expense", Kind::Expense.to_string());
+        assert_eq!(Kind::Income, BudgetItem::with_income("Salary", 1.0, Every1Month).kind());
+    }
+
+    #[test]
+    fn identical() {
+        let mut rent = BudgetItem::with_expense("Rent", 900.0, Every1Month);
+        assert!(rent.identical(&BudgetItem::with_expense("Rent", 900.0, Every1Month)));
+        assert!(!rent.identical(&BudgetItem::with_expense("Rent", 900.5, Every1Month)));
+        assert!(!rent.identical(&BudgetItem::with_income("Rent", 900.0, Every1Month)));
+
+        let copy = rent.clone();
+        rent.add_external_ref(ExternalRef { provider: "bank".to_owned(), external_id: "1".to_owned(), url: None });
+        assert!(rent.identical(&copy));
+    }
+
+    #[test]
+    fn try_new() {
+        let item = BudgetItem::try_new("Salary", Kind::Income, 100.0, Every1Month).unwrap();
+        assert_eq!(100.0, item.monthly_contribution());
+        for amount in &[0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(BudgetItem::try_new("Salary", Kind::Income, *amount, Every1Month),
+                             Err(BuildError::InvalidAmount(_))));
+        }
+    }
+
+    #[test]
+    fn yearly_contribution() {
+        assert_eq!(1_200.0, BudgetItem::with_income("Salary", 100.0, Every1Month).yearly_contribution());
+        assert_eq!(-400.0, BudgetItem::with_expense("Water", 100.0, Every3Months).yearly_contribution());
+        assert_eq!(-1.5, BudgetItem::with_expense("Fee", 0.25, Every2Months).yearly_contribution());
+    }
+
+    #[test]
+    fn contribution_over() {
+        let range = |item: BudgetItem, months| {
+            let range = item.contribution_over(months);
+            (range.lowest, range.highest)
+        };
+        assert_eq!((300.0, 300.0), range(BudgetItem::with_income("Salary", 100.0, Every1Month), 3));
+        assert_eq!((100.0, 200.0), range(BudgetItem::with_income("Bonus", 100.0, Every3Months), 4));
+        assert_eq!((-200.0, -100.0), range(BudgetItem::with_expense("Water", 100.0, Every3Months), 5));
+        assert_eq!((-200.0, -200.0), range(BudgetItem::with_expense("Water", 100.0, Every3Months), 6));
+        assert_eq!((-100.0, -0.0), range(BudgetItem::with_expense("Insurance", 100.0, Every12Months), 11));
+        assert_eq!((0.0, 0.0), range(BudgetItem::with_income("Salary", 100.0, Every1Month), 0));
+        assert_eq!((-546_200.0, -546_100.0), range(BudgetItem::with_expense("Tax", 100.0, Every12Months), u16::MAX));
+    }
+
+    #[test]
+    fn rounding() {
+        let quarterly = BudgetItem::with_expense("Water", 100.0, Every3Months);
+        assert_eq!(-100.0 / 3.0, quarterly.rounded_monthly_contribution(RoundingPolicy::None));
+        assert_eq!(-33.33, quarterly.rounded_monthly_contribution(RoundingPolicy::HalfUp));
+        assert_eq!(-33.33, quarterly.rounded_monthly_contribution(RoundingPolicy::HalfEven));
+
+        assert_eq!(0.13, RoundingPolicy::HalfUp.round(0.125));
+        assert_eq!(0.12, RoundingPolicy::HalfEven.round(0.125));
+        assert_eq!(0.38, RoundingPolicy::HalfEven.round(0.375));
+        assert_eq!(2.0, RoundingPolicy::HalfEven.round(1.999));
+        assert_eq!(1e300, RoundingPolicy::HalfUp.round(1e300));
+        assert!(RoundingPolicy::HalfUp.round(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn hash() {
+        let mut items = HashSet::new();
+        assert!(items.insert(BudgetItem::with_expense("Rent", 900.0, Every1Month)));
+        assert!(!items.insert(BudgetItem::with_expense("Rent", 950.0, Every1Month)));
+        assert!(items.insert(BudgetItem::with_income("Rent", 900.0, Every1Month)));
+        assert!(items.insert(BudgetItem::with_expense("Rent", 900.0, Every2Months)));
+        assert_eq!(3, items.len());
+    }
+
+    #[test]
+    fn builder() {
+        let reference = |id: &str| ExternalRef { provider: "bank".to_owned(), external_id: id.to_owned(), url: None };
+        let item = BudgetItem::builder()
+            .name("Salary")
+            .expense(10.0)
+            .income(3_000.0)
+            .period(Every3Months)
+            .external_ref(reference("1"))
+            .external_ref(reference("2"))
+            .build()
+            .unwrap();
+        assert_eq!(BudgetItem::with_income("Salary", 3_000.0, Every3Months), item);
+        assert_eq!(1_000.0, item.monthly_contribution());
+        assert_eq!(&[reference("2")], item.external_refs());
+
+        assert_eq!(Every1Month, BudgetItem::builder().name("Rent").expense(1.0).build().unwrap().period());
+        assert_eq!(Some(BuildError::MissingName), BudgetItem::builder().expense(1.0).build().err());
+        assert_eq!(Some(BuildError::MissingAmount), BudgetItem::builder().name("Rent").build().err());
+        assert_eq!(Some(BuildError::InvalidAmount(-1.0)), BudgetItem::builder().name("Rent").expense(-1.0).build().err());
+        assert!(BudgetItem::builder().name("Rent").income(f64::NAN).build().is_err());
+    }
+
+    #[test]
+    fn builder_with_defaults() {
+        let defaults = BudgetDefaults { period: Every6Months, ..BudgetDefaults::default() };
+        let build = |builder: BudgetItemBuilder| builder.name("Insurance").expense(300.0).build().unwrap().period();
+
+        assert_eq!(Every6Months, build(BudgetItemBuilder::with_defaults(&defaults)));
+        assert_eq!(Every12Months, build(BudgetItemBuilder::with_defaults(&defaults).period(Every12Months)));
+    }
+
+    #[test]
+    fn external_refs() {
+        let reference = |provider: &str, id: &str| ExternalRef { provider: provider.to_owned(), external_id: id.to_owned(), url: None };
+        let mut item = BudgetItem::with_expense("Rent", 900.0, Every1Month);
+        item.add_external_ref(reference("bank", "1"));
+        item.add_external_ref(reference("invoices", "2"));
+        item.add_external_ref(reference("bank", "3"));
+
+        assert_eq!(&[reference("bank", "3"), reference("invoices", "2")], item.clone().external_refs());
+        assert_eq!(BudgetItem::with_expense("Rent", 900.0, Every1Month), item);
+        assert_eq!(Some(reference("bank", "3")), item.remove_external_ref("bank"));
+        assert_eq!(None, item.remove_external_ref("bank"));
+    }
+}
@@ -0,0 +1,140 @@
+use alloc::rc::Rc;
+use core::cell::Cell;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A point in time, expressed as whole seconds since the UNIX epoch (1970-01-01 00:00:00 UTC).
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Copy, Clone)]
+pub struct Timestamp(pub u64);
+
+impl Timestamp {
+
+    /// Get the number of seconds since the UNIX epoch.
+    pub fn seconds(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Source of the current time for every date-dependent feature in the crate.
+///
+/// Features never read the system time directly, but ask a clock instead. This allows consumers
+/// and tests to run against a fixed or simulated time by supplying a [`FixedClock`].
+pub trait Clock {
+
+    /// Get the current point in time, according to this clock.
+    fn now(&self) -> Timestamp;
+}
+
+/// Clock backed by the operating system's wall-clock time.
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        Timestamp(seconds)
+    }
+}
+
+/// Clock that always reports the same time, until it is explicitly moved.
+///
+/// The time is kept in a [`Cell`], so a clock that is shared through an [`Rc`] can still be moved
+/// by the test or simulation owning the other handle.
+///
+/// ```
+/// use rbp_core::clock::{Clock, FixedClock, Timestamp};
+/// let clock = FixedClock::new(Timestamp(1_000));
+/// clock.advance(60);
+/// assert_eq!(Timestamp(1_060), clock.now());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedClock {
+    time: Cell<Timestamp>,
+}
+
+impl FixedClock {
+
+    /// Create a new clock fixed at the given time.
+    ///
+    /// # Parameters
+    /// * `time` - the time the clock should report.
+    pub fn new(time: Timestamp) -> FixedClock {
+        FixedClock {
+            time: Cell::new(time)
+        }
+    }
+
+    /// Move the clock to a specific point in time.
+    ///
+    /// # Parameters
+    /// * `time` - the new time the clock should report.
+    pub fn set(&self, time: Timestamp) {
+        self.time.set(time);
+    }
+
+    /// Move the clock forward. A clock moved past the last representable time stops there.
+    ///
+    /// # Parameters
+    /// * `seconds` - the number of seconds to move the clock forward with.
+    pub fn advance(&self, seconds: u64) {
+        self.time.set(Timestamp(self.time.get().0.saturating_add(seconds)));
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> Timestamp {
+        self.time.get()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Rc<C> {
+    fn now(&self) -> Timestamp {
+        (**self).now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use crate::clock::{Clock, FixedClock, Timestamp};
+
+    #[test]
+    fn fixed_clock_is_fixed() {
+        let clock = FixedClock::new(Timestamp(42));
+        assert_eq!(Timestamp(42), clock.now());
+        assert_eq!(Timestamp(42), clock.now());
+    }
+
+    #[test]
+    fn fixed_clock_set_and_advance() {
+        let clock = FixedClock::new(Timestamp(0));
+        clock.set(Timestamp(100));
+        assert_eq!(Timestamp(100), clock.now());
+
+        clock.advance(50);
+        assert_eq!(Timestamp(150), clock.now());
+
+        clock.advance(u64::MAX);
+        assert_eq!(Timestamp(u64::MAX), clock.now());
+    }
+
+    #[test]
+    fn shared_clock_observes_changes() {
+        let clock = Rc::new(FixedClock::new(Timestamp(0)));
+        let shared: Rc<dyn Clock> = clock.clone();
+
+        clock.advance(10);
+        assert_eq!(Timestamp(10), shared.now());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn system_clock_is_after_epoch() {
+        assert!(crate::clock::SystemClock.now() > Timestamp(0));
+    }
+}
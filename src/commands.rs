@@ -0,0 +1,485 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{BudgetItem, Period};
+use crate::report::income_and_expenses;
+use crate::undo::Edit;
+
+/// A terse textual command, as typed into a command line or a chat.
+///
+/// Commands are parsed with [`Command::parse`] and applied to a budget with
+/// [`Command::execute`]. Words are separated by spaces, and names containing reserved words or
+/// surrounding spaces can be double quoted. The supported commands are:
+///
+/// * `add income <name> <amount> [<period>] to <group>`
+/// * `add expense <name> <amount> [<period>] to <group>`
+/// * `remove income <name> from <group>`
+/// * `remove expense <name> from <group>`
+/// * `add group <name>`
+/// * `remove group <name>`
+/// * `show total`, `show surplus`, `show income`, `show expenses`, `show group <name>`
+///
+/// The period is one of `monthly`, `bimonthly`, `quarterly`, `half-yearly` and `yearly`, or a
+/// short form like `3m` or `1y`. When it is left out, the budget's default period is used.
+/// Keywords are case insensitive.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::commands::{Command, Outcome};
+/// let mut budget = Budget::new("Home");
+/// budget.add_group(BudgetGroup::new("Housing"));
+///
+/// Command::parse("add income Salary 2000 to Housing").unwrap().execute(&mut budget).unwrap();
+/// Command::parse("add expense Rent 1200 monthly to Housing").unwrap().execute(&mut budget).unwrap();
+/// let surplus = Command::parse("show surplus").unwrap().execute(&mut budget).unwrap();
+/// assert_eq!(Outcome::Value(800.0), surplus);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Add an item to an existing group.
+    AddItem {
+        /// The name of the group the item is added to.
+        group: String,
+        /// The name of the item.
+        name: String,
+        /// The positive amount of the item.
+        amount: f64,
+        /// The period of the item, or `None` for the budget's default period.
+        period: Option<Period>,
+        /// Whether the item is an income, as opposed to an expense.
+        income: bool,
+    },
+    /// Remove an item from a group.
+    RemoveItem {
+        /// The name of the group the item is removed from.
+        group: String,
+        /// The name of the item.
+        name: String,
+        /// Whether the item is an income, as opposed to an expense.
+        income: bool,
+    },
+    /// Add an empty group to the end of the budget.
+    AddGroup(String),
+    /// Remove a group, including its items, from the budget.
+    RemoveGroup(String),
+    /// Look up a figure of the budget.
+    Show(Query),
+}
+
+/// A figure of a budget that can be looked up with a [`Command::Show`] command. All figures are
+/// monthly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// The net total of the budget, i.e. the surplus, or the shortfall if negative.
+    Total,
+    /// The total income.
+    Income,
+    /// The total expenses, as a positive number.
+    Expenses,
+    /// The net total of a group.
+    Group(String),
+}
+
+/// The result of a successfully executed command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The budget was changed as requested.
+    Done,
+    /// The value of the looked up figure.
+    Value(f64),
+}
+
+/// Error thrown when a command cannot be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The command holds no words.
+    Empty,
+    /// The command does not start with a known verb and object, e.g. `add expense`.
+    UnknownCommand(String),
+    /// A name is missing where one is expected.
+    MissingName,
+    /// The amount of an item is missing.
+    MissingAmount,
+    /// The amount of an item is not a positive number.
+    InvalidAmount(String),
+    /// A word that cannot appear at this point, e.g. an unknown period.
+    UnexpectedWord(String),
+    /// A double quoted name is not closed.
+    UnterminatedQuote,
+}
+
+/// Error thrown when a command cannot be applied to a budget.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecuteError {
+    /// No group has the name.
+    UnknownGroup(String),
+    /// The group has no item of the requested kind with the name.
+    UnknownItem(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no command given"),
+            ParseError::UnknownCommand(command) => write!(f, "unknown command \"{}\"", command),
+            ParseError::MissingName => write!(f, "a name is missing"),
+            ParseError::MissingAmount => write!(f, "the amount is missing"),
+            ParseError::InvalidAmount(amount) => write!(f, "\"{}\" is not a positive amount", amount),
+            ParseError::UnexpectedWord(word) => write!(f, "unexpected \"{}\"", word),
+            ParseError::UnterminatedQuote => write!(f, "a quoted name is not closed"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError { }
+
+impl fmt::Display for ExecuteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecuteError::UnknownGroup(name) => write!(f, "no group named \"{}\"", name),
+            ExecuteError::UnknownItem(name) => write!(f, "no item named \"{}\"", name),
+        }
+    }
+}
+
+impl core::error::Error for ExecuteError { }
+
+// A word of a command. Quoted words are never taken as keywords or amounts.
+struct Word {
+    text: String,
+    quoted: bool,
+}
+
+impl Word {
+    fn is(&self, keyword: &str) -> bool {
+        !self.quoted && self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+impl Command {
+
+    /// Parse a command.
+    ///
+    /// # Parameters
+    /// * `text` - the command text, e.g. `"add expense Rent 1200 monthly to Housing"`.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the parsed command, or `Result::Err` describing what is wrong.
+    pub fn parse(text: &str) -> Result<Command, ParseError> {
+        let words = split(text)?;
+        let (verb, object) = match (words.first(), words.get(1)) {
+            (None, _) => return Err(ParseError::Empty),
+            (Some(verb), None) => return Err(ParseError::UnknownCommand(verb.text.clone())),
+            (Some(verb), Some(object)) => (verb, object),
+        };
+        let rest = &words[2..];
+        let unknown = || ParseError::UnknownCommand(verb.text.clone() + " " + &object.text);
+
+        if verb.is("add") {
+            if object.is("group") {
+                Ok(Command::AddGroup(name(rest)?))
+            } else if object.is("income") || object.is("expense") {
+                parse_add_item(rest, object.is("income"))
+            } else {
+                Err(unknown())
+            }
+        } else if verb.is("remove") {
+            if object.is("group") {
+                Ok(Command::RemoveGroup(name(rest)?))
+            } else if object.is("income") || object.is("expense") {
+                let from = rest.iter().position(|word| word.is("from")).ok_or(ParseError::MissingName)?;
+                Ok(Command::RemoveItem {
+                    group: name(&rest[from + 1..])?,
+                    name: name(&rest[..from])?,
+                    income: object.is("income"),
+                })
+            } else {
+                Err(unknown())
+            }
+        } else if verb.is("show") {
+            if object.is("group") {
+                return Ok(Command::Show(Query::Group(name(rest)?)));
+            }
+            let query = if object.is("total") || object.is("surplus") {
+                Query::Total
+            } else if object.is("income") {
+                Query::Income
+            } else if object.is("expenses") {
+                Query::Expenses
+            } else {
+                return Err(unknown());
+            };
+            match rest.first() {
+                Some(word) => Err(ParseError::UnexpectedWord(word.text.clone())),
+                None => Ok(Command::Show(query)),
+            }
+        } else {
+            Err(ParseError::UnknownCommand(verb.text.clone()))
+        }
+    }
+
+    /// Apply the command to a budget.
+    ///
+    /// # Parameters
+    /// * `budget` - the budget to change or look up a figure in.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the outcome of the command, or `Result::Err` if a named group or item does
+    /// not exist. A failed command leaves the budget unchanged. Changes are applied as
+    /// [`Edit`]s, so they are recorded in the audit log of the budget and can be undone.
+    pub fn execute(&self, budget: &mut Budget) -> Result<Outcome, ExecuteError> {
+        match self {
+            Command::AddItem { group, name, amount, period, income } => {
+                let period = period.unwrap_or(budget.defaults().period);
+                let group_idx = budget.group_index(group).ok_or_else(|| ExecuteError::UnknownGroup(group.clone()))?;
+                let item = if *income {
+                    BudgetItem::with_income(name, *amount, period)
+                } else {
+                    BudgetItem::with_expense(name, *amount, period)
+                };
+                // The index was just found, so it is valid.
+                let _ = budget.apply(Edit::AddItem(group_idx, item));
+                Ok(Outcome::Done)
+            }
+            Command::RemoveItem { group, name, income } => {
+                let group_idx = budget.group_index(group).ok_or_else(|| ExecuteError::UnknownGroup(group.clone()))?;
+                let item_idx = budget.enumerate().nth(group_idx)
+                    .and_then(|(_, group)| group.enumerate()
+                        .find(|(_, item)| item.name() == name && item.is_income() == *income))
+                    .map(|(idx, _)| idx)
+                    .ok_or_else(|| ExecuteError::UnknownItem(name.clone()))?;
+                // The indices were just found, so they are valid.
+                let _ = budget.apply(Edit::RemoveItem(group_idx, item_idx));
+                Ok(Outcome::Done)
+            }
+            Command::AddGroup(name) => {
+                // Adding a group to the end cannot fail.
+                let _ = budget.apply(Edit::AddGroup(BudgetGroup::new(name)));
+                Ok(Outcome::Done)
+            }
+            Command::RemoveGroup(name) => {
+                let idx = budget.group_index(name).ok_or_else(|| ExecuteError::UnknownGroup(name.clone()))?;
+                // The index was just found, so it is valid.
+                let _ = budget.apply(Edit::RemoveGroup(idx));
+                Ok(Outcome::Done)
+            }
+            Command::Show(Query::Total) => Ok(Outcome::Value(budget.total())),
+            Command::Show(Query::Income) => Ok(Outcome::Value(income_and_expenses(budget).0)),
+            Command::Show(Query::Expenses) => Ok(Outcome::Value(-income_and_expenses(budget).1)),
+            Command::Show(Query::Group(name)) => budget.group(name)
+                .map(|group| Outcome::Value(group.rounded_total(budget.defaults().rounding)))
+                .ok_or_else(|| ExecuteError::UnknownGroup(name.clone())),
+        }
+    }
+}
+
+// Parse the words following `add income` or `add expense`: a name, an amount, an optional period,
+// `to`, and a group name. The name ends at the first unquoted word that is a number.
+fn parse_add_item(words: &[Word], income: bool) -> Result<Command, ParseError> {
+    let amount_idx = words.iter()
+        .position(|word| !word.quoted && word.text.parse::<f64>().is_ok())
+        .ok_or(ParseError::MissingAmount)?;
+    let amount_text = &words[amount_idx].text;
+    let amount: f64 = amount_text.parse().unwrap_or(0.0);
+    if !(amount.is_finite() && amount > 0.0) {
+        return Err(ParseError::InvalidAmount(amount_text.clone()));
+    }
+
+    let mut rest = words[amount_idx + 1..].iter();
+    let mut next = rest.next();
+    let period = match next.filter(|word| !word.is("to")) {
+        Some(word) => {
+            next = rest.next();
+            Some(period(word)?)
+        }
+        None => None,
+    };
+    match next {
+        Some(word) if word.is("to") => (),
+        Some(word) => return Err(ParseError::UnexpectedWord(word.text.clone())),
+        None => return Err(ParseError::MissingName),
+    }
+
+    Ok(Command::AddItem {
+        group: name(&words[words.len() - rest.len()..])?,
+        name: name(&words[..amount_idx])?,
+        amount,
+        period,
+        income,
+    })
+}
+
+// Read a period keyword or short form.
+fn period(word: &Word) -> Result<Period, ParseError> {
+    Some(word)
+        .filter(|word| !word.quoted)
+        .and_then(|word| word.text.parse().ok())
+        .ok_or_else(|| ParseError::UnexpectedWord(word.text.clone()))
+}
+
+// Join words into a name, which must not be empty.
+fn name(words: &[Word]) -> Result<String, ParseError> {
+    if words.is_empty() {
+        return Err(ParseError::MissingName);
+    }
+    Ok(words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "))
+}
+
+// Split a command into words at whitespace, keeping double quoted text together.
+fn split(text: &str) -> Result<Vec<Word>, ParseError> {
+    let mut words = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut word = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => word.push(c),
+                    None => return Err(ParseError::UnterminatedQuote),
+                }
+            }
+            words.push(Word { text: word, quoted: true });
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            words.push(Word { text: word, quoted: false });
+        }
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::commands::{Command, ExecuteError, Outcome, ParseError, Query};
+
+    fn budget() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_income("Salary", 2_000.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget
+    }
+
+    fn run(budget: &mut Budget, text: &str) -> Result<Outcome, ExecuteError> {
+        Command::parse(text).unwrap().execute(budget)
+    }
+
+    #[test]
+    fn parse_add_item() {
+        assert_eq!(Command::AddItem {
+            group: "Home and garden".to_owned(),
+            name: "Car insurance".to_owned(),
+            amount: 1_200.5,
+            period: Some(Period::Every6Months),
+            income: false,
+        }, Command::parse("add expense Car insurance 1200.5 half-yearly to Home and garden").unwrap());
+
+        assert_eq!(Command::AddItem {
+            group: "Income".to_owned(),
+            name: "Pay to me".to_owned(),
+            amount: 10.0,
+            period: None,
+            income: true,
+        }, Command::parse("ADD Income \"Pay to me\" 10 TO Income").unwrap());
+
+        assert!(matches!(Command::parse("add expense Water 90 3m to Housing").unwrap(),
+                         Command::AddItem { period: Some(Period::Every3Months), .. }));
+    }
+
+    #[test]
+    fn parse_other_commands() {
+        assert_eq!(Command::RemoveItem { group: "Housing".to_owned(), name: "Rent".to_owned(), income: false },
+                   Command::parse("remove expense Rent from Housing").unwrap());
+        assert_eq!(Command::AddGroup("Car 2".to_owned()), Command::parse("add group Car 2").unwrap());
+        assert_eq!(Command::RemoveGroup("Car".to_owned()), Command::parse("remove group Car").unwrap());
+        assert_eq!(Command::Show(Query::Total), Command::parse("show surplus").unwrap());
+        assert_eq!(Command::Show(Query::Expenses), Command::parse("  show   expenses ").unwrap());
+        assert_eq!(Command::Show(Query::Group("Housing".to_owned())), Command::parse("show group Housing").unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Err(ParseError::Empty), Command::parse(" "));
+        assert_eq!(Err(ParseError::UnknownCommand("delete".to_owned())), Command::parse("delete group Car"));
+        assert_eq!(Err(ParseError::UnknownCommand("show budget".to_owned())), Command::parse("show budget"));
+        assert_eq!(Err(ParseError::MissingAmount), Command::parse("add expense Rent to Housing"));
+        assert_eq!(Err(ParseError::InvalidAmount("-5".to_owned())), Command::parse("add expense Rent -5 to Housing"));
+        assert_eq!(Err(ParseError::UnexpectedWord("weekly".to_owned())), Command::parse("add expense Rent 5 weekly to Housing"));
+        assert_eq!(Err(ParseError::UnexpectedWord("400000000y".to_owned())), Command::parse("add expense Rent 5 400000000y to Housing"));
+        assert_eq!(Err(ParseError::MissingName), Command::parse("add expense Rent 5 monthly to"));
+        assert_eq!(Err(ParseError::MissingName), Command::parse("add group"));
+        assert_eq!(Err(ParseError::UnexpectedWord("now".to_owned())), Command::parse("show total now"));
+        assert_eq!(Err(ParseError::UnterminatedQuote), Command::parse("add group \"Car"));
+    }
+
+    #[test]
+    fn execute_changes() {
+        let mut budget = budget();
+        budget.set_defaults(BudgetDefaults { period: Period::Every3Months, ..BudgetDefaults::default() });
+
+        assert_eq!(Ok(Outcome::Done), run(&mut budget, "add group Car"));
+        assert_eq!(Ok(Outcome::Done), run(&mut budget, "add expense Fuel 300 to Car"));
+        assert_eq!(Ok(Outcome::Value(-100.0)), run(&mut budget, "show group Car"));
+        assert_eq!(Ok(Outcome::Done), run(&mut budget, "remove expense Rent from Housing"));
+        assert_eq!(Ok(Outcome::Value(2_000.0)), run(&mut budget, "show group Housing"));
+        assert_eq!(Ok(Outcome::Done), run(&mut budget, "remove group Car"));
+        assert_eq!(1, budget.enumerate().len());
+    }
+
+    #[test]
+    fn execute_can_be_undone() {
+        let mut budget = budget();
+        run(&mut budget, "remove expense Rent from Housing").unwrap();
+        run(&mut budget, "add group Car").unwrap();
+
+        assert!(budget.undo());
+        assert!(budget.undo());
+        assert_eq!(1, budget.enumerate().len());
+        assert_eq!(Ok(Outcome::Value(1_100.0)), run(&mut budget, "show total"));
+    }
+
+    #[test]
+    fn execute_queries() {
+        let mut budget = budget();
+
+        assert_eq!(Ok(Outcome::Value(1_100.0)), run(&mut budget, "show total"));
+        assert_eq!(Ok(Outcome::Value(2_000.0)), run(&mut budget, "show income"));
+        assert_eq!(Ok(Outcome::Value(900.0)), run(&mut budget, "show expenses"));
+
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+        for name in &["Dividends", "Interest", "Bonus"] {
+            run(&mut budget, &format!("add income {} 10 quarterly to Housing", name)).unwrap();
+        }
+        assert_eq!(Ok(Outcome::Value(1_109.99)), run(&mut budget, "show total"));
+        assert_eq!(Ok(Outcome::Value(1_109.99)), run(&mut budget, "show group Housing"));
+        assert_eq!(Ok(Outcome::Value(2_009.99)), run(&mut budget, "show income"));
+    }
+
+    #[test]
+    fn execute_unknown_names() {
+        let mut budget = budget();
+
+        assert_eq!(Err(ExecuteError::UnknownGroup("Car".to_owned())), run(&mut budget, "add expense Fuel 300 to Car"));
+        assert_eq!(Err(ExecuteError::UnknownItem("Rent".to_owned())), run(&mut budget, "remove income Rent from Housing"));
+        assert_eq!(Err(ExecuteError::UnknownGroup("Car".to_owned())), run(&mut budget, "remove group Car"));
+        assert_eq!(Err(ExecuteError::UnknownGroup("Car".to_owned())), run(&mut budget, "show group Car"));
+        assert_eq!(Ok(Outcome::Value(1_100.0)), run(&mut budget, "show total"));
+    }
+}
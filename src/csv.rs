@@ -0,0 +1,123 @@
+use std::io;
+use std::io::Write;
+
+/// Error returned when a CSV document contains a quoted field that is never closed.
+#[derive(Debug, PartialEq)]
+pub struct UnterminatedQuote {
+    /// The 1-based line on which the unterminated field starts.
+    pub line: usize,
+}
+
+/// A single CSV record, together with the 1-based line number it starts on.
+pub struct Record {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// Split a CSV document into records of fields.
+///
+/// Fields may be quoted with `"`, in which case they can contain separators, line breaks and
+/// escaped quotes (`""`). Empty lines are skipped, and a leading byte order mark is ignored.
+pub fn parse(text: &str) -> Result<Vec<Record>, UnterminatedQuote> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => quoted = true,
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                push_record(&mut records, record_line, std::mem::take(&mut fields));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(UnterminatedQuote { line: record_line });
+    }
+    fields.push(field);
+    push_record(&mut records, record_line, fields);
+    Ok(records)
+}
+
+// Add a record, unless it stems from an empty line.
+fn push_record(records: &mut Vec<Record>, line: usize, fields: Vec<String>) {
+    if fields.len() == 1 && fields[0].is_empty() {
+        return;
+    }
+    records.push(Record { line, fields });
+}
+
+/// Write a single CSV record, quoting the fields that need it.
+pub fn write_record<W: Write, S: AsRef<str>>(writer: &mut W, fields: &[S]) -> io::Result<()> {
+    for (idx, field) in fields.iter().enumerate() {
+        if idx > 0 {
+            writer.write_all(b",")?;
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csv::{parse, write_record, UnterminatedQuote};
+
+    #[test]
+    fn parse_plain_and_quoted() {
+        let records = parse("a,b\r\n\"c, d\",\"e \"\"f\"\"\"\n\nlast,").unwrap();
+
+        assert_eq!(3, records.len());
+        assert_eq!(vec!["a", "b"], records[0].fields);
+        assert_eq!(vec!["c, d", "e \"f\""], records[1].fields);
+        assert_eq!(vec!["last", ""], records[2].fields);
+        assert_eq!(4, records[2].line);
+    }
+
+    #[test]
+    fn parse_unterminated_quote() {
+        assert_eq!(UnterminatedQuote { line: 2 }, parse("a\n\"b,c\n").err().unwrap());
+    }
+
+    #[test]
+    fn write_round_trip() {
+        let mut out = Vec::new();
+        write_record(&mut out, &["plain", "with,comma", "with \"quote\""]).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!("plain,\"with,comma\",\"with \"\"quote\"\"\"\r\n", text);
+        assert_eq!(vec!["plain", "with,comma", "with \"quote\""], parse(&text).unwrap()[0].fields);
+    }
+}
@@ -0,0 +1,177 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::audit::Change;
+use crate::budget::Budget;
+use crate::budget_item::{saturating_sum, RoundingPolicy};
+
+/// What to delete from a budget with
+/// [`Budget::delete`](../budget/struct.Budget.html#method.delete).
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Selector {
+    /// Every group with a name, including all of its items.
+    Group(String),
+    /// Every item with a name, in every group with a name.
+    Item {
+        /// The name of the group.
+        group: String,
+        /// The name of the item.
+        item: String,
+    },
+}
+
+/// Everything a deletion removes from a budget, as reported by
+/// [`Budget::preview_delete`](../budget/struct.Budget.html#method.preview_delete) before the
+/// deletion, and by [`Budget::delete`](../budget/struct.Budget.html#method.delete) after it.
+#[derive(Debug, Clone)]
+pub struct DeleteImpact {
+    changes: Vec<Change>,
+    rounding: RoundingPolicy,
+}
+
+impl DeleteImpact {
+
+    /// Get the removals, in the order of the budget. A removed group is preceded by the removal
+    /// of each of its items, so that a frontend can list everything that is lost.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Check whether nothing matched the selector.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Calculate how the monthly total of the budget changes by the deletion, with the
+    /// contributions rounded by the rounding policy of the budget, like
+    /// [`Budget::total`](../budget/struct.Budget.html#method.total).
+    ///
+    /// # Returns
+    /// The change, which is positive if more expenses than income are removed.
+    pub fn total_change(&self) -> f64 {
+        let rounding = self.rounding;
+        rounding.round(saturating_sum(self.changes.iter().map(|change| match change {
+            Change::ItemRemoved { item, .. } => -item.rounded_monthly_contribution(rounding),
+            _ => 0.0,
+        })))
+    }
+}
+
+// Find what a selector matches, as described on `DeleteImpact`.
+pub(crate) fn preview(budget: &Budget, selector: &Selector) -> DeleteImpact {
+    let mut changes = Vec::new();
+    for (_, group) in budget.enumerate() {
+        match selector {
+            Selector::Group(name) if group.name() == name => {
+                for (_, item) in group.enumerate() {
+                    changes.push(Change::ItemRemoved { group: group.name().clone(), item: item.clone() });
+                }
+                changes.push(Change::GroupRemoved { group: group.name().clone() });
+            }
+            Selector::Item { group: group_name, item: item_name } if group.name() == group_name => {
+                for (_, item) in group.enumerate().filter(|(_, item)| item.name() == item_name) {
+                    changes.push(Change::ItemRemoved { group: group.name().clone(), item: item.clone() });
+                }
+            }
+            _ => (),
+        }
+    }
+    DeleteImpact { changes, rounding: budget.defaults().rounding }
+}
+
+// Delete what a selector matches, returning what was removed.
+pub(crate) fn delete(budget: &mut Budget, selector: &Selector) -> DeleteImpact {
+    let impact = preview(budget, selector);
+    match selector {
+        Selector::Group(name) => {
+            while let Some(idx) = budget.group_index(name) {
+                let _ = budget.remove_group(idx);
+            }
+        }
+        Selector::Item { group: group_name, item: item_name } => {
+            let groups: Vec<usize> = budget.enumerate()
+                .filter(|(_, group)| group.name() == group_name)
+                .map(|(idx, _)| idx)
+                .collect();
+            for group_idx in groups {
+                // Remove from the back, so that the indices of the remaining matches stay valid.
+                let items: Vec<usize> = budget.enumerate().nth(group_idx).map(|(_, group)| group.enumerate()
+                    .filter(|(_, item)| item.name() == item_name)
+                    .map(|(idx, _)| idx)
+                    .collect()).unwrap_or_default();
+                for item_idx in items.into_iter().rev() {
+                    let _ = budget.remove_item(group_idx, item_idx);
+                }
+            }
+        }
+    }
+    impact
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::Change;
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::delete::Selector;
+
+    fn budget() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Insurance", 240.0, Period::Every12Months));
+        housing.add(BudgetItem::with_expense("Insurance", 60.0, Period::Every3Months));
+        let mut salary = BudgetGroup::new("Salary");
+        salary.add(BudgetItem::with_income("Job", 3_000.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(salary);
+        budget
+    }
+
+    #[test]
+    fn preview_group() {
+        let budget = budget();
+        let impact = budget.preview_delete(&Selector::Group("Housing".to_owned()));
+
+        assert_eq!(4, impact.changes().len());
+        assert!(matches!(&impact.changes()[3], Change::GroupRemoved { group } if group == "Housing"));
+        assert_eq!(940.0, impact.total_change());
+        assert_eq!(2, budget.enumerate().len());
+    }
+
+    #[test]
+    fn delete_items() {
+        let mut budget = budget();
+        let selector = Selector::Item { group: "Housing".to_owned(), item: "Insurance".to_owned() };
+        let preview = budget.preview_delete(&selector);
+        let impact = budget.delete(&selector);
+
+        assert_eq!(2, impact.changes().len());
+        assert_eq!(preview.total_change(), impact.total_change());
+        assert_eq!(1, budget.group("Housing").unwrap().enumerate().len());
+        assert_eq!(2_100.0, budget.total());
+    }
+
+    #[test]
+    fn delete_group() {
+        let mut budget = budget();
+        let impact = budget.delete(&Selector::Group("Salary".to_owned()));
+
+        assert_eq!(-3_000.0, impact.total_change());
+        assert!(budget.group("Salary").is_none());
+        assert!(budget.delete(&Selector::Group("Salary".to_owned())).is_empty());
+    }
+
+    #[test]
+    fn rounded_total_change() {
+        let mut budget = budget();
+        budget.add_item(0, BudgetItem::with_expense("Water", 100.0, Period::Every3Months)).unwrap();
+        budget.add_item(0, BudgetItem::with_expense("Sewage", 0.25, Period::Every2Months)).unwrap();
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+        let before = budget.total();
+
+        let impact = budget.delete(&Selector::Group("Housing".to_owned()));
+        assert_eq!(973.46, impact.total_change());
+        assert_eq!(before + impact.total_change(), budget.total());
+    }
+}
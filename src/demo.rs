@@ -0,0 +1,211 @@
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{BudgetItem, Period};
+
+/// Settings controlling the size and content of a generated demo budget.
+///
+/// Generation is fully deterministic: the same configuration always produces the same budget.
+#[derive(Debug, Copy, Clone)]
+pub struct DemoConfig {
+    /// The seed of the random generator used to pick names, amounts and periods.
+    pub seed: u64,
+    /// The number of expense groups to generate, in addition to the single income group.
+    pub groups: usize,
+    /// The number of items to generate in each expense group.
+    pub items_per_group: usize,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        DemoConfig {
+            seed: 1,
+            groups: 5,
+            items_per_group: 4
+        }
+    }
+}
+
+// Template for a generated item: name, lowest amount, highest amount, and period.
+type ItemTemplate = (&'static str, f64, f64, Period);
+
+const INCOME: &[ItemTemplate] = &[
+    ("Salary", 2_500.0, 4_500.0, Period::Every1Month),
+    ("Partner salary", 2_000.0, 4_000.0, Period::Every1Month),
+    ("Child benefit", 300.0, 900.0, Period::Every3Months),
+    ("Bonus", 1_000.0, 5_000.0, Period::Every12Months),
+];
+
+const EXPENSES: &[(&str, &[ItemTemplate])] = &[
+    ("Housing", &[
+        ("Rent", 700.0, 1_600.0, Period::Every1Month),
+        ("Electricity", 60.0, 180.0, Period::Every1Month),
+        ("Water", 90.0, 240.0, Period::Every3Months),
+        ("Home insurance", 150.0, 450.0, Period::Every12Months),
+        ("Heating", 150.0, 400.0, Period::Every2Months),
+    ]),
+    ("Transport", &[
+        ("Fuel", 80.0, 250.0, Period::Every1Month),
+        ("Car insurance", 300.0, 900.0, Period::Every6Months),
+        ("Public transport pass", 40.0, 90.0, Period::Every1Month),
+        ("Car service", 200.0, 600.0, Period::Every12Months),
+    ]),
+    ("Food", &[
+        ("Groceries", 300.0, 800.0, Period::Every1Month),
+        ("Lunch at work", 60.0, 160.0, Period::Every1Month),
+        ("Takeaway", 30.0, 120.0, Period::Every1Month),
+    ]),
+    ("Subscriptions", &[
+        ("Streaming", 8.0, 20.0, Period::Every1Month),
+        ("Mobile phone", 15.0, 45.0, Period::Every1Month),
+        ("Internet", 25.0, 60.0, Period::Every1Month),
+        ("Newspaper", 90.0, 200.0, Period::Every6Months),
+        ("Gym", 25.0, 50.0, Period::Every1Month),
+    ]),
+    ("Savings", &[
+        ("Emergency fund", 100.0, 400.0, Period::Every1Month),
+        ("Pension", 150.0, 500.0, Period::Every1Month),
+        ("Holiday", 500.0, 1_500.0, Period::Every6Months),
+    ]),
+    ("Leisure", &[
+        ("Hobbies", 20.0, 120.0, Period::Every1Month),
+        ("Clothing", 150.0, 400.0, Period::Every3Months),
+        ("Gifts", 300.0, 900.0, Period::Every12Months),
+    ]),
+];
+
+/// Generate a realistic-looking synthetic budget.
+///
+/// The budget always starts with an "Income" group, followed by the configured number of expense
+/// groups. Group and item names are drawn from a catalogue of common household entries; when more
+/// groups or items are requested than the catalogue holds, the names are repeated with a numbered
+/// suffix, so generated budgets can be made arbitrarily large for load testing.
+///
+/// # Parameters
+/// * `config` - the size and seed of the generated budget.
+///
+/// # Returns
+/// The generated budget.
+///
+/// ```
+/// use rbp_core::demo::{generate, DemoConfig};
+/// let budget = generate(&DemoConfig::default());
+/// assert_eq!(6, budget.enumerate().len());
+/// ```
+pub fn generate(config: &DemoConfig) -> Budget {
+    let mut rng = Rng::new(config.seed);
+    let mut budget = Budget::new("Demo budget");
+
+    let mut income = BudgetGroup::new("Income");
+    let income_count = 1 + rng.below(INCOME.len());
+    for template in INCOME.iter().take(income_count) {
+        income.add(BudgetItem::with_income(template.0, rng.amount(template.1, template.2), template.3));
+    }
+    budget.add_group(income);
+
+    let offset = rng.below(EXPENSES.len());
+    for group_idx in 0..config.groups {
+        let (group_name, templates) = EXPENSES[(offset + group_idx) % EXPENSES.len()];
+        let mut group = BudgetGroup::new(&numbered(group_name, group_idx / EXPENSES.len()));
+
+        for item_idx in 0..config.items_per_group {
+            let template = templates[item_idx % templates.len()];
+            let name = numbered(template.0, item_idx / templates.len());
+            group.add(BudgetItem::with_expense(&name, rng.amount(template.1, template.2), template.3));
+        }
+        budget.add_group(group);
+    }
+
+    budget
+}
+
+// Append a repetition number to a name, unless it is the first occurrence.
+fn numbered(name: &str, repetition: usize) -> String {
+    if repetition == 0 {
+        name.to_owned()
+    } else {
+        format!("{} {}", name, repetition + 1)
+    }
+}
+
+// Small xorshift64* generator. Good enough for demo data, and avoids pulling in a dependency.
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        const MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+        // A zero state would make xorshift return zeroes forever, and the one seed that mixes to
+        // zero gets the mixing constant instead.
+        let state = seed ^ MIX;
+        Rng { state: if state == 0 { MIX } else { state } }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    // A whole-cent amount in the range `[low, high]`.
+    fn amount(&mut self, low: f64, high: f64) -> f64 {
+        let fraction = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        // Rounded by truncation, as `f64::round` needs the standard library. Amounts are positive.
+        ((low + fraction * (high - low)) * 100.0 + 0.5) as u64 as f64 / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::demo::{generate, DemoConfig, Rng};
+
+    #[test]
+    fn configured_size() {
+        let config = DemoConfig { seed: 7, groups: 10, items_per_group: 12 };
+        let budget = generate(&config);
+
+        assert_eq!(11, budget.enumerate().len());
+        for (_, group) in budget.enumerate().skip(1) {
+            assert_eq!(12, group.enumerate().len(), "Unexpected item count in {}", group.name());
+        }
+    }
+
+    #[test]
+    fn same_seed_same_budget() {
+        let config = DemoConfig { seed: 42, ..DemoConfig::default() };
+        assert_eq!(generate(&config).total(), generate(&config).total());
+    }
+
+    #[test]
+    fn different_seed_different_budget() {
+        let first = generate(&DemoConfig { seed: 1, ..DemoConfig::default() });
+        let second = generate(&DemoConfig { seed: 2, ..DemoConfig::default() });
+        assert_ne!(first.total(), second.total());
+    }
+
+    #[test]
+    fn no_seed_gets_stuck() {
+        let mut rng = Rng::new(0x9E37_79B9_7F4A_7C15);
+        let first = rng.next();
+        assert_ne!(0, first);
+        assert_ne!(first, rng.next());
+        assert!(generate(&DemoConfig { seed: 0x9E37_79B9_7F4A_7C15, ..DemoConfig::default() }).total() != 0.0);
+    }
+
+    #[test]
+    fn starts_with_income() {
+        let budget = generate(&DemoConfig::default());
+        let (_, income) = budget.enumerate().next().unwrap();
+
+        assert_eq!("Income", income.name());
+        assert!(income.total() > 0.0);
+    }
+}
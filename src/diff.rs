@@ -0,0 +1,179 @@
+use alloc::vec::Vec;
+use crate::audit::Change;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::BudgetItem;
+
+/// The differences between two budgets, as the changes that turn the first budget into the
+/// second.
+///
+/// Groups are matched by name, and items within a group by name and kind (income or expense).
+/// A matched item with another amount or period is reported as updated. A group that only exists
+/// in the second budget is reported as added, followed by each of its items; a group that only
+/// exists in the first budget is reported as removed, which includes its items. Renames cannot be
+/// told apart from a removal and an addition, so they are reported as such.
+///
+/// ```
+/// use rbp_core::audit::Change;
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// let mut group = BudgetGroup::new("Housing");
+/// group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+/// let mut last_month = Budget::new("Home");
+/// last_month.add_group(group);
+///
+/// let mut group = BudgetGroup::new("Housing");
+/// group.add(BudgetItem::with_expense("Rent", 950.0, Period::Every1Month));
+/// let mut this_month = Budget::new("Home");
+/// this_month.add_group(group);
+///
+/// let diff = last_month.diff(&this_month);
+/// assert!(matches!(&diff.changes()[0], Change::ItemUpdated { to, .. } if to.amount() == 950.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BudgetDiff {
+    changes: Vec<Change>,
+}
+
+impl BudgetDiff {
+
+    /// Get the changes, in the order of the groups and items of the first budget, followed by
+    /// what only exists in the second budget.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Check whether the budgets have the same groups and items.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+// Compare two budgets, as described on `BudgetDiff`.
+pub(crate) fn diff(mine: &Budget, theirs: &Budget) -> BudgetDiff {
+    let mut changes = Vec::new();
+    let mut unmatched: Vec<&BudgetGroup> = theirs.enumerate().map(|(_, group)| group).collect();
+
+    // The number of groups after the changes so far, as groups are added to the end.
+    let mut count = mine.enumerate().len();
+    for (_, group) in mine.enumerate() {
+        match unmatched.iter().position(|other| other.name() == group.name()) {
+            Some(idx) => diff_items(group, unmatched.remove(idx), &mut changes),
+            None => {
+                changes.push(Change::GroupRemoved { group: group.name().clone() });
+                count -= 1;
+            }
+        }
+    }
+    for group in unmatched {
+        changes.push(Change::GroupAdded { group: group.name().clone(), index: count });
+        count += 1;
+        for (_, item) in group.enumerate() {
+            changes.push(Change::ItemAdded { group: group.name().clone(), item: item.clone() });
+        }
+    }
+    BudgetDiff { changes }
+}
+
+// Compare the items of two groups with the same name.
+fn diff_items(mine: &BudgetGroup, theirs: &BudgetGroup, changes: &mut Vec<Change>) {
+    let mut unmatched: Vec<&BudgetItem> = theirs.enumerate().map(|(_, item)| item).collect();
+
+    // Pair up the unchanged items first, so that a changed duplicate is not paired with the
+    // unchanged one.
+    let mut changed = Vec::new();
+    for (_, item) in mine.enumerate() {
+        match unmatched.iter().position(|other| other.identical(item)) {
+            Some(idx) => { unmatched.remove(idx); }
+            None => changed.push(item),
+        }
+    }
+
+    for item in changed {
+        let group = mine.name().clone();
+        match unmatched.iter().position(|other| other.name() == item.name() && other.is_income() == item.is_income()) {
+            Some(idx) => changes.push(Change::ItemUpdated { group, from: item.clone(), to: unmatched.remove(idx).clone() }),
+            None => changes.push(Change::ItemRemoved { group, item: item.clone() }),
+        }
+    }
+    for item in unmatched {
+        changes.push(Change::ItemAdded { group: mine.name().clone(), item: item.clone() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::webhook::event_type;
+
+    // A group name, and the name, amount and period of each of its expenses.
+    type Group<'a> = (&'a str, &'a [(&'a str, f64, Period)]);
+
+    fn budget(groups: &[Group<'_>]) -> Budget {
+        let mut budget = Budget::new("Home");
+        for (name, items) in groups {
+            let mut group = BudgetGroup::new(name);
+            for (item, amount, period) in items.iter() {
+                group.add(BudgetItem::with_expense(item, *amount, *period));
+            }
+            budget.add_group(group);
+        }
+        budget
+    }
+
+    // The diff as one line per change: its webhook event type, followed by its debug output.
+    fn describe(mine: &Budget, theirs: &Budget) -> Vec<String> {
+        mine.diff(theirs).changes().iter().map(|change| format!("{} {:?}", event_type(change), change)).collect()
+    }
+
+    #[test]
+    fn equal_budgets() {
+        let groups: &[Group<'_>] = &[("Housing", &[("Rent", 900.0, Period::Every1Month)])];
+        assert!(budget(groups).diff(&budget(groups)).is_empty());
+    }
+
+    #[test]
+    fn group_changes() {
+        let mine = budget(&[("Housing", &[]), ("Food", &[])]);
+        let theirs = budget(&[("Food", &[]), ("Car", &[("Fuel", 50.0, Period::Every1Month)])]);
+
+        let changes = describe(&mine, &theirs);
+        assert_eq!(3, changes.len());
+        assert!(changes[0].starts_with("budget.group.removed GroupRemoved { group: \"Housing\""));
+        assert!(changes[1].starts_with("budget.group.added GroupAdded { group: \"Car\""));
+        assert!(changes[2].starts_with("budget.item.added ItemAdded { group: \"Car\", item: BudgetItem { name: \"Fuel\""));
+    }
+
+    #[test]
+    fn item_changes() {
+        let mine = budget(&[("Housing", &[
+            ("Rent", 900.0, Period::Every1Month),
+            ("Water", 30.0, Period::Every1Month),
+            ("Heating", 100.0, Period::Every1Month),
+        ])]);
+        let theirs = budget(&[("Housing", &[
+            ("Rent", 900.0, Period::Every1Month),
+            ("Water", 90.0, Period::Every3Months),
+            ("Insurance", 200.0, Period::Every12Months),
+        ])]);
+
+        let diff = mine.diff(&theirs);
+        let types: Vec<&str> = diff.changes().iter().map(event_type).collect();
+        assert_eq!(vec!["budget.item.removed", "budget.item.updated", "budget.item.added"], types);
+        assert_eq!(mine.diff(&theirs).changes().len(), theirs.diff(&mine).changes().len());
+    }
+
+    #[test]
+    fn unchanged_duplicates_are_paired_first() {
+        let mine = budget(&[("Food", &[("Lunch", 10.0, Period::Every1Month), ("Lunch", 20.0, Period::Every1Month)])]);
+        let theirs = budget(&[("Food", &[("Lunch", 20.0, Period::Every1Month), ("Lunch", 15.0, Period::Every1Month)])]);
+
+        let changes = describe(&mine, &theirs);
+        assert_eq!(1, changes.len());
+        assert!(changes[0].contains("amount: 10.0"));
+        assert!(changes[0].contains("amount: 15.0"));
+    }
+}
@@ -0,0 +1,414 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use crate::budget::{Budget, BudgetDefaults};
+use crate::budget_group::{BudgetGroup, ItemOrder};
+use crate::budget_item::{BudgetItem, ExternalRef, Period, RoundingPolicy};
+use crate::locale::Locale;
+use crate::migrations::{self, MigrationError};
+
+/// The first word of every document, identifying it as a budget.
+pub(crate) const MAGIC: &str = "rbp-budget";
+
+/// The version of the document format written by [`to_string`].
+pub const VERSION: u32 = 1;
+
+/// Error thrown when a document cannot be read. Every variant with a line number holds the
+/// 1-based line where the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The document does not start with the budget header.
+    MissingHeader,
+    /// The document is written in a format version this crate does not know. Holds the version.
+    UnsupportedVersion(String),
+    /// A line is not one of the known entries, or has the wrong number of fields.
+    InvalidLine(usize),
+    /// An amount is not a positive number. Holds the line and the offending value.
+    InvalidAmount(usize, String),
+    /// An amount is infinite or not a number, e.g. `inf`, `NaN`, or too large to be represented.
+    /// Holds the line and the offending value.
+    NonFiniteAmount(usize, String),
+    /// A period is not one of the known periods. Holds the line and the offending value.
+    InvalidPeriod(usize, String),
+    /// An item appears before the first group.
+    ItemOutsideGroup(usize),
+    /// The document is written in an older format version, and could not be upgraded.
+    Migration(MigrationError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingHeader => write!(f, "not a budget document"),
+            ParseError::UnsupportedVersion(version) => write!(f, "unsupported document version \"{}\"", version),
+            ParseError::InvalidLine(line) => write!(f, "invalid entry on line {}", line),
+            ParseError::InvalidAmount(line, value) => write!(f, "invalid amount \"{}\" on line {}", value, line),
+            ParseError::NonFiniteAmount(line, value) => write!(f, "amount \"{}\" on line {} is not a finite number", value, line),
+            ParseError::InvalidPeriod(line, value) => write!(f, "invalid period \"{}\" on line {}", value, line),
+            ParseError::ItemOutsideGroup(line) => write!(f, "item outside of a group on line {}", line),
+            ParseError::Migration(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl core::error::Error for ParseError { }
+
+/// Write a budget as a document in the crate's own text format.
+///
+/// The format is lossless: reading the document back with [`from_str`] gives an equal budget,
+/// including its defaults. Only the budget data is kept, not its undo history, audit log or
+/// subscribers.
+///
+/// The document is a header line followed by one entry per line, with tab separated fields.
+/// Tabs, line breaks and backslashes in names are escaped with a backslash (`\t`, `\n`, `\r`
+/// and `\\`).
+///
+/// For example, with the tabs shown as spaces:
+///
+/// ```text
+/// rbp-budget  1
+/// name        Home
+/// currency    €
+/// period      monthly
+/// group       Housing
+/// item        expense  Rent  900  monthly
+/// ref         bank     42    https://bank.example/42
+/// ```
+///
+/// A `rounding` entry follows the period if the budget rounds its contributions, and a `locale`
+/// entry with the decimal and thousands separators if the budget has a locale. The thousands
+/// separator is empty if the locale has none.
+/// A `ref` entry holds an external reference of the item before it, with an empty link if the
+/// reference has none. A group with manually ordered items is followed by an `order manual` entry,
+/// and its items are written in their order.
+///
+/// # Parameters
+/// * `budget` - the budget to write.
+///
+/// # Returns
+/// The document text.
+pub fn to_string(budget: &Budget) -> String {
+    let mut doc = String::new();
+    // Writing to a String cannot fail.
+    let _ = writeln!(doc, "{}\t{}", MAGIC, VERSION);
+    let _ = writeln!(doc, "name\t{}", escape(budget.name()));
+    let _ = writeln!(doc, "currency\t{}", escape(&budget.defaults().currency));
+    let _ = writeln!(doc, "period\t{}", budget.defaults().period.keyword());
+    if budget.defaults().rounding != RoundingPolicy::None {
+        let _ = writeln!(doc, "rounding\t{}", budget.defaults().rounding.keyword());
+    }
+    if let Some(locale) = budget.defaults().locale {
+        let _ = writeln!(doc, "locale\t{}\t{}", escape(&locale.decimal_separator.to_string()), escape(&locale.thousands_text()));
+    }
+    for (_, group) in budget.enumerate() {
+        let _ = writeln!(doc, "group\t{}", escape(group.name()));
+        if group.order() == ItemOrder::Manual {
+            let _ = writeln!(doc, "order\tmanual");
+        }
+        for (_, item) in group.enumerate() {
+            let _ = writeln!(doc, "item\t{}\t{}\t{}\t{}",
+                             if item.is_income() { "income" } else { "expense" },
+                             escape(item.name()),
+                             item.amount(),
+                             item.period().keyword());
+            for external_ref in item.external_refs() {
+                let _ = writeln!(doc, "ref\t{}\t{}\t{}",
+                                 escape(&external_ref.provider),
+                                 escape(&external_ref.external_id),
+                                 escape(external_ref.url.as_deref().unwrap_or("")));
+            }
+        }
+    }
+    doc
+}
+
+/// Read a budget from a document written by [`to_string`].
+///
+/// Documents written in an older format version are upgraded with the
+/// [`migrations`](../migrations/index.html) first, so budgets saved by older versions of the crate
+/// keep loading.
+///
+/// # Parameters
+/// * `text` - the document text.
+///
+/// # Returns
+/// `Result::Ok` with the budget, or `Result::Err` describing what is wrong and where.
+pub fn from_str(text: &str) -> Result<Budget, ParseError> {
+    let text = migrations::migrate(text).map_err(|err| match err {
+        MigrationError::UnknownFormat => ParseError::MissingHeader,
+        MigrationError::NewerVersion(version) => ParseError::UnsupportedVersion(version.to_string()),
+        err => ParseError::Migration(err),
+    })?;
+    // The header is checked by the migration.
+    let lines = text.lines().skip(1);
+
+    let mut name = String::new();
+    let mut defaults = BudgetDefaults::default();
+    let mut groups: Vec<BudgetGroup> = Vec::new();
+    // The items read for the last group, which are added all at once when the next group starts,
+    // so that the group is only sorted once. References belong to the last item, which must be on
+    // the line before them.
+    let mut items: Vec<BudgetItem> = Vec::new();
+    let mut after_item = false;
+    for (idx, line) in lines.enumerate() {
+        let line_no = idx + 2;
+        let fields: Vec<&str> = line.split('\t').collect();
+        if let ["ref", provider, external_id, url] = fields.as_slice() {
+            let item = items.last_mut().filter(|_| after_item).ok_or(ParseError::InvalidLine(line_no))?;
+            let url = unescape(url);
+            item.add_external_ref(ExternalRef {
+                provider: unescape(provider),
+                external_id: unescape(external_id),
+                url: if url.is_empty() { None } else { Some(url) },
+            });
+            continue;
+        }
+        after_item = false;
+        match fields.as_slice() {
+            [""] => (),
+            ["name", value] => name = unescape(value),
+            ["currency", currency] => defaults.currency = unescape(currency),
+            ["period", period] => defaults.period = parse_period(line_no, period)?,
+            ["rounding", rounding] => {
+                defaults.rounding = RoundingPolicy::from_keyword(rounding).ok_or(ParseError::InvalidLine(line_no))?;
+            }
+            ["locale", decimal, thousands] => {
+                let locale = Locale::from_separators(&unescape(decimal), &unescape(thousands));
+                defaults.locale = Some(locale.ok_or(ParseError::InvalidLine(line_no))?);
+            }
+            ["group", value] => {
+                if let Some(group) = groups.last_mut() {
+                    group.add_all(items.drain(..));
+                }
+                groups.push(BudgetGroup::new(&unescape(value)));
+            }
+            ["order", "manual"] => groups.last_mut().ok_or(ParseError::InvalidLine(line_no))?.set_order(ItemOrder::Manual),
+            ["item", kind, item_name, amount, period] => {
+                if groups.is_empty() {
+                    return Err(ParseError::ItemOutsideGroup(line_no));
+                }
+                let amount = parse_amount(line_no, amount)?;
+                let period = parse_period(line_no, period)?;
+                items.push(match *kind {
+                    "income" => BudgetItem::with_income(&unescape(item_name), amount, period),
+                    "expense" => BudgetItem::with_expense(&unescape(item_name), amount, period),
+                    _ => return Err(ParseError::InvalidLine(line_no)),
+                });
+                after_item = true;
+            }
+            _ => return Err(ParseError::InvalidLine(line_no)),
+        }
+    }
+    if let Some(group) = groups.last_mut() {
+        group.add_all(items);
+    }
+    let mut budget = Budget::new(&name);
+    for group in groups {
+        budget.add_group(group);
+    }
+    budget.set_defaults(defaults);
+    budget.mark_clean();
+    Ok(budget)
+}
+
+// Read a positive, finite amount.
+pub(crate) fn parse_amount(line: usize, value: &str) -> Result<f64, ParseError> {
+    match value.parse::<f64>() {
+        Ok(amount) if !amount.is_finite() => Err(ParseError::NonFiniteAmount(line, unescape(value))),
+        Ok(amount) if amount > 0.0 => Ok(amount),
+        _ => Err(ParseError::InvalidAmount(line, unescape(value))),
+    }
+}
+
+// Read a period keyword.
+fn parse_period(line: usize, value: &str) -> Result<Period, ParseError> {
+    Period::from_keyword(value).ok_or_else(|| ParseError::InvalidPeriod(line, unescape(value)))
+}
+
+// Escape the characters that separate fields and lines.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Reverse `escape`. A backslash before any other character is kept as is.
+pub(crate) fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::{BudgetGroup, ItemOrder};
+    use crate::budget_item::{BudgetItem, ExternalRef, Period, RoundingPolicy};
+    use crate::document::{escape, from_str, to_string, unescape, ParseError};
+    use crate::locale::Locale;
+
+    fn budget() -> Budget {
+        let mut housing = BudgetGroup::new("Housing\tand\\co");
+        housing.add(BudgetItem::with_expense("Rent", 900.5, Period::Every1Month));
+        let mut room = BudgetItem::with_income("Room\nrent", 0.1, Period::Every6Months);
+        room.add_external_ref(ExternalRef { provider: "bank".to_owned(), external_id: "4\t2".to_owned(), url: None });
+        room.add_external_ref(ExternalRef {
+            provider: "invoices".to_owned(),
+            external_id: "7".to_owned(),
+            url: Some("https://invoices.example/7".to_owned()),
+        });
+        housing.add(room);
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        let mut manual = BudgetGroup::new("Manual");
+        manual.set_order(ItemOrder::Manual);
+        manual.add(BudgetItem::with_expense("b", 1.0, Period::Every1Month));
+        manual.add(BudgetItem::with_expense("a", 2.0, Period::Every1Month));
+        budget.add_group(manual);
+        budget.add_group(BudgetGroup::new("Empty"));
+        budget.set_defaults(BudgetDefaults {
+            currency: "€".to_owned(),
+            period: Period::Every3Months,
+            rounding: RoundingPolicy::HalfEven,
+            locale: Some(Locale::FR_FR),
+        });
+        budget
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(concat!(
+            "rbp-budget\t1\n",
+            "name\tHome\n",
+            "currency\t€\n",
+            "period\tquarterly\n",
+            "rounding\thalf-even\n",
+            "locale\t,\t \n",
+            "group\tHousing\\tand\\\\co\n",
+            "item\texpense\tRent\t900.5\tmonthly\n",
+            "item\tincome\tRoom\\nrent\t0.1\thalf-yearly\n",
+            "ref\tbank\t4\\t2\t\n",
+            "ref\tinvoices\t7\thttps://invoices.example/7\n",
+            "group\tManual\n",
+            "order\tmanual\n",
+            "item\texpense\tb\t1\tmonthly\n",
+            "item\texpense\ta\t2\tmonthly\n",
+            "group\tEmpty\n"
+        ), to_string(&budget()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let original = budget();
+        let read = from_str(&to_string(&original)).unwrap();
+
+        assert_eq!(to_string(&original), to_string(&read));
+        assert_eq!(original.total(), read.total());
+        assert_eq!(original.defaults(), read.defaults());
+        assert!(!read.is_dirty());
+    }
+
+    #[test]
+    fn read_errors() {
+        assert_eq!(Err(ParseError::MissingHeader), from_str("").map(|_| ()));
+        assert_eq!(Err(ParseError::MissingHeader), from_str("budget\t1\n").map(|_| ()));
+        assert_eq!(Err(ParseError::UnsupportedVersion("2".to_owned())), from_str("rbp-budget\t2\n").map(|_| ()));
+        assert_eq!(Err(ParseError::ItemOutsideGroup(2)), from_str("rbp-budget\t1\nitem\texpense\tRent\t9\tmonthly").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidAmount(3, "-9".to_owned())),
+                   from_str("rbp-budget\t1\ngroup\tA\nitem\texpense\tRent\t-9\tmonthly").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidPeriod(3, "weekly".to_owned())),
+                   from_str("rbp-budget\t1\ngroup\tA\nitem\texpense\tRent\t9\tweekly").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidLine(3)), from_str("rbp-budget\t1\ngroup\tA\nitem\tgift\tRent\t9\tmonthly").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidLine(2)), from_str("rbp-budget\t1\nnotes\tA").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidLine(2)), from_str("rbp-budget\t1\nlocale\t,.\t").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidLine(2)), from_str("rbp-budget\t1\nlocale\t\t.").map(|_| ()));
+        assert_eq!(Err(ParseError::InvalidLine(3)), from_str("rbp-budget\t1\ngroup\tA\nref\tbank\t1\t").map(|_| ()));
+    }
+
+    #[test]
+    fn non_finite_amounts() {
+        let item = |amount: &str| from_str(&format!("rbp-budget\t1\ngroup\tA\nitem\texpense\tRent\t{}\tmonthly", amount)).map(|_| ());
+        for amount in ["inf", "-inf", "+infinity", "Infinity", "NaN", "nan", "1e309", "-1e400"] {
+            assert_eq!(Err(ParseError::NonFiniteAmount(3, amount.to_owned())), item(amount), "amount {}", amount);
+        }
+        for amount in ["0", "-0", "-1", "", "1,5", "0x10", "1e-400"] {
+            assert_eq!(Err(ParseError::InvalidAmount(3, amount.to_owned())), item(amount), "amount {}", amount);
+        }
+        assert!(item("1e308").is_ok());
+    }
+
+    #[test]
+    fn mutated_documents() {
+        // Fragments that are likely to hit the edge cases of the parser.
+        const FRAGMENTS: [&str; 12] =
+            ["\t", "\n", "\\", "1e308", "9e307", "-1", "nan", "inf", "item\texpense\tX\t1e308\tmonthly\n", "group\tA\n", "é", ""];
+        let original = to_string(&budget());
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next = |bound: usize| {
+            // xorshift64, which is enough to spread the mutations.
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+        for _ in 0..2_000 {
+            let mut text = original.clone();
+            for _ in 0..1 + next(4) {
+                let mut at = next(text.len() + 1);
+                while !text.is_char_boundary(at) {
+                    at -= 1;
+                }
+                match next(3) {
+                    0 => text.insert_str(at, FRAGMENTS[next(FRAGMENTS.len())]),
+                    1 if at < text.len() => {
+                        text.remove(at);
+                    }
+                    _ => text.truncate(at),
+                }
+            }
+
+            if let Ok(budget) = from_str(&text) {
+                assert!(budget.total().is_finite(), "{:?}", text);
+                for (_, group) in budget.enumerate() {
+                    assert!(group.total().is_finite(), "{:?}", text);
+                    assert!(group.iter().all(|item| item.amount().is_finite()), "{:?}", text);
+                }
+                let written = to_string(&budget);
+                assert_eq!(written, to_string(&from_str(&written).unwrap()), "{:?}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        let text = "a\tb\\c\nd\re\\";
+        assert_eq!(text, unescape(&escape(text)));
+        assert_eq!("a\\b", unescape("a\\b"));
+    }
+}
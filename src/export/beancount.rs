@@ -0,0 +1,173 @@
+use std::io;
+use std::io::Write;
+use crate::budget::Budget;
+use crate::budget_item::Period;
+
+/// Settings for a beancount export.
+///
+/// The account templates may contain the placeholders `{group}` and `{item}`, which are replaced
+/// with the sanitized names of the group and item of each entry.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The date of the written directives, formatted as `YYYY-MM-DD`.
+    pub date: String,
+    /// The commodity of the amounts, e.g. `EUR`.
+    pub currency: String,
+    /// The account template used for expense items.
+    pub expense_account: String,
+    /// The account template used for income items.
+    pub income_account: String,
+}
+
+impl Options {
+
+    /// Create export options with the default account templates, `Expenses:{group}:{item}` and
+    /// `Income:{group}:{item}`.
+    ///
+    /// # Parameters
+    /// * `date` - the date of the written directives, formatted as `YYYY-MM-DD`.
+    /// * `currency` - the commodity of the amounts.
+    pub fn new(date: &str, currency: &str) -> Options {
+        Options {
+            date: date.to_owned(),
+            currency: currency.to_owned(),
+            expense_account: "Expenses:{group}:{item}".to_owned(),
+            income_account: "Income:{group}:{item}".to_owned()
+        }
+    }
+}
+
+/// Write a budget as beancount directives.
+///
+/// Every item gets an account in a hierarchy built from the account templates, and is written
+/// as an `open` directive for that account plus a recurring `custom "budget"` directive in the
+/// format understood by Fava's budget reports. Income amounts are written as negative numbers,
+/// following beancount's sign convention for income accounts.
+///
+/// Fava only supports monthly, quarterly and yearly budgets of the periods used here, so
+/// 2-month items are written as half the amount per month and 6-month items as twice the amount
+/// per year.
+///
+/// # Parameters
+/// * `budget` - the budget to export.
+/// * `options` - the date, currency and account templates to use.
+/// * `writer` - the destination of the directives.
+pub fn write<W: Write>(budget: &Budget, options: &Options, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "; Budget: {}", budget.name())?;
+
+    let mut opened: Vec<String> = Vec::new();
+    let mut budgets: Vec<String> = Vec::new();
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            let template = if item.is_income() { &options.income_account } else { &options.expense_account };
+            let account = template
+                .replace("{group}", &account_component(group.name()))
+                .replace("{item}", &account_component(item.name()));
+
+            let (period, amount) = match item.period() {
+                Period::Every1Month => ("monthly", item.amount()),
+                Period::Every2Months => ("monthly", item.amount() / 2.0),
+                Period::Every3Months => ("quarterly", item.amount()),
+                Period::Every6Months => ("yearly", item.amount() * 2.0),
+                Period::Every12Months => ("yearly", item.amount()),
+            };
+            let amount = if item.is_income() { -amount } else { amount };
+
+            budgets.push(format!("{} custom \"budget\" {} \"{}\" {:.2} {}",
+                                 options.date, account, period, amount, options.currency));
+            if !opened.contains(&account) {
+                opened.push(account);
+            }
+        }
+    }
+
+    writeln!(writer)?;
+    for account in &opened {
+        writeln!(writer, "{} open {}", options.date, account)?;
+    }
+    writeln!(writer)?;
+    for directive in &budgets {
+        writeln!(writer, "{}", directive)?;
+    }
+    Ok(())
+}
+
+// Turn a name into a valid account name component: letters, digits and dashes only, starting
+// with a capital letter or a digit.
+fn account_component(name: &str) -> String {
+    let mut component = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            component.push(c);
+        } else if !component.is_empty() && !component.ends_with('-') {
+            component.push('-');
+        }
+    }
+    while component.ends_with('-') {
+        component.pop();
+    }
+
+    let mut chars = component.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Unnamed".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::export::beancount::{account_component, write, Options};
+
+    fn budget() -> Budget {
+        let mut income = BudgetGroup::new("Work");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut bills = BudgetGroup::new("Bills");
+        bills.add(BudgetItem::with_expense("Heating", 300.0, Period::Every2Months));
+        bills.add(BudgetItem::with_expense("car insurance", 400.0, Period::Every6Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(bills);
+        budget
+    }
+
+    #[test]
+    fn write_default_templates() {
+        let mut out = Vec::new();
+        write(&budget(), &Options::new("2024-01-01", "EUR"), &mut out).unwrap();
+
+        let expected = "; Budget: Home\n\
+            \n\
+            2024-01-01 open Income:Work:Salary\n\
+            2024-01-01 open Expenses:Bills:Heating\n\
+            2024-01-01 open Expenses:Bills:Car-insurance\n\
+            \n\
+            2024-01-01 custom \"budget\" Income:Work:Salary \"monthly\" -3000.00 EUR\n\
+            2024-01-01 custom \"budget\" Expenses:Bills:Heating \"monthly\" 150.00 EUR\n\
+            2024-01-01 custom \"budget\" Expenses:Bills:Car-insurance \"yearly\" 800.00 EUR\n";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn write_custom_templates() {
+        let mut options = Options::new("2024-01-01", "DKK");
+        options.expense_account = "Expenses:Budget:{item}".to_owned();
+        options.income_account = "Income:{item}".to_owned();
+
+        let mut out = Vec::new();
+        write(&budget(), &options, &mut out).unwrap();
+
+        let journal = String::from_utf8(out).unwrap();
+        assert!(journal.contains("2024-01-01 open Income:Salary\n"));
+        assert!(journal.contains("2024-01-01 open Expenses:Budget:Heating\n"));
+    }
+
+    #[test]
+    fn account_components() {
+        assert_eq!("Food-drinks", account_component("food & drinks"));
+        assert_eq!("2nd-car", account_component(" 2nd car!"));
+        assert_eq!("Unnamed", account_component("??"));
+    }
+}
@@ -0,0 +1,92 @@
+use std::io;
+use std::io::Write;
+use crate::budget::Budget;
+use crate::csv::write_record;
+
+/// The columns of the item rows.
+const ITEM_HEADER: [&str; 6] = ["Group", "Name", "Kind", "Amount", "Period", "Monthly contribution"];
+
+/// The columns of the totals section.
+const TOTALS_HEADER: [&str; 2] = ["Group", "Monthly total"];
+
+/// Write every item of a budget as a CSV row, ready for use in a spreadsheet.
+///
+/// Each row holds the item's group, name, kind (`income` or `expense`), amount, period, and
+/// monthly contribution. Amounts are positive, while the monthly contribution is negative for
+/// expenses, so that the contributions can be summed directly.
+///
+/// When totals are requested, the item rows are followed by an empty line and a second table
+/// with the monthly total of every group and a final `Net` row for the whole budget.
+///
+/// # Parameters
+/// * `budget` - the budget to export.
+/// * `with_totals` - whether to append the totals section.
+/// * `writer` - the destination of the CSV.
+pub fn write<W: Write>(budget: &Budget, with_totals: bool, mut writer: W) -> io::Result<()> {
+    write_record(&mut writer, &ITEM_HEADER)?;
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            write_record(&mut writer, &[
+                group.name().to_owned(),
+                item.name().to_owned(),
+                (if item.is_income() { "income" } else { "expense" }).to_owned(),
+                format!("{:.2}", item.amount()),
+                item.period().keyword().to_owned(),
+                format!("{:.2}", item.monthly_contribution()),
+            ])?;
+        }
+    }
+
+    if with_totals {
+        writer.write_all(b"\r\n")?;
+        write_record(&mut writer, &TOTALS_HEADER)?;
+        for (_, group) in budget.enumerate() {
+            write_record(&mut writer, &[group.name().to_owned(), format!("{:.2}", group.rounded_total(budget.defaults().rounding))])?;
+        }
+        write_record(&mut writer, &["Net".to_owned(), format!("{:.2}", budget.total())])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::export::csv::write;
+
+    fn budget() -> Budget {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing, etc.");
+        housing.add(BudgetItem::with_expense("Water", 100.0, Period::Every3Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget
+    }
+
+    #[test]
+    fn write_items() {
+        let mut out = Vec::new();
+        write(&budget(), false, &mut out).unwrap();
+
+        let expected = "Group,Name,Kind,Amount,Period,Monthly contribution\r\n\
+            Income,Salary,income,3000.00,monthly,3000.00\r\n\
+            \"Housing, etc.\",Water,expense,100.00,quarterly,-33.33\r\n";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn write_with_totals() {
+        let mut out = Vec::new();
+        write(&budget(), true, &mut out).unwrap();
+
+        let expected = "\r\n\
+            Group,Monthly total\r\n\
+            Income,3000.00\r\n\
+            \"Housing, etc.\",-33.33\r\n\
+            Net,2966.67\r\n";
+        assert!(String::from_utf8(out).unwrap().ends_with(expected));
+    }
+}
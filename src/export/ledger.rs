@@ -0,0 +1,109 @@
+use std::io;
+use std::io::Write;
+use crate::budget::Budget;
+use crate::budget_item::Period;
+
+/// Write a budget as plaintext accounting periodic transactions, for hledger and ledger-cli.
+///
+/// Every item is written as its own periodic transaction rule (`~ monthly`, `~ quarterly`, ...),
+/// posting the item's amount to `Expenses:<group>:<item>` or `Income:<group>:<item>` and balancing
+/// it against `Assets`. This is the format hledger's `--budget` reports consume.
+///
+/// # Parameters
+/// * `budget` - the budget to export.
+/// * `writer` - the destination of the journal.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// let mut group = BudgetGroup::new("Housing");
+/// group.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+/// let mut budget = Budget::new("Home");
+/// budget.add_group(group);
+///
+/// let mut journal = Vec::new();
+/// rbp_core::export::ledger::write(&budget, &mut journal).unwrap();
+/// assert!(String::from_utf8(journal).unwrap().contains("~ monthly  Rent\n    Expenses:Housing:Rent    1200.00\n"));
+/// ```
+pub fn write<W: Write>(budget: &Budget, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "; Budget: {}", budget.name())?;
+
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            let (root, amount) = if item.is_income() {
+                ("Income", -item.amount())
+            } else {
+                ("Expenses", item.amount())
+            };
+
+            writeln!(writer)?;
+            writeln!(writer, "~ {}  {}", period_expression(item.period()), item.name())?;
+            writeln!(writer, "    {}:{}:{}    {:.2}", root, account_name(group.name()), account_name(item.name()), amount)?;
+            writeln!(writer, "    Assets")?;
+        }
+    }
+    Ok(())
+}
+
+// The hledger period expression matching a budget period.
+fn period_expression(period: Period) -> &'static str {
+    match period {
+        Period::Every1Month => "monthly",
+        Period::Every2Months => "bimonthly",
+        Period::Every3Months => "quarterly",
+        Period::Every6Months => "every 6 months",
+        Period::Every12Months => "yearly",
+    }
+}
+
+// Make a name safe for use as an account name component. Colons separate components, and two
+// consecutive spaces end the account name, so neither may appear within a component.
+fn account_name(name: &str) -> String {
+    let name = name.trim().replace(':', "-");
+    let mut sanitized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if !(c.is_whitespace() && sanitized.ends_with(' ')) {
+            sanitized.push(if c.is_whitespace() { ' ' } else { c });
+        }
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::export::ledger::{account_name, write};
+
+    #[test]
+    fn write_periodic_transactions() {
+        let mut income = BudgetGroup::new("Work");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut bills = BudgetGroup::new("Bills");
+        bills.add(BudgetItem::with_expense("Water", 150.5, Period::Every3Months));
+        bills.add(BudgetItem::with_expense("Car insurance", 400.0, Period::Every6Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(bills);
+
+        let mut out = Vec::new();
+        write(&budget, &mut out).unwrap();
+
+        let expected = "; Budget: Home\n\
+            \n\
+            ~ monthly  Salary\n    Income:Work:Salary    -3000.00\n    Assets\n\
+            \n\
+            ~ every 6 months  Car insurance\n    Expenses:Bills:Car insurance    400.00\n    Assets\n\
+            \n\
+            ~ quarterly  Water\n    Expenses:Bills:Water    150.50\n    Assets\n";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn account_names_are_sanitized() {
+        assert_eq!("Food-Drinks", account_name("Food:Drinks"));
+        assert_eq!("Eating out", account_name(" Eating \t out "));
+    }
+}
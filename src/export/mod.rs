@@ -0,0 +1,8 @@
+/// Module for exporting a budget as periodic transactions for ledger-cli and hledger.
+pub mod ledger;
+
+/// Module for exporting a budget as beancount account and budget directives.
+pub mod beancount;
+
+/// Module for exporting the items and totals of a budget as CSV.
+pub mod csv;
@@ -0,0 +1,247 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{BudgetItem, Period};
+use crate::undo::Edit;
+
+/// The function completed successfully.
+pub const RBP_OK: c_int = 0;
+/// A required pointer argument was null.
+pub const RBP_NULL_POINTER: c_int = 1;
+/// A string argument was not valid UTF-8.
+pub const RBP_INVALID_UTF8: c_int = 2;
+/// No group exists with the given name.
+pub const RBP_NOT_FOUND: c_int = 3;
+/// The amount was not a finite number greater than 0.
+pub const RBP_INVALID_AMOUNT: c_int = 4;
+/// The period was not one of 1, 2, 3, 6, or 12 months.
+pub const RBP_INVALID_PERIOD: c_int = 5;
+
+/// Create a new, empty budget.
+///
+/// The returned handle is owned by the caller and must be released with [`rbp_budget_free`].
+///
+/// # Returns
+/// The budget handle, or null if `name` is null or not valid UTF-8.
+///
+/// # Safety
+/// `name` must be null or point to a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rbp_budget_new(name: *const c_char) -> *mut Budget {
+    match to_str(name) {
+        Ok(name) => Box::into_raw(Box::new(Budget::new(name))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Release a budget created with [`rbp_budget_new`]. Passing null does nothing.
+///
+/// # Safety
+/// `budget` must be null or a handle returned by [`rbp_budget_new`] that has not been released.
+#[no_mangle]
+pub unsafe extern "C" fn rbp_budget_free(budget: *mut Budget) {
+    if !budget.is_null() {
+        drop(Box::from_raw(budget));
+    }
+}
+
+/// Add an empty group to the end of a budget.
+///
+/// # Returns
+/// [`RBP_OK`], or an error code if an argument is invalid.
+///
+/// # Safety
+/// `budget` must be null or a live handle, and `name` must be null or point to a nul-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rbp_budget_add_group(budget: *mut Budget, name: *const c_char) -> c_int {
+    let budget = match budget.as_mut() {
+        Some(budget) => budget,
+        None => return RBP_NULL_POINTER,
+    };
+    match to_str(name) {
+        Ok(name) => {
+            budget.add_group(BudgetGroup::new(name));
+            RBP_OK
+        }
+        Err(code) => code,
+    }
+}
+
+/// Add an item to the first group of a budget with the given name.
+///
+/// # Parameters
+/// * `amount` - the positive amount of the item.
+/// * `period_months` - the recurring period in months: 1, 2, 3, 6, or 12.
+/// * `is_income` - non-zero for an income, zero for an expense.
+///
+/// # Returns
+/// [`RBP_OK`], or an error code if an argument is invalid or the group does not exist.
+///
+/// # Safety
+/// `budget` must be null or a live handle, and `group` and `name` must be null or point to
+/// nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn rbp_budget_add_item(budget: *mut Budget, group: *const c_char, name: *const c_char,
+                                             amount: f64, period_months: c_int, is_income: c_int) -> c_int {
+    let budget = match budget.as_mut() {
+        Some(budget) => budget,
+        None => return RBP_NULL_POINTER,
+    };
+    let (group, name) = match (to_str(group), to_str(name)) {
+        (Ok(group), Ok(name)) => (group, name),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    if !amount.is_finite() || amount <= 0.0 {
+        return RBP_INVALID_AMOUNT;
+    }
+    let period = match period_months {
+        1 => Period::Every1Month,
+        2 => Period::Every2Months,
+        3 => Period::Every3Months,
+        6 => Period::Every6Months,
+        12 => Period::Every12Months,
+        _ => return RBP_INVALID_PERIOD,
+    };
+
+    let group_idx = match budget.group_index(group) {
+        Some(idx) => idx,
+        None => return RBP_NOT_FOUND,
+    };
+    let item = if is_income != 0 {
+        BudgetItem::with_income(name, amount, period)
+    } else {
+        BudgetItem::with_expense(name, amount, period)
+    };
+    // Added as an edit, so that subscribers, the audit log and undo see the item.
+    match budget.apply(Edit::AddItem(group_idx, item)) {
+        Ok(()) => RBP_OK,
+        Err(_) => RBP_NOT_FOUND,
+    }
+}
+
+/// Calculate the monthly total of a budget.
+///
+/// # Returns
+/// The total, or 0 if `budget` is null.
+///
+/// # Safety
+/// `budget` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn rbp_budget_total(budget: *const Budget) -> f64 {
+    budget.as_ref().map(|budget| budget.total()).unwrap_or(0.0)
+}
+
+/// Calculate the monthly total of the first group of a budget with the given name.
+///
+/// # Parameters
+/// * `total` - receives the total when the function succeeds.
+///
+/// # Returns
+/// [`RBP_OK`], or an error code if an argument is invalid or the group does not exist.
+///
+/// # Safety
+/// `budget` must be null or a live handle, `group` must be null or point to a nul-terminated
+/// string, and `total` must be null or point to writable memory for a `double`.
+#[no_mangle]
+pub unsafe extern "C" fn rbp_budget_group_total(budget: *const Budget, group: *const c_char, total: *mut f64) -> c_int {
+    let budget = match budget.as_ref() {
+        Some(budget) => budget,
+        None => return RBP_NULL_POINTER,
+    };
+    if total.is_null() {
+        return RBP_NULL_POINTER;
+    }
+    let group = match to_str(group) {
+        Ok(group) => group,
+        Err(code) => return code,
+    };
+
+    match budget.group(group) {
+        Some(group) => {
+            *total = group.rounded_total(budget.defaults().rounding);
+            RBP_OK
+        }
+        None => RBP_NOT_FOUND,
+    }
+}
+
+// Borrow a C string as a string slice, or return the matching error code.
+unsafe fn to_str<'a>(text: *const c_char) -> Result<&'a str, c_int> {
+    if text.is_null() {
+        return Err(RBP_NULL_POINTER);
+    }
+    CStr::from_ptr(text).to_str().map_err(|_| RBP_INVALID_UTF8)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::ptr;
+    use crate::ffi::*;
+
+    const HEADER: &str = include_str!("../include/rbp_core.h");
+
+    fn text(text: &str) -> CString {
+        CString::new(text).unwrap()
+    }
+
+    #[test]
+    fn build_and_query_budget() {
+        unsafe {
+            let budget = rbp_budget_new(text("Home").as_ptr());
+            assert!(!budget.is_null());
+
+            assert_eq!(RBP_OK, rbp_budget_add_group(budget, text("Bills").as_ptr()));
+            assert_eq!(RBP_OK, rbp_budget_add_item(budget, text("Bills").as_ptr(), text("Salary").as_ptr(), 1_000.0, 1, 1));
+            assert_eq!(RBP_OK, rbp_budget_add_item(budget, text("Bills").as_ptr(), text("Water").as_ptr(), 90.0, 3, 0));
+            assert_eq!(970.0, rbp_budget_total(budget));
+            assert!((*budget).can_undo());
+
+            let mut total = 0.0;
+            assert_eq!(RBP_OK, rbp_budget_group_total(budget, text("Bills").as_ptr(), &mut total));
+            assert_eq!(970.0, total);
+
+            rbp_budget_free(budget);
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        unsafe {
+            let budget = rbp_budget_new(text("Home").as_ptr());
+            rbp_budget_add_group(budget, text("Bills").as_ptr());
+
+            assert_eq!(RBP_NULL_POINTER, rbp_budget_add_group(ptr::null_mut(), text("Bills").as_ptr()));
+            assert_eq!(RBP_INVALID_UTF8, rbp_budget_add_group(budget, CString::new(vec![0xff]).unwrap().as_ptr()));
+            assert_eq!(RBP_NOT_FOUND, rbp_budget_add_item(budget, text("Food").as_ptr(), text("Milk").as_ptr(), 1.0, 1, 0));
+            assert_eq!(RBP_INVALID_AMOUNT, rbp_budget_add_item(budget, text("Bills").as_ptr(), text("Rent").as_ptr(), -1.0, 1, 0));
+            assert_eq!(RBP_INVALID_AMOUNT, rbp_budget_add_item(budget, text("Bills").as_ptr(), text("Rent").as_ptr(), f64::NAN, 1, 0));
+            assert_eq!(RBP_INVALID_PERIOD, rbp_budget_add_item(budget, text("Bills").as_ptr(), text("Rent").as_ptr(), 1.0, 4, 0));
+            assert_eq!(RBP_NULL_POINTER, rbp_budget_group_total(budget, text("Bills").as_ptr(), ptr::null_mut()));
+            assert_eq!(0.0, rbp_budget_total(ptr::null()));
+            assert!(rbp_budget_new(ptr::null()).is_null());
+
+            rbp_budget_free(budget);
+            rbp_budget_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn header_declares_every_function() {
+        let functions = ["rbp_budget_new", "rbp_budget_free", "rbp_budget_add_group", "rbp_budget_add_item",
+            "rbp_budget_total", "rbp_budget_group_total"];
+        for function in functions.iter() {
+            assert!(HEADER.contains(&format!("{}(", function)), "Header does not declare {}", function);
+        }
+
+        let codes = [("RBP_OK", RBP_OK), ("RBP_NULL_POINTER", RBP_NULL_POINTER), ("RBP_INVALID_UTF8", RBP_INVALID_UTF8),
+            ("RBP_NOT_FOUND", RBP_NOT_FOUND), ("RBP_INVALID_AMOUNT", RBP_INVALID_AMOUNT),
+            ("RBP_INVALID_PERIOD", RBP_INVALID_PERIOD)];
+        for (name, value) in codes.iter() {
+            assert!(HEADER.contains(&format!("#define {} {}\n", name, value)), "Header does not define {}", name);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::BudgetItem;
+
+/// Settings for presenting amounts to a user, e.g. `$1,234.50` or `1.234,50 kr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// The currency symbol written with every amount. May be empty.
+    pub currency_symbol: String,
+    /// Whether the currency symbol is written before the number (`$5`) or after it (`5 kr`).
+    pub symbol_before: bool,
+    /// The number of decimals amounts are rounded to.
+    pub decimal_places: usize,
+    /// The character separating the integer part from the decimals.
+    pub decimal_separator: char,
+    /// The character grouping the thousands of the integer part, if any.
+    pub thousands_separator: Option<char>,
+}
+
+/// A budget item or group together with the options to display it, returned by
+/// [`BudgetItem::display`](../budget_item/struct.BudgetItem.html#method.display) and
+/// [`BudgetGroup::display`](../budget_group/struct.BudgetGroup.html#method.display).
+///
+/// An item is written as its name, its amount, negative for expenses, and its period, e.g.
+/// `Rent: -$900.00 (Monthly)`. A group is written as its name and monthly total, followed by its
+/// items on indented lines.
+///
+/// ```
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::format::FormatOptions;
+/// let mut group = BudgetGroup::new("Housing");
+/// group.add(BudgetItem::with_expense("Insurance", 1_200.0, Period::Every12Months));
+/// group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+///
+/// let options = FormatOptions { currency_symbol: "$".to_owned(), ..FormatOptions::default() };
+/// assert_eq!(
+///     "Housing: -$1,000.00 per month\n  Insurance: -$1,200.00 (Yearly)\n  Rent: -$900.00 (Monthly)",
+///     group.display(&options).to_string());
+/// ```
+pub struct Formatted<'a, T> {
+    value: &'a T,
+    options: &'a FormatOptions,
+}
+
+impl<'a, T> Formatted<'a, T> {
+    pub(crate) fn new(value: &'a T, options: &'a FormatOptions) -> Formatted<'a, T> {
+        Formatted { value, options }
+    }
+}
+
+impl fmt::Display for Formatted<'_, BudgetItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let item = self.value;
+        let amount = if item.is_income() { item.amount() } else { -item.amount() };
+        write!(f, "{}: {} ({})", item.name(), self.options.amount(amount), item.period())
+    }
+}
+
+impl fmt::Display for Formatted<'_, BudgetGroup> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let group = self.value;
+        write!(f, "{}: {} per month", group.name(), self.options.amount(group.total()))?;
+        for item in group {
+            write!(f, "\n  {}", item.display(self.options))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            currency_symbol: String::new(),
+            symbol_before: true,
+            decimal_places: 2,
+            decimal_separator: '.',
+            thousands_separator: Option::Some(',')
+        }
+    }
+}
+
+impl FormatOptions {
+
+    /// Format an amount according to these options.
+    ///
+    /// # Parameters
+    /// * `amount` - the amount to format. Negative amounts are prefixed with `-`.
+    ///
+    /// # Returns
+    /// The formatted amount, including the currency symbol.
+    ///
+    /// ```
+    /// use rbp_core::format::FormatOptions;
+    /// let options = FormatOptions { currency_symbol: "$".to_owned(), ..FormatOptions::default() };
+    /// assert_eq!("-$1,234.50", options.amount(-1234.5));
+    /// ```
+    pub fn amount(&self, amount: f64) -> String {
+        let number = self.number(amount.abs());
+        let sign = if amount < 0.0 && number.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+
+        if self.currency_symbol.is_empty() {
+            format!("{}{}", sign, number)
+        } else if self.symbol_before {
+            format!("{}{}{}", sign, self.currency_symbol, number)
+        } else {
+            format!("{}{} {}", sign, number, self.currency_symbol)
+        }
+    }
+
+    // Format a non-negative number with the configured decimals and separators.
+    fn number(&self, value: f64) -> String {
+        let plain = format!("{:.*}", self.decimal_places, value);
+        let (integer, decimals) = match plain.find('.') {
+            Some(idx) => (&plain[..idx], &plain[idx + 1..]),
+            None => (plain.as_str(), ""),
+        };
+
+        let mut number = String::with_capacity(plain.len() + integer.len() / 3);
+        for (idx, digit) in integer.chars().enumerate() {
+            if idx > 0 && (integer.len() - idx) % 3 == 0 {
+                if let Some(separator) = self.thousands_separator {
+                    number.push(separator);
+                }
+            }
+            number.push(digit);
+        }
+        if !decimals.is_empty() {
+            number.push(self.decimal_separator);
+            number.push_str(decimals);
+        }
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::format::FormatOptions;
+
+    #[test]
+    fn default_options() {
+        let options = FormatOptions::default();
+        assert_eq!("0.00", options.amount(0.0));
+        assert_eq!("999.99", options.amount(999.99));
+        assert_eq!("1,000.00", options.amount(1_000.0));
+        assert_eq!("-1,234,567.89", options.amount(-1_234_567.891));
+    }
+
+    #[test]
+    fn european_style() {
+        let options = FormatOptions {
+            currency_symbol: "kr".to_owned(),
+            symbol_before: false,
+            decimal_places: 2,
+            decimal_separator: ',',
+            thousands_separator: Option::Some('.')
+        };
+        assert_eq!("1.234,50 kr", options.amount(1_234.5));
+        assert_eq!("-12,00 kr", options.amount(-12.0));
+    }
+
+    #[test]
+    fn no_decimals_or_grouping() {
+        let options = FormatOptions { decimal_places: 0, thousands_separator: None, ..FormatOptions::default() };
+        assert_eq!("12346", options.amount(12_345.6));
+    }
+
+    #[test]
+    fn display() {
+        let options = FormatOptions { currency_symbol: "kr".to_owned(), symbol_before: false, ..FormatOptions::default() };
+        let salary = BudgetItem::with_income("Salary", 30_000.0, Period::Every1Month);
+        assert_eq!("Salary: 30,000.00 kr (Monthly)", salary.display(&options).to_string());
+        assert_eq!("Salary: 30,000.00 (Monthly)", salary.to_string());
+        assert_eq!("Every 6 months", Period::Every6Months.to_string());
+
+        let empty = BudgetGroup::new("Income");
+        assert_eq!("Income: 0.00 kr per month", empty.display(&options).to_string());
+        assert_eq!("Income: 0.00 per month", empty.to_string());
+    }
+
+    #[test]
+    fn negative_zero_has_no_sign() {
+        assert_eq!("0.00", FormatOptions::default().amount(-0.001));
+    }
+}
@@ -0,0 +1,441 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+use core::iter::Peekable;
+use core::str::CharIndices;
+use crate::budget::Budget;
+
+/// A parsed amount formula, such as `=income("Salary") * 0.1 + 50`.
+///
+/// Formulas let users derive amounts from the rest of their budget without writing code. A formula
+/// is built from numbers, the operators `+`, `-`, `*`, `/`, parentheses, and these functions, which
+/// all work on monthly figures:
+///
+/// * `income("name")` - the monthly contribution of the income items with the name.
+/// * `expense("name")` - the monthly cost of the expense items with the name, as a positive number.
+/// * `group("name")` - the monthly total of the group with the name.
+/// * `total()` - the monthly total of the whole budget.
+///
+/// The figures are rounded according to the rounding policy of the budget's
+/// [defaults](../budget/struct.Budget.html#method.defaults), as the budget total is.
+///
+/// A leading `=` is optional, so formulas can be taken directly from spreadsheet-like input.
+///
+/// Formulas may nest at most 256 levels deep, where every operator, negation and pair of
+/// parentheses adds a level. E.g. a sum may have at most 256 terms. Deeper formulas are rejected
+/// when parsed, so that user input cannot exhaust the stack.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::formula::Formula;
+/// let mut group = BudgetGroup::new("Income");
+/// group.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+/// let mut budget = Budget::new("Home");
+/// budget.add_group(group);
+///
+/// let formula = Formula::parse("=income(\"Salary\") * 0.1 + 50").unwrap();
+/// assert_eq!(350.0, formula.evaluate(&budget).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    expr: Expr,
+}
+
+/// Error thrown when a formula cannot be parsed. Every variant holds the byte position in the
+/// formula text where the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// A character that cannot appear at this point.
+    UnexpectedCharacter(usize, char),
+    /// The formula ended while more input was expected.
+    UnexpectedEnd(usize),
+    /// A number could not be read.
+    InvalidNumber(usize),
+    /// A name that is not one of the supported functions.
+    UnknownFunction(usize, String),
+    /// A function was given the wrong number of arguments.
+    WrongArgumentCount(usize, String),
+    /// The formula nests deeper than the limit described on [`Formula`].
+    TooDeep(usize),
+}
+
+/// Error thrown when a formula cannot be evaluated against a budget.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// No income item has the name.
+    UnknownIncome(String),
+    /// No expense item has the name.
+    UnknownExpense(String),
+    /// No group has the name.
+    UnknownGroup(String),
+    /// The formula divides by zero.
+    DivisionByZero,
+    /// The result is not a finite number.
+    NotFinite,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedCharacter(pos, c) => write!(f, "unexpected '{}' at position {}", c, pos),
+            ParseError::UnexpectedEnd(pos) => write!(f, "formula ends unexpectedly at position {}", pos),
+            ParseError::InvalidNumber(pos) => write!(f, "invalid number at position {}", pos),
+            ParseError::UnknownFunction(pos, name) => write!(f, "unknown function \"{}\" at position {}", name, pos),
+            ParseError::WrongArgumentCount(pos, name) => write!(f, "wrong number of arguments to \"{}\" at position {}", name, pos),
+            ParseError::TooDeep(pos) => write!(f, "formula nests too deeply at position {}", pos),
+        }
+    }
+}
+
+impl core::error::Error for ParseError { }
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownIncome(name) => write!(f, "no income named \"{}\"", name),
+            EvalError::UnknownExpense(name) => write!(f, "no expense named \"{}\"", name),
+            EvalError::UnknownGroup(name) => write!(f, "no group named \"{}\"", name),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::NotFinite => write!(f, "result is not a finite number"),
+        }
+    }
+}
+
+impl core::error::Error for EvalError { }
+
+// The syntax tree of a formula.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, char, Box<Expr>),
+    Income(String),
+    Expense(String),
+    Group(String),
+    Total,
+}
+
+impl Formula {
+
+    /// Parse a formula.
+    ///
+    /// # Parameters
+    /// * `text` - the formula text, optionally starting with `=`.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the parsed formula, or `Result::Err` describing what is wrong and where.
+    pub fn parse(text: &str) -> Result<Formula, ParseError> {
+        let mut parser = Parser { text, chars: text.char_indices().peekable(), depth: 0 };
+        parser.skip_whitespace();
+        if let Some(&(_, '=')) = parser.chars.peek() {
+            parser.chars.next();
+        }
+
+        let (expr, _) = parser.expr()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            Some((pos, c)) => Err(ParseError::UnexpectedCharacter(pos, c)),
+            None => Ok(Formula { expr }),
+        }
+    }
+
+    /// Evaluate the formula against a budget.
+    ///
+    /// # Parameters
+    /// * `budget` - the budget the functions of the formula look up their values in.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the value of the formula, or `Result::Err` if a name does not exist in the
+    /// budget or the calculation is undefined.
+    pub fn evaluate(&self, budget: &Budget) -> Result<f64, EvalError> {
+        let value = evaluate(&self.expr, budget)?;
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(EvalError::NotFinite)
+        }
+    }
+}
+
+fn evaluate(expr: &Expr, budget: &Budget) -> Result<f64, EvalError> {
+    match expr {
+        Expr::Number(value) => Ok(*value),
+        Expr::Negate(expr) => Ok(-evaluate(expr, budget)?),
+        Expr::Binary(left, op, right) => {
+            let left = evaluate(left, budget)?;
+            let right = evaluate(right, budget)?;
+            match op {
+                '+' => Ok(left + right),
+                '-' => Ok(left - right),
+                '*' => Ok(left * right),
+                _ if right == 0.0 => Err(EvalError::DivisionByZero),
+                _ => Ok(left / right),
+            }
+        }
+        Expr::Income(name) => sum_items(budget, name, true).ok_or_else(|| EvalError::UnknownIncome(name.clone())),
+        Expr::Expense(name) => sum_items(budget, name, false)
+            .map(|sum| -sum)
+            .ok_or_else(|| EvalError::UnknownExpense(name.clone())),
+        Expr::Group(name) => budget.group(name)
+            .map(|group| group.rounded_total(budget.defaults().rounding))
+            .ok_or_else(|| EvalError::UnknownGroup(name.clone())),
+        Expr::Total => Ok(budget.total()),
+    }
+}
+
+// The summed monthly contribution of the items with a name and kind, rounded by the policy of the
+// budget, or None if there are none.
+fn sum_items(budget: &Budget, name: &str, income: bool) -> Option<f64> {
+    let rounding = budget.defaults().rounding;
+    let mut sum = None;
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            if item.is_income() == income && item.name() == name {
+                sum = Some(sum.unwrap_or(0.0) + item.rounded_monthly_contribution(rounding));
+            }
+        }
+    }
+    sum.map(|sum| rounding.round(sum))
+}
+
+// Recursive descent parser over the formula grammar:
+//   expr   := term (('+' | '-') term)*
+//   term   := factor (('*' | '/') factor)*
+//   factor := '-' factor | number | '(' expr ')' | name '(' (string)? ')'
+// Every rule returns the parsed expression with its depth, counting parentheses as a level, so
+// that long operator chains are limited as well as deep nesting. `depth` counts the negations and
+// parentheses the parser is currently inside, which stops the recursion before it gets too deep.
+struct Parser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    depth: usize,
+}
+
+// The deepest a formula may nest. Parsing, evaluating and dropping a formula all recurse over its
+// depth.
+const MAX_DEPTH: usize = 256;
+
+impl Parser<'_> {
+    fn expr(&mut self) -> Result<(Expr, usize), ParseError> {
+        let (mut expr, mut depth) = self.term()?;
+        while let Some((pos, op)) = self.operator(&['+', '-']) {
+            let (right, right_depth) = self.term()?;
+            depth = limit_depth(pos, depth.max(right_depth) + 1)?;
+            expr = Expr::Binary(Box::new(expr), op, Box::new(right));
+        }
+        Ok((expr, depth))
+    }
+
+    fn term(&mut self) -> Result<(Expr, usize), ParseError> {
+        let (mut expr, mut depth) = self.factor()?;
+        while let Some((pos, op)) = self.operator(&['*', '/']) {
+            let (right, right_depth) = self.factor()?;
+            depth = limit_depth(pos, depth.max(right_depth) + 1)?;
+            expr = Expr::Binary(Box::new(expr), op, Box::new(right));
+        }
+        Ok((expr, depth))
+    }
+
+    fn factor(&mut self) -> Result<(Expr, usize), ParseError> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some((pos, '-')) => {
+                self.chars.next();
+                let (expr, depth) = self.nested(pos, Parser::factor)?;
+                Ok((Expr::Negate(Box::new(expr)), depth))
+            }
+            Some((pos, '(')) => {
+                self.chars.next();
+                let (expr, depth) = self.nested(pos, Parser::expr)?;
+                self.expect(')')?;
+                Ok((expr, depth))
+            }
+            Some((pos, c)) if c.is_ascii_digit() || c == '.' => Ok((self.number(pos)?, 1)),
+            Some((pos, c)) if c.is_alphabetic() => Ok((self.function(pos)?, 1)),
+            Some((pos, c)) => Err(ParseError::UnexpectedCharacter(pos, c)),
+            None => Err(ParseError::UnexpectedEnd(self.text.len())),
+        }
+    }
+
+    fn number(&mut self, start: usize) -> Result<Expr, ParseError> {
+        let mut end = start;
+        while let Some(&(pos, c)) = self.chars.peek() {
+            if !(c.is_ascii_digit() || c == '.') {
+                break;
+            }
+            end = pos + c.len_utf8();
+            self.chars.next();
+        }
+        self.text[start..end].parse().map(Expr::Number).map_err(|_| ParseError::InvalidNumber(start))
+    }
+
+    fn function(&mut self, start: usize) -> Result<Expr, ParseError> {
+        let mut name = String::new();
+        while let Some(&(_, c)) = self.chars.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            name.push(c);
+            self.chars.next();
+        }
+        self.expect('(')?;
+        self.skip_whitespace();
+        let argument = match self.chars.peek() {
+            Some(&(_, '"')) => Some(self.string()?),
+            _ => None,
+        };
+        self.expect(')')?;
+
+        match (name.as_str(), argument) {
+            ("income", Some(argument)) => Ok(Expr::Income(argument)),
+            ("expense", Some(argument)) => Ok(Expr::Expense(argument)),
+            ("group", Some(argument)) => Ok(Expr::Group(argument)),
+            ("total", None) => Ok(Expr::Total),
+            ("income", _) | ("expense", _) | ("group", _) | ("total", _) => Err(ParseError::WrongArgumentCount(start, name)),
+            _ => Err(ParseError::UnknownFunction(start, name)),
+        }
+    }
+
+    // Read a double quoted string, in which `\"` and `\\` escape a quote and a backslash.
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c)) => value.push(c),
+                    None => return Err(ParseError::UnexpectedEnd(self.text.len())),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err(ParseError::UnexpectedEnd(self.text.len())),
+            }
+        }
+    }
+
+    // Parse a negated or parenthesized expression, one level deeper than the current one.
+    fn nested(&mut self, pos: usize, parse: fn(&mut Self) -> Result<(Expr, usize), ParseError>) -> Result<(Expr, usize), ParseError> {
+        self.depth = limit_depth(pos, self.depth + 1)?;
+        let (expr, depth) = parse(self)?;
+        self.depth -= 1;
+        Ok((expr, limit_depth(pos, depth + 1)?))
+    }
+
+    fn operator(&mut self, operators: &[char]) -> Option<(usize, char)> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some(&(pos, c)) if operators.contains(&c) => {
+                self.chars.next();
+                Some((pos, c))
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((pos, c)) => Err(ParseError::UnexpectedCharacter(pos, c)),
+            None => Err(ParseError::UnexpectedEnd(self.text.len())),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+    }
+}
+
+// Check that a depth is within the limit, with the position at which it was reached.
+fn limit_depth(pos: usize, depth: usize) -> Result<usize, ParseError> {
+    if depth > MAX_DEPTH {
+        Err(ParseError::TooDeep(pos))
+    } else {
+        Ok(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::formula::{EvalError, Formula, ParseError};
+
+    fn budget() -> Budget {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 1_000.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Water", 60.0, Period::Every3Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget
+    }
+
+    fn eval(text: &str) -> Result<f64, EvalError> {
+        Formula::parse(text).unwrap().evaluate(&budget())
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Ok(7.0), eval("1 + 2 * 3"));
+        assert_eq!(Ok(9.0), eval("(1 + 2) * 3"));
+        assert_eq!(Ok(-1.5), eval("=-3 / 2"));
+        assert_eq!(Ok(4.0), eval("10 - 3 - 3"));
+        assert_eq!(Ok(0.25), eval(".5 * .5"));
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(Ok(350.0), eval("=income(\"Salary\") * 0.1 + 50"));
+        assert_eq!(Ok(20.0), eval("expense(\"Water\")"));
+        assert_eq!(Ok(-1_020.0), eval("group( \"Housing\" )"));
+        assert_eq!(Ok(1_980.0), eval("total()"));
+    }
+
+    #[test]
+    fn evaluation_errors() {
+        assert_eq!(Err(EvalError::UnknownIncome("Bonus".to_owned())), eval("income(\"Bonus\")"));
+        assert_eq!(Err(EvalError::UnknownExpense("Salary".to_owned())), eval("expense(\"Salary\")"));
+        assert_eq!(Err(EvalError::UnknownGroup("Food".to_owned())), eval("group(\"Food\")"));
+        assert_eq!(Err(EvalError::DivisionByZero), eval("1 / (2 - 2)"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Err(ParseError::UnexpectedCharacter(4, '*')), Formula::parse("1 + * 2"));
+        assert_eq!(Err(ParseError::UnexpectedEnd(5)), Formula::parse("(1 + "));
+        assert_eq!(Err(ParseError::InvalidNumber(0)), Formula::parse("1.2.3"));
+        assert_eq!(Err(ParseError::UnknownFunction(0, "salary".to_owned())), Formula::parse("salary()"));
+        assert_eq!(Err(ParseError::WrongArgumentCount(0, "income".to_owned())), Formula::parse("income()"));
+        assert_eq!(Err(ParseError::UnexpectedCharacter(2, '2')), Formula::parse("1 2"));
+    }
+
+    #[test]
+    fn depth_limit() {
+        assert_eq!(Ok(256.0), eval(&("1+".repeat(255) + "1")));
+        assert_eq!(Ok(1.0), eval(&("(".repeat(255) + "1" + &")".repeat(255))));
+        assert_eq!(Err(ParseError::TooDeep(0)), Formula::parse(&("-".repeat(256) + "1")));
+        assert_eq!(Err(ParseError::TooDeep(511)), Formula::parse(&("1+".repeat(256) + "1")));
+
+        // Without the limit, these overflowed the stack.
+        assert_eq!(Err(ParseError::TooDeep(256)), Formula::parse(&("-".repeat(200_000) + "1")));
+        assert_eq!(Err(ParseError::TooDeep(256)), Formula::parse(&"(".repeat(200_000)));
+        assert!(Formula::parse(&("1*".repeat(200_000) + "1")).is_err());
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!("unexpected '*' at position 4", Formula::parse("1 + * 2").unwrap_err().to_string());
+        assert_eq!("no group named \"Food\"", eval("group(\"Food\")").unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Calculate the Levenshtein edit distance between two strings.
+///
+/// The distance is the smallest number of single character insertions, deletions, and
+/// substitutions needed to turn one string into the other. Characters are compared exactly, so
+/// callers wanting case-insensitive distances should lowercase both strings first.
+///
+/// # Parameters
+/// * `a` - the first string.
+/// * `b` - the second string.
+///
+/// # Returns
+/// The edit distance, which is 0 for equal strings.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Calculate how similar two strings are, based on the trigrams (three character sequences) they
+/// share.
+///
+/// The comparison ignores case, and is more forgiving than [`levenshtein`] towards reordered
+/// words, e.g. "Car insurance" and "Insurance (car)".
+///
+/// # Parameters
+/// * `a` - the first string.
+/// * `b` - the second string.
+///
+/// # Returns
+/// A similarity between 0.0 (no trigrams in common) and 1.0 (identical trigrams).
+pub fn trigram_similarity(a: &str, b: &str) -> f64 {
+    let a = trigrams(a);
+    let b = trigrams(b);
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let common = a.iter().filter(|trigram| b.binary_search(trigram).is_ok()).count();
+    common as f64 / (a.len() + b.len() - common) as f64
+}
+
+/// Check whether a search query matches a candidate string, tolerating typos.
+///
+/// The check ignores case. A candidate matches if it contains the query, or if the query is
+/// within a small edit distance of the whole candidate or of one of its words. The number of
+/// tolerated typos grows with the length of the query: none for up to 3 characters, one for up
+/// to 6 characters, and two beyond that.
+///
+/// # Parameters
+/// * `query` - the text searched for, e.g. "Netflx".
+/// * `candidate` - the text searched in, e.g. "Netflix subscription".
+///
+/// # Returns
+/// `true` if the candidate matches the query.
+///
+/// ```
+/// use rbp_core::fuzzy::matches;
+/// assert!(matches("Netflx", "Netflix"));
+/// assert!(matches("netflix", "Netflix subscription"));
+/// assert!(!matches("Netflx", "Spotify"));
+/// ```
+pub fn matches(query: &str, candidate: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if candidate.contains(&query) {
+        return true;
+    }
+
+    let tolerance = match query.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    };
+    tolerance > 0 && (levenshtein(&query, &candidate) <= tolerance
+        || candidate.split_whitespace().any(|word| levenshtein(&query, word) <= tolerance))
+}
+
+// The sorted, unique, lowercase trigrams of a string. Words are padded with spaces, so that short
+// words and word boundaries also produce trigrams.
+fn trigrams(text: &str) -> Vec<[char; 3]> {
+    let mut trigrams = Vec::new();
+    for word in text.to_lowercase().split_whitespace() {
+        let padded: Vec<char> = "  ".chars().chain(word.chars()).chain(" ".chars()).collect();
+        trigrams.extend(padded.windows(3).map(|window| [window[0], window[1], window[2]]));
+    }
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzzy::{levenshtein, matches, trigram_similarity};
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(0, levenshtein("rent", "rent"));
+        assert_eq!(1, levenshtein("netflx", "netflix"));
+        assert_eq!(3, levenshtein("kitten", "sitting"));
+        assert_eq!(4, levenshtein("", "gym "));
+        assert_eq!(1, levenshtein("café", "cafe"));
+    }
+
+    #[test]
+    fn trigram_similarities() {
+        assert_eq!(1.0, trigram_similarity("Car insurance", "insurance car"));
+        assert_eq!(0.0, trigram_similarity("Rent", "Gym"));
+        let similarity = trigram_similarity("Electricity", "Electricty");
+        assert!(similarity > 0.5 && similarity < 1.0);
+    }
+
+    #[test]
+    fn matches_with_typos() {
+        assert!(matches("Netflx", "Netflix"));
+        assert!(matches("insurnce", "Home insurance"));
+        assert!(matches("RENT", "Rent"));
+        assert!(matches("gro", "Groceries"));
+    }
+
+    #[test]
+    fn short_queries_need_exact_substrings() {
+        assert!(!matches("gum", "Gym"));
+        assert!(!matches("Netflx", "Hulu"));
+    }
+}
@@ -0,0 +1,2 @@
+/// Module for reading and writing the budget exports of YNAB ("You Need A Budget").
+pub mod ynab;
@@ -0,0 +1,268 @@
+use std::fmt;
+use std::io;
+use std::io::{Read, Write};
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{BudgetItem, Period};
+use crate::csv;
+
+/// The columns written by [`write`], matching the layout of a YNAB budget export.
+const HEADER: [&str; 6] = ["Category Group/Category", "Category Group", "Category", "Budgeted", "Activity", "Available"];
+
+/// Category groups YNAB uses internally, which do not hold budgeted expenses.
+const INTERNAL_GROUPS: [&str; 3] = ["Inflow", "Internal Master Category", "Hidden Categories"];
+
+/// Error thrown when a YNAB export cannot be read.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The export could not be read from its source.
+    Io(io::Error),
+    /// A quoted field was never closed. Holds the line of the field.
+    UnterminatedQuote(usize),
+    /// The header does not contain a column required for the import. Holds the column name.
+    MissingColumn(&'static str),
+    /// A budgeted amount is not a number. Holds the line and the offending value.
+    InvalidAmount(usize, String),
+    /// A budgeted amount is too large to be represented. Holds the line and the offending value.
+    NonFiniteAmount(usize, String),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "could not read YNAB export: {}", err),
+            ReadError::UnterminatedQuote(line) => write!(f, "unterminated quoted field on line {}", line),
+            ReadError::MissingColumn(column) => write!(f, "missing column \"{}\"", column),
+            ReadError::InvalidAmount(line, value) => write!(f, "invalid amount \"{}\" on line {}", value, line),
+            ReadError::NonFiniteAmount(line, value) => write!(f, "amount \"{}\" on line {} is not a finite number", value, line),
+        }
+    }
+}
+
+impl std::error::Error for ReadError { }
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+/// Read a YNAB budget export (CSV) into a new budget.
+///
+/// Every YNAB category group becomes a budget group, and every category with a positive budgeted
+/// amount becomes a monthly expense item in that group. Categories with nothing budgeted, and
+/// YNAB's internal groups (such as "Inflow"), are skipped.
+///
+/// Exports spanning several months repeat every category once per month. In that case only the
+/// month of the last row is imported, as that is the most recent plan.
+///
+/// # Parameters
+/// * `name` - the name of the created budget.
+/// * `reader` - the source of the CSV export.
+///
+/// # Returns
+/// `Result::Ok` with the imported budget, or `Result::Err` if the export could not be read or is
+/// not a YNAB budget export.
+pub fn read<R: Read>(name: &str, mut reader: R) -> Result<Budget, ReadError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let records = csv::parse(&text).map_err(|err| ReadError::UnterminatedQuote(err.line))?;
+
+    let mut budget = Budget::new(name);
+    let mut records = records.iter();
+    let header = match records.next() {
+        Some(header) => &header.fields,
+        None => return Ok(budget),
+    };
+    let column = |name: &'static str| {
+        header.iter().position(|field| field.trim() == name).ok_or(ReadError::MissingColumn(name))
+    };
+    let group_col = column("Category Group")?;
+    let category_col = column("Category")?;
+    let budgeted_col = column("Budgeted")?;
+    let month_col = column("Month").ok();
+
+    let rows: Vec<&csv::Record> = records.collect();
+    let last_month = month_col.and_then(|col| rows.last().and_then(|row| row.fields.get(col)));
+
+    for row in &rows {
+        let field = |col: usize| row.fields.get(col).map(|field| field.trim()).unwrap_or("");
+        if let (Some(col), Some(month)) = (month_col, last_month) {
+            if field(col) != month.trim() {
+                continue;
+            }
+        }
+
+        let group_name = field(group_col);
+        if INTERNAL_GROUPS.contains(&group_name) {
+            continue;
+        }
+        let amount = parse_amount(field(budgeted_col))
+            .ok_or_else(|| ReadError::InvalidAmount(row.line, field(budgeted_col).to_owned()))?;
+        if !amount.is_finite() {
+            return Err(ReadError::NonFiniteAmount(row.line, field(budgeted_col).to_owned()));
+        }
+        if amount <= 0.0 {
+            continue;
+        }
+
+        if budget.group(group_name).is_none() {
+            budget.add_group(BudgetGroup::new(group_name));
+        }
+        let item = BudgetItem::with_expense(field(category_col), amount, Period::Every1Month);
+        budget.group_mut(group_name).unwrap().add(item);
+    }
+
+    Ok(budget)
+}
+
+/// Write a budget as a YNAB compatible budget CSV.
+///
+/// Every group is written as a category group, and every expense item as a category whose
+/// budgeted amount is the item's monthly contribution. Income items are left out, since YNAB does
+/// not budget income. The activity and available columns are written as zero.
+///
+/// # Parameters
+/// * `budget` - the budget to export.
+/// * `writer` - the destination of the CSV.
+pub fn write<W: Write>(budget: &Budget, mut writer: W) -> io::Result<()> {
+    csv::write_record(&mut writer, &HEADER)?;
+
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            let contribution = item.monthly_contribution();
+            if contribution >= 0.0 {
+                continue;
+            }
+            csv::write_record(&mut writer, &[
+                format!("{}: {}", group.name(), item.name()),
+                group.name().to_owned(),
+                item.name().to_owned(),
+                format!("{:.2}", -contribution),
+                "0.00".to_owned(),
+                "0.00".to_owned(),
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+// Parse a YNAB formatted amount, such as "$1,200.00" or "-45.10", ignoring currency symbols and
+// thousands separators. Blank amounts count as zero.
+fn parse_amount(value: &str) -> Option<f64> {
+    let number: String = value.chars().filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-').collect();
+    if number.is_empty() {
+        return if value.chars().any(|c| c.is_alphanumeric()) { None } else { Some(0.0) };
+    }
+    number.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::interop::ynab::{read, write, ReadError};
+
+    const EXPORT: &str = "\u{feff}\"Month\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Budgeted\",\"Activity\",\"Available\"\r\n\
+        \"Jan 2024\",\"Bills: Rent\",\"Bills\",\"Rent\",$1000.00,-$1000.00,$0.00\r\n\
+        \"Feb 2024\",\"Inflow: Ready to Assign\",\"Inflow\",\"Ready to Assign\",$0.00,$3000.00,$500.00\r\n\
+        \"Feb 2024\",\"Bills: Rent\",\"Bills\",\"Rent\",\"$1,200.00\",-$1200.00,$0.00\r\n\
+        \"Feb 2024\",\"Bills: Internet\",\"Bills\",\"Internet\",$40.00,$0.00,$40.00\r\n\
+        \"Feb 2024\",\"Fun: Games\",\"Fun\",\"Games\",$0.00,$0.00,$0.00\r\n\
+        \"Feb 2024\",\"Food: Groceries\",\"Food\",\"Groceries\",$400.50,-$10.00,$390.50\r\n";
+
+    #[test]
+    fn read_latest_month() {
+        let budget = read("Imported", EXPORT.as_bytes()).unwrap();
+
+        assert_eq!("Imported", budget.name());
+        let groups: Vec<&String> = budget.enumerate().map(|(_, group)| group.name()).collect();
+        assert_eq!(vec!["Bills", "Food"], groups);
+        assert_eq!(-1_240.0, budget.group("Bills").unwrap().total());
+        assert_eq!(-400.5, budget.group("Food").unwrap().total());
+    }
+
+    #[test]
+    fn read_missing_column() {
+        let result = read("Imported", "Category Group,Category\r\nBills,Rent\r\n".as_bytes());
+        assert!(matches!(result, Err(ReadError::MissingColumn("Budgeted"))));
+    }
+
+    #[test]
+    fn read_invalid_amount() {
+        let result = read("Imported", "Category Group,Category,Budgeted\r\nBills,Rent,lots\r\n".as_bytes());
+        assert!(matches!(result, Err(ReadError::InvalidAmount(2, _))));
+
+        let huge = format!("Category Group,Category,Budgeted\r\nBills,Rent,1{}\r\n", "0".repeat(400));
+        assert!(matches!(read("Imported", huge.as_bytes()), Err(ReadError::NonFiniteAmount(2, _))));
+    }
+
+    #[test]
+    fn read_mutated_exports() {
+        // Fragments that are likely to hit the edge cases of the CSV and amount parsing.
+        const FRAGMENTS: [&str; 12] =
+            ["\"", ",", "\r\n", "\n", "$", "-", "1e308", "999999", "\"$1,7e308\"", "Infinity", "é", "NaN"];
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next = |bound: usize| {
+            // xorshift64, which is enough to spread the mutations.
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+        for _ in 0..2_000 {
+            let mut text = EXPORT.to_owned();
+            for _ in 0..1 + next(4) {
+                let mut at = next(text.len() + 1);
+                while !text.is_char_boundary(at) {
+                    at -= 1;
+                }
+                match next(3) {
+                    0 => text.insert_str(at, FRAGMENTS[next(FRAGMENTS.len())]),
+                    1 if at < text.len() => {
+                        text.remove(at);
+                    }
+                    _ => text.truncate(at),
+                }
+            }
+
+            if let Ok(budget) = read("Imported", text.as_bytes()) {
+                assert!(budget.total().is_finite(), "{:?}", text);
+                for (_, group) in budget.enumerate() {
+                    assert!(group.iter().all(|item| item.amount().is_finite() && item.amount() > 0.0), "{:?}", text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn write_expenses_as_monthly_categories() {
+        let mut group = BudgetGroup::new("Home");
+        group.add(BudgetItem::with_expense("Insurance", 120.0, Period::Every12Months));
+        group.add(BudgetItem::with_income("Rent out", 50.0, Period::Every1Month));
+        let mut budget = Budget::new("Mine");
+        budget.add_group(group);
+
+        let mut out = Vec::new();
+        write(&budget, &mut out).unwrap();
+
+        let expected = "Category Group/Category,Category Group,Category,Budgeted,Activity,Available\r\n\
+            Home: Insurance,Home,Insurance,10.00,0.00,0.00\r\n";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn write_then_read() {
+        let mut group = BudgetGroup::new("Home, sweet home");
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Mine");
+        budget.add_group(group);
+
+        let mut out = Vec::new();
+        write(&budget, &mut out).unwrap();
+        let imported = read("Mine", out.as_slice()).unwrap();
+
+        assert_eq!(-900.0, imported.group("Home, sweet home").unwrap().total());
+    }
+}
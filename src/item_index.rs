@@ -0,0 +1,138 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::budget_item::{BudgetItem, ItemId};
+
+// Hash maps need the standard library, so without it the index falls back to ordered maps.
+#[cfg(feature = "std")]
+type Map<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+/// The positions of the items of a group by their identifier and by their name, so that looking
+/// items up does not scan the group.
+///
+/// The group keeps the index up to date with every change to its items: single insertions and
+/// removals shift the positions after them, and anything rearranging many items rebuilds it.
+#[derive(Debug, Default)]
+pub struct ItemIndex {
+    ids: Map<ItemId, usize>,
+    // The positions of the items with each name, in ascending order.
+    names: Map<String, Vec<usize>>,
+    // The number of indexed items, so that appending and removing the last item shift nothing.
+    len: usize,
+}
+
+impl ItemIndex {
+
+    /// Get the position of the item with an identifier, if the group holds it.
+    pub fn position(&self, id: ItemId) -> Option<usize> {
+        self.ids.get(&id).copied()
+    }
+
+    /// Get the positions of the items with a name, in ascending order.
+    pub fn positions(&self, name: &str) -> &[usize] {
+        self.names.get(name).map_or(&[], |positions| positions.as_slice())
+    }
+
+    /// Record an item inserted at a position, shifting the items from that position on.
+    pub fn inserted(&mut self, idx: usize, item: &BudgetItem) {
+        if idx < self.len {
+            self.shift(idx, |position| position + 1);
+        }
+        self.add(idx, item);
+    }
+
+    /// Record the removal of the item at a position, shifting the items after it.
+    pub fn removed(&mut self, idx: usize, item: &BudgetItem) {
+        if let Some(id) = item.id() {
+            self.ids.remove(&id);
+        }
+        if let Some(positions) = self.names.get_mut(item.name().as_str()) {
+            positions.retain(|position| *position != idx);
+            if positions.is_empty() {
+                self.names.remove(item.name().as_str());
+            }
+        }
+        self.len -= 1;
+        if idx < self.len {
+            self.shift(idx + 1, |position| position - 1);
+        }
+    }
+
+    /// Index the items from scratch, after they were rearranged.
+    pub fn rebuild(&mut self, items: &[BudgetItem]) {
+        self.ids.clear();
+        self.names.clear();
+        self.len = 0;
+        for (idx, item) in items.iter().enumerate() {
+            self.add(idx, item);
+        }
+    }
+
+    // Add an item at a position, without shifting the others.
+    fn add(&mut self, idx: usize, item: &BudgetItem) {
+        self.len += 1;
+        if let Some(id) = item.id() {
+            self.ids.insert(id, idx);
+        }
+        match self.names.get_mut(item.name().as_str()) {
+            Some(positions) => {
+                let at = positions.partition_point(|position| *position < idx);
+                positions.insert(at, idx);
+            }
+            None => {
+                self.names.insert(item.name().to_owned(), vec![idx]);
+            }
+        }
+    }
+
+    // Move every position from a position on, which keeps the positions of each name in order.
+    fn shift<F: Fn(usize) -> usize>(&mut self, from: usize, shift: F) {
+        for position in self.ids.values_mut().chain(self.names.values_mut().flatten()) {
+            if *position >= from {
+                *position = shift(*position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget_item::{BudgetItem, ItemId, Period};
+    use crate::item_index::ItemIndex;
+
+    fn item(name: &str, id: u64) -> BudgetItem {
+        let mut item = BudgetItem::with_expense(name, 10.0, Period::Every1Month);
+        item.set_id(Some(ItemId(id)));
+        item
+    }
+
+    #[test]
+    fn insert_and_remove_shift_positions() {
+        let mut index = ItemIndex::default();
+        index.inserted(0, &item("b", 1));
+        index.inserted(0, &item("a", 2));
+        index.inserted(2, &item("b", 3));
+        assert_eq!(Some(1), index.position(ItemId(1)));
+        assert_eq!(&[1, 2], index.positions("b"));
+
+        index.removed(0, &item("a", 2));
+        assert_eq!(None, index.position(ItemId(2)));
+        assert!(index.positions("a").is_empty());
+        assert_eq!(&[0, 1], index.positions("b"));
+        assert_eq!(Some(1), index.position(ItemId(3)));
+    }
+
+    #[test]
+    fn rebuild() {
+        let mut index = ItemIndex::default();
+        index.inserted(0, &item("stale", 9));
+        index.rebuild(&[item("a", 1), item("a", 2)]);
+
+        assert_eq!(None, index.position(ItemId(9)));
+        assert_eq!(&[0, 1], index.positions("a"));
+        assert_eq!(Some(1), index.position(ItemId(2)));
+    }
+}
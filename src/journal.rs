@@ -0,0 +1,407 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use crate::audit::Change;
+use crate::budget::{Budget, BudgetDefaults};
+use crate::budget_group::{BudgetGroup, ItemOrder};
+use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+use crate::document::{escape, parse_amount, unescape};
+use crate::locale::Locale;
+use crate::webhook::event_type;
+
+/// Error thrown when a journal cannot be read or replayed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalError {
+    /// An entry is not a known change, or has the wrong fields. Holds the 1-based line.
+    InvalidEntry(usize),
+    /// A change cannot be applied to the budget, e.g. because its group does not exist. Holds the
+    /// 0-based index of the change.
+    CannotApply(usize),
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::InvalidEntry(line) => write!(f, "invalid journal entry on line {}", line),
+            JournalError::CannotApply(idx) => write!(f, "journal change {} cannot be applied", idx),
+        }
+    }
+}
+
+impl core::error::Error for JournalError { }
+
+/// Write a change as a single journal entry, without a line break.
+///
+/// The entry starts with the [event type](../webhook/fn.event_type.html) of the change, followed
+/// by tab separated fields escaped as in a [document](../document/fn.to_string.html). Items are
+/// written as their kind, name, amount and period, and defaults as their currency, period,
+/// rounding, and the decimal and thousands separators of their locale, which are empty if there
+/// is none. External references of items are not kept.
+///
+/// # Parameters
+/// * `change` - the change to write.
+///
+/// # Returns
+/// The entry text.
+pub fn encode(change: &Change) -> String {
+    let mut entry = String::from(event_type(change));
+    match change {
+        Change::GroupAdded { group, index } => {
+            push_field(&mut entry, group);
+            // Writing to a String cannot fail.
+            let _ = write!(entry, "\t{}", index);
+        }
+        Change::GroupRemoved { group } => push_field(&mut entry, group),
+        Change::GroupRenamed { from, to } => {
+            push_field(&mut entry, from);
+            push_field(&mut entry, to);
+        }
+        Change::ItemAdded { group, item } | Change::ItemRemoved { group, item } => {
+            push_field(&mut entry, group);
+            push_item(&mut entry, item);
+        }
+        Change::ItemUpdated { group, from, to } => {
+            push_field(&mut entry, group);
+            push_item(&mut entry, from);
+            push_item(&mut entry, to);
+        }
+        Change::ItemMoved { from, to, item } => {
+            push_field(&mut entry, from);
+            push_field(&mut entry, to);
+            push_item(&mut entry, item);
+        }
+        Change::ItemReordered { group, item, to } => {
+            push_field(&mut entry, group);
+            push_item(&mut entry, item);
+            // Writing to a String cannot fail.
+            let _ = write!(entry, "\t{}", to);
+        }
+        Change::OrderChanged { group, order } => {
+            push_field(&mut entry, group);
+            push_field(&mut entry, order.keyword());
+        }
+        Change::DefaultsChanged { from, to } => {
+            push_defaults(&mut entry, from);
+            push_defaults(&mut entry, to);
+        }
+    }
+    entry
+}
+
+/// Read the journal entries written by [`encode`], one per line.
+///
+/// # Parameters
+/// * `text` - the journal text. Blank lines are skipped.
+///
+/// # Returns
+/// `Result::Ok` with the changes in order, or `Result::Err` with the line of the first invalid
+/// entry.
+pub fn decode(text: &str) -> Result<Vec<Change>, JournalError> {
+    let mut changes = Vec::new();
+    for (idx, line) in text.lines().enumerate().filter(|(_, line)| !line.is_empty()) {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let invalid = JournalError::InvalidEntry(idx + 1);
+        let item = |fields: &[&str]| read_item(fields).ok_or(JournalError::InvalidEntry(idx + 1));
+        let defaults = |fields: &[&str]| read_defaults(fields).ok_or(JournalError::InvalidEntry(idx + 1));
+        changes.push(match fields.as_slice() {
+            ["budget.group.added", group, index] =>
+                Change::GroupAdded { group: unescape(group), index: index.parse().map_err(|_| invalid)? },
+            ["budget.group.removed", group] => Change::GroupRemoved { group: unescape(group) },
+            ["budget.group.renamed", from, to] => Change::GroupRenamed { from: unescape(from), to: unescape(to) },
+            ["budget.item.added", group, rest @ ..] if rest.len() == 4 =>
+                Change::ItemAdded { group: unescape(group), item: item(rest)? },
+            ["budget.item.removed", group, rest @ ..] if rest.len() == 4 =>
+                Change::ItemRemoved { group: unescape(group), item: item(rest)? },
+            ["budget.item.updated", group, rest @ ..] if rest.len() == 8 =>
+                Change::ItemUpdated { group: unescape(group), from: item(&rest[..4])?, to: item(&rest[4..])? },
+            ["budget.item.moved", from, to, rest @ ..] if rest.len() == 4 =>
+                Change::ItemMoved { from: unescape(from), to: unescape(to), item: item(rest)? },
+            ["budget.item.reordered", group, rest @ ..] if rest.len() == 5 =>
+                Change::ItemReordered { group: unescape(group), item: item(&rest[..4])?, to: rest[4].parse().map_err(|_| invalid)? },
+            ["budget.group.order_changed", group, order] =>
+                Change::OrderChanged { group: unescape(group), order: ItemOrder::from_keyword(order).ok_or(invalid)? },
+            // Journals written before defaults had a locale have three fields per defaults.
+            ["budget.defaults.changed", rest @ ..] if rest.len() == 6 || rest.len() == 10 => {
+                let (from, to) = rest.split_at(rest.len() / 2);
+                Change::DefaultsChanged { from: defaults(from)?, to: defaults(to)? }
+            }
+            _ => return Err(invalid),
+        });
+    }
+    Ok(changes)
+}
+
+// Replay changes onto a budget, as described on `Budget::recover`.
+pub(crate) fn replay(budget: &mut Budget, changes: &[Change]) -> Result<(), JournalError> {
+    for (idx, change) in changes.iter().enumerate() {
+        apply(budget, change).ok_or(JournalError::CannotApply(idx))?;
+    }
+    Ok(())
+}
+
+// Apply a single change, matching groups by name and items exactly.
+fn apply(budget: &mut Budget, change: &Change) -> Option<()> {
+    let item_idx = |budget: &Budget, group: usize, item: &BudgetItem| budget.enumerate().nth(group)
+        .and_then(|(_, group)| group.enumerate().find(|(_, other)| other.identical(item)))
+        .map(|(idx, _)| idx);
+
+    match change {
+        // The items of the group follow as separate changes.
+        Change::GroupAdded { group, index } => budget.insert_group(*index, BudgetGroup::new(group)).ok()?,
+        Change::GroupRemoved { group } => {
+            budget.remove_group(budget.group_index(group)?).ok()?;
+        }
+        Change::GroupRenamed { from, to } => budget.rename_group(budget.group_index(from)?, to).ok()?,
+        Change::ItemAdded { group, item } => budget.add_item(budget.group_index(group)?, item.clone()).ok()?,
+        Change::ItemRemoved { group, item } => {
+            let group = budget.group_index(group)?;
+            budget.remove_item(group, item_idx(budget, group, item)?).ok()?;
+        }
+        Change::ItemUpdated { group, from, to } => {
+            let group = budget.group_index(group)?;
+            budget.update_item(group, item_idx(budget, group, from)?, to.clone()).ok()?;
+        }
+        Change::ItemMoved { from, to, item } => {
+            let from = budget.group_index(from)?;
+            let to = budget.group_index(to)?;
+            budget.move_item(from, item_idx(budget, from, item)?, to).ok()?;
+        }
+        Change::ItemReordered { group, item, to } => {
+            let group = budget.group_index(group)?;
+            budget.reorder_item(group, item_idx(budget, group, item)?, *to).ok()?;
+        }
+        Change::OrderChanged { group, order } => budget.set_group_order(budget.group_index(group)?, *order).ok()?,
+        Change::DefaultsChanged { to, .. } => budget.set_defaults(to.clone()),
+    }
+    Some(())
+}
+
+// Append a tab and an escaped field.
+fn push_field(entry: &mut String, value: &str) {
+    entry.push('\t');
+    entry.push_str(&escape(value));
+}
+
+// Append the fields of an item.
+fn push_item(entry: &mut String, item: &BudgetItem) {
+    // Writing to a String cannot fail.
+    let _ = write!(entry, "\t{}\t{}\t{}\t{}",
+                   if item.is_income() { "income" } else { "expense" },
+                   escape(item.name()),
+                   item.amount(),
+                   item.period().keyword());
+}
+
+// Append the fields of budget defaults.
+fn push_defaults(entry: &mut String, defaults: &BudgetDefaults) {
+    push_field(entry, &defaults.currency);
+    // Writing to a String cannot fail.
+    let _ = write!(entry, "\t{}\t{}", defaults.period.keyword(), defaults.rounding.keyword());
+    match defaults.locale {
+        Some(locale) => {
+            push_field(entry, &locale.decimal_separator.to_string());
+            push_field(entry, &locale.thousands_text());
+        }
+        None => entry.push_str("\t\t"),
+    }
+}
+
+// Read the currency, period, rounding and, if there are five fields, locale of budget defaults.
+fn read_defaults(fields: &[&str]) -> Option<BudgetDefaults> {
+    let locale = match &fields[3..] {
+        [] | ["", ""] => None,
+        [decimal, thousands] => Some(Locale::from_separators(&unescape(decimal), &unescape(thousands))?),
+        _ => return None,
+    };
+    Some(BudgetDefaults {
+        currency: unescape(fields[0]),
+        period: Period::from_keyword(fields[1])?,
+        rounding: RoundingPolicy::from_keyword(fields[2])?,
+        locale,
+    })
+}
+
+// Read the kind, name, amount and period of an item.
+fn read_item(fields: &[&str]) -> Option<BudgetItem> {
+    let amount = parse_amount(0, fields[2]).ok()?;
+    let period = Period::from_keyword(fields[3])?;
+    match fields[0] {
+        "income" => Some(BudgetItem::with_income(&unescape(fields[1]), amount, period)),
+        "expense" => Some(BudgetItem::with_expense(&unescape(fields[1]), amount, period)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::audit::Change;
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::{BudgetGroup, ItemOrder};
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::document;
+    use crate::journal::{decode, encode, JournalError};
+    use crate::locale::Locale;
+    use crate::undo::Edit;
+
+    #[test]
+    fn encode_and_decode() {
+        let rent = BudgetItem::with_expense("Rent\tflat", 900.5, Period::Every1Month);
+        let changes = vec![
+            Change::GroupAdded { group: "Housing".to_owned(), index: 0 },
+            Change::GroupRenamed { from: "Housing".to_owned(), to: "Home\\".to_owned() },
+            Change::ItemAdded { group: "Home\\".to_owned(), item: rent.clone() },
+            Change::ItemUpdated {
+                group: "Home\\".to_owned(),
+                from: rent,
+                to: BudgetItem::with_income("Rent out", 300.0, Period::Every3Months),
+            },
+            Change::ItemMoved {
+                from: "Home\\".to_owned(),
+                to: "Car".to_owned(),
+                item: BudgetItem::with_expense("Parking", 40.0, Period::Every1Month),
+            },
+            Change::DefaultsChanged {
+                from: BudgetDefaults::default(),
+                to: BudgetDefaults {
+                    currency: "kr\t".to_owned(),
+                    period: Period::Every3Months,
+                    rounding: RoundingPolicy::HalfEven,
+                    locale: Some(Locale { decimal_separator: ',', thousands_separator: None }),
+                },
+            },
+            Change::ItemReordered {
+                group: "Car".to_owned(),
+                item: BudgetItem::with_expense("Parking", 40.0, Period::Every1Month),
+                to: 2,
+            },
+            Change::OrderChanged { group: "Car".to_owned(), order: ItemOrder::Sorted },
+        ];
+        let text: String = changes.iter().map(|change| encode(change) + "\n").collect();
+
+        assert_eq!("budget.item.added\tHome\\\\\texpense\tRent\\tflat\t900.5\tmonthly", encode(&changes[2]));
+        assert_eq!("budget.defaults.changed\t\tmonthly\tnone\t\t\tkr\\t\tquarterly\thalf-even\t,\t", encode(&changes[5]));
+        let decoded = decode(&text).unwrap();
+        assert_eq!(format!("{:?}", changes), format!("{:?}", decoded));
+    }
+
+    #[test]
+    fn decode_errors() {
+        let error = |text| decode(text).err();
+        assert_eq!(Some(JournalError::InvalidEntry(2)), error("budget.group.added\tA\t0\nbudget.group.added\tB\n"));
+        assert_eq!(Some(JournalError::InvalidEntry(1)), error("budget.item.added\tA\texpense\tRent\tinf\tmonthly"));
+        assert_eq!(Some(JournalError::InvalidEntry(1)), error("budget.item.added\tA\tgift\tRent\t9\tmonthly"));
+        assert!(decode("\n\n").unwrap().is_empty());
+        assert_eq!(Some(JournalError::InvalidEntry(1)), error("budget.defaults.changed\t\tmonthly\tnone\t\t.\t€\tmonthly\tnone\t\t"));
+    }
+
+    #[test]
+    fn decode_defaults_without_locale() {
+        let changes = decode("budget.defaults.changed\t\tmonthly\tnone\t€\tyearly\thalf-up").unwrap();
+        match &changes[0] {
+            Change::DefaultsChanged { from, to } => {
+                assert_eq!(&BudgetDefaults::default(), from);
+                assert_eq!("€", to.currency);
+                assert_eq!(Period::Every12Months, to.period);
+                assert_eq!(None, to.locale);
+            }
+            change => panic!("unexpected change {:?}", change),
+        }
+    }
+
+    #[test]
+    fn recover_unsaved_edits() {
+        let mut budget = Budget::new("Home");
+        budget.add_group(BudgetGroup::new("Housing"));
+        let saved = document::to_string(&budget);
+
+        let journal = Rc::new(RefCell::new(String::new()));
+        let log = journal.clone();
+        budget.subscribe(move |change| {
+            let mut log = log.borrow_mut();
+            log.push_str(&encode(change));
+            log.push('\n');
+        });
+        budget.apply(Edit::AddItem(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month))).unwrap();
+        budget.apply(Edit::UpdateItem(0, 0, BudgetItem::with_expense("Rent", 950.0, Period::Every1Month))).unwrap();
+        budget.add_group(BudgetGroup::new("Food"));
+        budget.rename_group(1, "Groceries").unwrap();
+        budget.undo();
+
+        let recovered = Budget::recover(document::from_str(&saved).unwrap(), &decode(&journal.borrow()).unwrap()).unwrap();
+        assert_eq!(document::to_string(&budget), document::to_string(&recovered));
+        assert!(recovered.is_dirty());
+    }
+
+    #[test]
+    fn recover_groups_with_items() {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(BudgetGroup::new("Food"));
+        let saved = document::to_string(&budget);
+
+        let journal = Rc::new(RefCell::new(String::new()));
+        let log = journal.clone();
+        budget.subscribe(move |change| {
+            let mut log = log.borrow_mut();
+            log.push_str(&encode(change));
+            log.push('\n');
+        });
+        budget.apply(Edit::RemoveGroup(0)).unwrap();
+        budget.undo();
+        let mut car = BudgetGroup::new("Car");
+        car.add(BudgetItem::with_expense("Fuel", 80.0, Period::Every1Month));
+        budget.apply(Edit::InsertGroup(1, car)).unwrap();
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+
+        let recovered = Budget::recover(document::from_str(&saved).unwrap(), &decode(&journal.borrow()).unwrap()).unwrap();
+        assert_eq!(-980.0, recovered.total());
+        let names: Vec<&String> = recovered.enumerate().map(|(_, group)| group.name()).collect();
+        assert_eq!(vec!["Housing", "Car", "Food"], names);
+        assert_eq!(budget.defaults(), recovered.defaults());
+        assert_eq!(document::to_string(&budget), document::to_string(&recovered));
+    }
+
+    #[test]
+    fn recover_manual_order() {
+        let mut food = BudgetGroup::new("Food");
+        food.set_order(ItemOrder::Manual);
+        food.add(BudgetItem::with_expense("Lunch", 100.0, Period::Every1Month));
+        food.add(BudgetItem::with_expense("Dinner", 200.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(BudgetGroup::new("Housing"));
+        let saved = document::to_string(&budget);
+
+        let journal = Rc::new(RefCell::new(String::new()));
+        let log = journal.clone();
+        budget.subscribe(move |change| {
+            let mut log = log.borrow_mut();
+            log.push_str(&encode(change));
+            log.push('\n');
+        });
+        budget.add_group(food);
+        budget.add_item(0, BudgetItem::with_expense("Water", 30.0, Period::Every1Month)).unwrap();
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        budget.reorder_item(0, 1, 0).unwrap();
+        budget.set_group_order(1, ItemOrder::Sorted).unwrap();
+        budget.set_group_order(1, ItemOrder::Manual).unwrap();
+        budget.reorder_item(1, 0, 1).unwrap();
+
+        let recovered = Budget::recover(document::from_str(&saved).unwrap(), &decode(&journal.borrow()).unwrap()).unwrap();
+        assert_eq!(document::to_string(&budget), document::to_string(&recovered));
+        assert_eq!(ItemOrder::Manual, recovered.group("Housing").unwrap().order());
+    }
+
+    #[test]
+    fn recover_errors() {
+        let changes = vec![
+            Change::GroupAdded { group: "Housing".to_owned(), index: 0 },
+            Change::GroupRemoved { group: "Food".to_owned() },
+        ];
+        assert_eq!(Some(JournalError::CannotApply(1)), Budget::recover(Budget::new("Home"), &changes).err());
+    }
+}
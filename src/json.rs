@@ -0,0 +1,99 @@
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+use crate::budget::BudgetDefaults;
+use crate::budget_item::BudgetItem;
+
+// Append a string as a quoted JSON string, escaping quotes, backslashes and control characters.
+pub(crate) fn string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // Writing to a String cannot fail.
+            c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Append a number. JSON has no representation of infinities and NaN, so those become null.
+pub(crate) fn number(out: &mut String, value: f64) {
+    if value.is_finite() {
+        let _ = write!(out, "{}", value);
+    } else {
+        out.push_str("null");
+    }
+}
+
+// Append a budget item as an object with its name, kind, amount and period.
+pub(crate) fn item(out: &mut String, item: &BudgetItem) {
+    out.push_str("{\"name\":");
+    string(out, item.name());
+    out.push_str(",\"kind\":");
+    string(out, if item.is_income() { "income" } else { "expense" });
+    out.push_str(",\"amount\":");
+    number(out, item.amount());
+    out.push_str(",\"period\":");
+    string(out, item.period().keyword());
+    out.push('}');
+}
+
+// Append budget defaults as an object with their currency, period, rounding and locale.
+pub(crate) fn defaults(out: &mut String, defaults: &BudgetDefaults) {
+    out.push_str("{\"currency\":");
+    string(out, &defaults.currency);
+    out.push_str(",\"period\":");
+    string(out, defaults.period.keyword());
+    out.push_str(",\"rounding\":");
+    string(out, defaults.rounding.keyword());
+    out.push_str(",\"locale\":");
+    match defaults.locale {
+        Some(locale) => {
+            out.push_str("{\"decimal_separator\":");
+            string(out, &locale.decimal_separator.to_string());
+            out.push_str(",\"thousands_separator\":");
+            match locale.thousands_separator {
+                Some(separator) => string(out, &separator.to_string()),
+                None => out.push_str("null"),
+            }
+            out.push('}');
+        }
+        None => out.push_str("null"),
+    }
+    out.push('}');
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::json::{item, number, string};
+
+    #[test]
+    fn escaped_strings() {
+        let mut out = String::new();
+        string(&mut out, "Tom \"&\" Jerry\\\n\u{1}");
+        assert_eq!("\"Tom \\\"&\\\" Jerry\\\\\\n\\u0001\"", out);
+    }
+
+    #[test]
+    fn numbers() {
+        let mut out = String::new();
+        number(&mut out, 1_200.5);
+        out.push(' ');
+        number(&mut out, f64::NAN);
+        assert_eq!("1200.5 null", out);
+    }
+
+    #[test]
+    fn items() {
+        let mut out = String::new();
+        item(&mut out, &BudgetItem::with_expense("Rent", 900.0, Period::Every3Months));
+        assert_eq!("{\"name\":\"Rent\",\"kind\":\"expense\",\"amount\":900,\"period\":\"quarterly\"}", out);
+    }
+}
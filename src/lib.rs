@@ -2,9 +2,134 @@
 //!
 //! This crate contains all the business logic for the simple rust budget planner and is meant to
 //! be integrated with external UI executables or libraries.
+//!
+//! The business logic itself is pure computation, so the crate also builds without the standard
+//! library (`#![no_std]` with `alloc`) when the default `std` feature is disabled. The modules
+//! doing I/O, and the system clock, are only available with `std`.
+//!
+//! Optional subsystems sit behind cargo features, so consumers that do not need them, e.g.
+//! embedded or WebAssembly frontends, only build the core model:
+//!
+//! * `std` (default) - use the standard library.
+//! * `interop` (default) - the `interop` module, reading and writing the files of other budgeting
+//!   applications. Requires `std`.
+//! * `export` (default) - the `export` module, writing plain text accounting and CSV files.
+//!   Requires `std`.
+//! * `capi` - the `ffi` module, the C foreign function interface. Requires `std`.
+//! * `demo` - the `demo` module, generating synthetic budgets.
+//! * `fuzzy` - the `fuzzy` module, typo tolerant name matching.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 /// Module holding the core budget item type that acts as entries in a budget group.
 pub mod budget_item;
 
 /// Module holding the core budget group types that manages a collection of budget items.
 pub mod budget_group;
+
+/// Module holding the budget type that gathers budget groups into a complete budget.
+pub mod budget;
+
+/// Module holding the clock abstraction used as the source of time by date-dependent features.
+pub mod clock;
+
+/// Module holding the audit log of the structural changes made to a budget.
+pub mod audit;
+
+/// Module holding the change events passed to the callbacks observing a budget.
+pub mod observer;
+
+/// Module for serializing budget changes into JSON payloads for webhooks and message queues.
+pub mod webhook;
+
+/// Module for comparing budgets with each other.
+pub mod diff;
+
+/// Module holding the selectors for deleting groups and items, and the preview of their impact.
+pub mod delete;
+
+/// Module holding the frozen copies of a budget, used to track how it evolves.
+pub mod snapshot;
+
+/// Module holding the retention policies that bound the history kept with a budget.
+pub mod retention;
+
+/// Module holding the search hits used to look up groups and items by name.
+pub mod search;
+
+/// Module holding the what-if scenarios branched off a budget.
+pub mod scenario;
+
+/// Module holding the policies for resolving conflicts when merging budgets.
+pub mod merge;
+
+/// Module holding the reversible edits behind the undo and redo of budget changes.
+pub mod undo;
+
+/// Module holding the small, versioned summaries of a budget for home-screen widgets.
+pub mod widgets;
+
+/// Module for creating pre-populated budgets from common budgeting methods.
+pub mod templates;
+
+/// Module for generating synthetic demo budgets.
+#[cfg(feature = "demo")]
+pub mod demo;
+
+/// Module holding the crate's own text format for saving and loading budgets.
+pub mod document;
+
+/// Module holding the steps that upgrade documents written by older versions of the crate.
+pub mod migrations;
+
+/// Module holding the journal of changes used to recover unsaved edits.
+pub mod journal;
+
+/// Module for saving and loading budgets through swappable persistence backends.
+pub mod storage;
+
+/// Module for exchanging budgets with other budgeting applications.
+#[cfg(feature = "interop")]
+pub mod interop;
+
+/// Module for exporting budgets into formats consumed by other tools.
+#[cfg(feature = "export")]
+pub mod export;
+
+/// Module holding the options for presenting amounts to users.
+pub mod format;
+
+/// Module for reading and writing amounts according to the conventions of a locale.
+pub mod locale;
+
+/// Module for rendering budgets as human readable reports.
+pub mod report;
+
+/// Module for parsing and evaluating formulas that derive amounts from a budget.
+pub mod formula;
+
+/// Module for parsing and executing terse textual commands, shared by command line and chat
+/// frontends.
+pub mod commands;
+
+/// Module for typo tolerant matching of names, used by searches and matching rules.
+#[cfg(feature = "fuzzy")]
+pub mod fuzzy;
+
+/// Module exposing the budget to C and other languages with a C foreign function interface.
+#[cfg(feature = "capi")]
+pub mod ffi;
+
+/// Module with the CSV reading and writing shared by the import and export modules.
+/// Reading is only used for importing, so it is unused when only exporting is enabled.
+#[cfg(any(feature = "interop", feature = "export"))]
+#[cfg_attr(not(feature = "interop"), allow(dead_code))]
+mod csv;
+
+/// Module with the JSON writing shared by the modules serializing to JSON.
+mod json;
+
+/// Module with the index a budget group keeps to look up its items by identifier and by name.
+mod item_index;
@@ -0,0 +1,231 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::format::FormatOptions;
+
+/// The conventions for writing amounts in a region, e.g. `1,234.56` in the US and `1.234,56` in
+/// Germany, used to read amounts typed by users and to write them back.
+///
+/// ```
+/// use rbp_core::locale::Locale;
+/// let locale = Locale::from_tag("de-DE").unwrap();
+/// assert_eq!(Ok(1_234.56), locale.parse("1.234,56"));
+/// assert!(locale.parse("1,234.56").is_err());
+/// assert_eq!("1.234,56", locale.format(1_234.56));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// The character separating the integer part from the decimals.
+    pub decimal_separator: char,
+    /// The character grouping the thousands of the integer part, if any.
+    pub thousands_separator: Option<char>,
+}
+
+/// Error thrown when a text is not an amount written according to a locale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidAmount { }
+
+impl fmt::Display for InvalidAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid amount")
+    }
+}
+
+impl core::error::Error for InvalidAmount { }
+
+// The languages writing amounts as `1.234,56`.
+const DOT_GROUPED: &[&str] = &["da", "de", "el", "es", "id", "it", "nl", "pt", "ro", "tr"];
+
+// The languages writing amounts as `1 234,56`.
+const SPACE_GROUPED: &[&str] = &["cs", "fi", "fr", "hu", "nb", "nn", "no", "pl", "ru", "sk", "sv", "uk"];
+
+impl Locale {
+
+    /// The conventions of the US and the UK: `1,234.56`.
+    pub const EN_US: Locale = Locale { decimal_separator: '.', thousands_separator: Some(',') };
+
+    /// The conventions of most of continental Europe, e.g. Germany: `1.234,56`.
+    pub const DE_DE: Locale = Locale { decimal_separator: ',', thousands_separator: Some('.') };
+
+    /// The conventions of e.g. France and the Nordic countries: `1 234,56`. When parsing, no-break
+    /// spaces are accepted as well.
+    pub const FR_FR: Locale = Locale { decimal_separator: ',', thousands_separator: Some(' ') };
+
+    /// Find the conventions of a locale from its language tag.
+    ///
+    /// Only the language is considered, e.g. `en-US` and `en-GB` give the same conventions.
+    ///
+    /// # Parameters
+    /// * `tag` - the language tag, e.g. `de-DE`, `sv_SE` or `fr`. The case is ignored.
+    ///
+    /// # Returns
+    /// The conventions, or `None` if the language is not known.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next().unwrap_or("");
+        let known = |languages: &[&str]| languages.iter().any(|known| known.eq_ignore_ascii_case(language));
+        if language.eq_ignore_ascii_case("en") {
+            Some(Locale::EN_US)
+        } else if known(DOT_GROUPED) {
+            Some(Locale::DE_DE)
+        } else if known(SPACE_GROUPED) {
+            Some(Locale::FR_FR)
+        } else {
+            None
+        }
+    }
+
+    /// Read an amount written according to the locale, e.g. as typed by a user.
+    ///
+    /// The thousands separator is optional, but where it is used, it must group the integer part
+    /// by three digits, so that an amount written for another locale is not misread.
+    ///
+    /// # Parameters
+    /// * `text` - the amount, optionally with a leading sign. Surrounding whitespace is ignored.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the amount, or `Result::Err` if the text is not a finite amount.
+    pub fn parse(&self, text: &str) -> Result<f64, InvalidAmount> {
+        let text = text.trim();
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (integer, decimals) = match text.find(self.decimal_separator) {
+            Some(idx) => (&text[..idx], Some(&text[idx + self.decimal_separator.len_utf8()..])),
+            None => (text, None),
+        };
+
+        let mut number = String::with_capacity(text.len() + 1);
+        if negative {
+            number.push('-');
+        }
+        let groups: Vec<&str> = integer.split(|c| self.is_thousands_separator(c)).collect();
+        let grouped = groups.len() > 1;
+        for (idx, group) in groups.iter().enumerate() {
+            let valid = !group.is_empty()
+                && (!grouped || idx == 0 && group.len() <= 3 || group.len() == 3)
+                && group.chars().all(|c| c.is_ascii_digit());
+            if !valid {
+                return Err(InvalidAmount { });
+            }
+            number.push_str(group);
+        }
+        if let Some(decimals) = decimals {
+            if decimals.is_empty() || !decimals.chars().all(|c| c.is_ascii_digit()) {
+                return Err(InvalidAmount { });
+            }
+            number.push('.');
+            number.push_str(decimals);
+        }
+        match number.parse::<f64>() {
+            Ok(amount) if amount.is_finite() => Ok(amount),
+            _ => Err(InvalidAmount { }),
+        }
+    }
+
+    /// Write an amount according to the locale, with two decimals and without a currency symbol.
+    /// Use [`format_options`](#method.format_options) for more control.
+    ///
+    /// # Parameters
+    /// * `amount` - the amount to write.
+    pub fn format(&self, amount: f64) -> String {
+        self.format_options().amount(amount)
+    }
+
+    /// Get the options for formatting amounts according to the locale, which can be adjusted with
+    /// e.g. a currency symbol.
+    pub fn format_options(&self) -> FormatOptions {
+        FormatOptions {
+            decimal_separator: self.decimal_separator,
+            thousands_separator: self.thousands_separator,
+            ..FormatOptions::default()
+        }
+    }
+
+    // Whether a character separates thousands. Spaces are often typed or copied as no-break
+    // spaces, which are accepted for a space separator.
+    fn is_thousands_separator(&self, c: char) -> bool {
+        match self.thousands_separator {
+            Some(' ') => c == ' ' || c == '\u{a0}' || c == '\u{202f}',
+            Some(separator) => c == separator,
+            None => false,
+        }
+    }
+
+    // The locale with separators read from a document or journal, where each is a single
+    // character, and the thousands separator is empty if there is none.
+    pub(crate) fn from_separators(decimal: &str, thousands: &str) -> Option<Locale> {
+        let single = |text: &str| {
+            let mut chars = text.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        };
+        let thousands_separator = if thousands.is_empty() { None } else { Some(single(thousands)?) };
+        Some(Locale { decimal_separator: single(decimal)?, thousands_separator })
+    }
+
+    // The thousands separator as written to a document or journal, which is empty if there is
+    // none.
+    pub(crate) fn thousands_text(&self) -> String {
+        self.thousands_separator.map(String::from).unwrap_or_default()
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::EN_US
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::locale::{InvalidAmount, Locale};
+
+    #[test]
+    fn from_tag() {
+        assert_eq!(Some(Locale::EN_US), Locale::from_tag("en-GB"));
+        assert_eq!(Some(Locale::DE_DE), Locale::from_tag("ES"));
+        assert_eq!(Some(Locale::FR_FR), Locale::from_tag("sv_SE"));
+        assert_eq!(None, Locale::from_tag("xx-XX"));
+        assert_eq!(None, Locale::from_tag(""));
+    }
+
+    #[test]
+    fn parse_us() {
+        let locale = Locale::EN_US;
+        assert_eq!(Ok(1_234.56), locale.parse("1,234.56"));
+        assert_eq!(Ok(1_234.56), locale.parse(" 1234.56 "));
+        assert_eq!(Ok(-1_234_567.0), locale.parse("-1,234,567"));
+        assert_eq!(Ok(12.0), locale.parse("+12"));
+        assert_eq!(Err(InvalidAmount { }), locale.parse("1.234,56"));
+        assert_eq!(Err(InvalidAmount { }), locale.parse("1,5"));
+        assert_eq!(Err(InvalidAmount { }), locale.parse("1234,567"));
+        assert_eq!(Err(InvalidAmount { }), locale.parse(",123"));
+    }
+
+    #[test]
+    fn parse_european() {
+        assert_eq!(Ok(1_234.56), Locale::DE_DE.parse("1.234,56"));
+        assert_eq!(Ok(0.5), Locale::DE_DE.parse("0,5"));
+        assert_eq!(Err(InvalidAmount { }), Locale::DE_DE.parse("1,234.56"));
+        assert_eq!(Ok(1_234.56), Locale::FR_FR.parse("1 234,56"));
+        assert_eq!(Ok(1_234.56), Locale::FR_FR.parse("1\u{a0}234,56"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let locale = Locale::EN_US;
+        for text in &["", "-", "1.", ".5", "1.5.5", "1e5", "inf", "NaN", "$5", "--5", "1,,234"] {
+            assert_eq!(Err(InvalidAmount { }), locale.parse(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("1,234.56", Locale::EN_US.format(1_234.56));
+        assert_eq!("-1.234,56", Locale::DE_DE.format(-1_234.56));
+        assert_eq!("1 234,50", Locale::FR_FR.format(1_234.5));
+        for locale in &[Locale::EN_US, Locale::DE_DE, Locale::FR_FR] {
+            assert_eq!(Ok(9_876_543.21), locale.parse(&locale.format(9_876_543.21)));
+        }
+    }
+}
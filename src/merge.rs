@@ -0,0 +1,246 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::audit::Change;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::BudgetItem;
+
+/// How a conflict between two versions of an item is resolved when merging budgets or groups.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum MergePolicy {
+    /// Keep the item of the budget merged into.
+    KeepMine,
+    /// Replace the item with the one of the budget merged from.
+    KeepTheirs,
+    /// Keep the item, with the amount of the other item added to it. The other amount is first
+    /// converted to the period of the kept item, e.g. a yearly 1,200 adds 100 to a monthly item.
+    /// Amounts too large to add up leave the item as it is, like `KeepMine`.
+    SumAmounts,
+}
+
+/// An item that exists in both merged budgets, with a different amount or period.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// The name of the group holding the item.
+    pub group: String,
+    /// The item of the budget merged into.
+    pub mine: BudgetItem,
+    /// The item of the budget merged from.
+    pub theirs: BudgetItem,
+}
+
+// Merge another budget into a budget, as described on `Budget::merge_with`.
+pub(crate) fn merge<F: FnMut(&Conflict) -> MergePolicy>(budget: &mut Budget, other: &Budget, mut resolve: F) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    for change in budget.diff(other).changes() {
+        match change {
+            Change::GroupAdded { group, .. } => budget.add_group(BudgetGroup::new(group)),
+            Change::ItemAdded { group, item } => {
+                // The group either existed, or was added by a preceding change.
+                if let Some(group_idx) = budget.group_index(group) {
+                    // The index was just found, so it is valid.
+                    let _ = budget.add_item(group_idx, item.clone());
+                }
+            }
+            Change::ItemUpdated { group, from, to } => {
+                let conflict = Conflict { group: group.clone(), mine: from.clone(), theirs: to.clone() };
+                let merged = match resolve(&conflict) {
+                    MergePolicy::KeepMine => None,
+                    MergePolicy::KeepTheirs => Some(with_refs(to.clone(), from)),
+                    MergePolicy::SumAmounts => sum(from, to).map(|summed| with_refs(summed, to)),
+                };
+                // An item that cannot be found is left as it is, rather than replacing another one.
+                if let (Some(merged), Some((group_idx, item_idx))) = (merged, find_item(budget, group, from)) {
+                    // The indices were just found, so they are valid.
+                    let _ = budget.update_item(group_idx, item_idx, merged);
+                }
+                conflicts.push(conflict);
+            }
+            // What only exists in this budget is kept, and renames, moves, orders and defaults are
+            // not compared by the diff.
+            Change::GroupRemoved { .. } | Change::ItemRemoved { .. } | Change::GroupRenamed { .. }
+                | Change::ItemMoved { .. } | Change::ItemReordered { .. } | Change::OrderChanged { .. }
+                | Change::DefaultsChanged { .. } => (),
+        }
+    }
+    conflicts
+}
+
+// The group and item indices of the first item identical to an item, in any group with a name. The
+// groups of a diff are matched by name in order, so with duplicate group names the item may be in a
+// later group than the first one with the name.
+fn find_item(budget: &Budget, group: &str, item: &BudgetItem) -> Option<(usize, usize)> {
+    budget.enumerate()
+        .filter(|(_, other)| other.name() == group)
+        .find_map(|(group_idx, other)| other.enumerate()
+            .find(|(_, other)| other.identical(item))
+            .map(|(item_idx, _)| (group_idx, item_idx)))
+}
+
+// My item, with the amount of theirs converted to its period added, or `None` if the sum is too
+// large to be represented.
+pub(crate) fn sum(mine: &BudgetItem, theirs: &BudgetItem) -> Option<BudgetItem> {
+    let amount = mine.amount() + theirs.amount() * mine.period().months() as f64 / theirs.period().months() as f64;
+    if !amount.is_finite() {
+        return None;
+    }
+    let summed = if mine.is_income() {
+        BudgetItem::with_income(mine.name(), amount, mine.period())
+    } else {
+        BudgetItem::with_expense(mine.name(), amount, mine.period())
+    };
+    Some(with_refs(summed, mine))
+}
+
+// An item with the external references of another item added, for the providers it has no
+// reference to itself.
+pub(crate) fn with_refs(mut item: BudgetItem, other: &BudgetItem) -> BudgetItem {
+    for external_ref in other.external_refs() {
+        if !item.external_refs().iter().any(|existing| existing.provider == external_ref.provider) {
+            item.add_external_ref(external_ref.clone());
+        }
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, ExternalRef, Period};
+    use crate::merge::MergePolicy;
+
+    fn laptop() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Water", 30.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(BudgetGroup::new("Food"));
+        budget
+    }
+
+    fn phone() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 950.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Insurance", 240.0, Period::Every12Months));
+        let mut car = BudgetGroup::new("Car");
+        car.add(BudgetItem::with_expense("Fuel", 50.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(car);
+        budget
+    }
+
+    fn amount(budget: &Budget, group: &str, item: &str) -> f64 {
+        budget.group(group).unwrap().find(item).unwrap().amount()
+    }
+
+    #[test]
+    fn merge_is_a_union() {
+        let mut budget = laptop();
+        let conflicts = budget.merge(&phone(), MergePolicy::KeepMine);
+
+        let groups: Vec<&String> = budget.enumerate().map(|(_, group)| group.name()).collect();
+        assert_eq!(vec!["Housing", "Food", "Car"], groups);
+        assert_eq!(3, budget.group("Housing").unwrap().enumerate().len());
+        assert_eq!(-50.0, budget.group("Car").unwrap().total());
+        assert_eq!(1, conflicts.len());
+        assert_eq!("Housing", conflicts[0].group);
+    }
+
+    #[test]
+    fn policies() {
+        let mut mine = laptop();
+        mine.merge(&phone(), MergePolicy::KeepMine);
+        assert_eq!(900.0, amount(&mine, "Housing", "Rent"));
+
+        let mut theirs = laptop();
+        theirs.merge(&phone(), MergePolicy::KeepTheirs);
+        assert_eq!(950.0, amount(&theirs, "Housing", "Rent"));
+
+        let mut summed = laptop();
+        summed.merge(&phone(), MergePolicy::SumAmounts);
+        assert_eq!(1_850.0, amount(&summed, "Housing", "Rent"));
+    }
+
+    #[test]
+    fn sum_converts_periods() {
+        let mut budget = laptop();
+        let mut other = Budget::new("Home");
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Water", 120.0, Period::Every12Months));
+        other.add_group(housing);
+
+        budget.merge(&other, MergePolicy::SumAmounts);
+        assert_eq!(40.0, amount(&budget, "Housing", "Water"));
+    }
+
+    #[test]
+    fn sum_too_large_keeps_mine() {
+        let huge = |amount: f64| {
+            let mut housing = BudgetGroup::new("Housing");
+            housing.add(BudgetItem::with_expense("Rent", amount, Period::Every1Month));
+            let mut budget = Budget::new("Home");
+            budget.add_group(housing);
+            budget
+        };
+        let mut budget = huge(f64::MAX);
+
+        let conflicts = budget.merge(&huge(f64::MAX / 2.0), MergePolicy::SumAmounts);
+        assert_eq!(1, conflicts.len());
+        assert_eq!(f64::MAX, amount(&budget, "Housing", "Rent"));
+    }
+
+    #[test]
+    fn resolve_per_conflict() {
+        let mut budget = laptop();
+        let mut other = phone();
+        other.add_item(0, BudgetItem::with_expense("Water", 35.0, Period::Every1Month)).unwrap();
+
+        let conflicts = budget.merge_with(&other, |conflict| {
+            if conflict.mine.name() == "Rent" { MergePolicy::KeepTheirs } else { MergePolicy::KeepMine }
+        });
+        assert_eq!(2, conflicts.len());
+        assert_eq!(950.0, amount(&budget, "Housing", "Rent"));
+        assert_eq!(30.0, amount(&budget, "Housing", "Water"));
+    }
+
+    #[test]
+    fn duplicate_group_names() {
+        let budget = |water: f64| {
+            let mut first = BudgetGroup::new("Housing");
+            first.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+            let mut second = BudgetGroup::new("Housing");
+            second.add(BudgetItem::with_expense("Water", water, Period::Every1Month));
+            let mut budget = Budget::new("Home");
+            budget.add_group(first);
+            budget.add_group(second);
+            budget
+        };
+
+        let mut merged = budget(30.0);
+        assert_eq!(1, merged.merge(&budget(40.0), MergePolicy::KeepTheirs).len());
+        let items: Vec<(String, f64)> = merged.enumerate()
+            .flat_map(|(_, group)| group.iter().map(|item| (item.name().clone(), item.amount())))
+            .collect();
+        assert_eq!(vec![("Rent".to_owned(), 900.0), ("Water".to_owned(), 40.0)], items);
+    }
+
+    #[test]
+    fn external_refs_are_kept() {
+        let reference = |provider: &str| ExternalRef { provider: provider.to_owned(), external_id: "1".to_owned(), url: None };
+        let mut budget = laptop();
+        let mut rent = BudgetItem::with_expense("Rent", 900.0, Period::Every1Month);
+        rent.add_external_ref(reference("bank"));
+        budget.update_item(0, 0, rent).unwrap();
+        let mut other = phone();
+        let mut rent = BudgetItem::with_expense("Rent", 950.0, Period::Every1Month);
+        rent.add_external_ref(reference("invoices"));
+        other.update_item(0, 1, rent).unwrap();
+
+        budget.merge(&other, MergePolicy::KeepTheirs);
+        let rent = budget.group("Housing").unwrap().find("Rent").unwrap();
+        assert_eq!(&[reference("invoices"), reference("bank")], rent.external_refs());
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use crate::document::{MAGIC, VERSION};
+
+/// A step upgrading a document from one format version to the next.
+#[derive(Debug, Copy, Clone)]
+pub struct Migration {
+    /// The version the step upgrades from. It upgrades to the version after it.
+    pub from: u32,
+    /// What the step changes, e.g. "rename the period names".
+    pub description: &'static str,
+    /// The function rewriting a document. It gets the whole document, and only has to change the
+    /// entries; the version in the header is updated afterwards.
+    pub apply: fn(&str) -> Result<String, String>,
+}
+
+/// The steps upgrading documents written by older versions of the crate, in order. Every format
+/// change that is not backwards compatible adds a step here, so documents of any older version
+/// keep loading.
+///
+/// Version 1 is the first version of the format, so there are no steps yet.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Error thrown when a document cannot be upgraded to the current format version.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    /// The text does not start with a budget document header.
+    UnknownFormat,
+    /// The document is written by a newer version of the crate. Holds its version.
+    NewerVersion(u32),
+    /// There is no step upgrading from a version. Holds the version.
+    MissingStep(u32),
+    /// A step failed. Holds the version it upgrades from, and the reason.
+    StepFailed(u32, String),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::UnknownFormat => write!(f, "not a budget document"),
+            MigrationError::NewerVersion(version) => write!(f, "document version {} is newer than this version of the crate supports", version),
+            MigrationError::MissingStep(version) => write!(f, "no migration from document version {}", version),
+            MigrationError::StepFailed(version, reason) => write!(f, "migration from document version {} failed: {}", version, reason),
+        }
+    }
+}
+
+impl core::error::Error for MigrationError { }
+
+/// Detect the format version of a document.
+///
+/// # Parameters
+/// * `text` - the document text.
+///
+/// # Returns
+/// The version, or `None` if the text does not start with a budget document header.
+pub fn detect_version(text: &str) -> Option<u32> {
+    let header = text.lines().next()?;
+    let mut fields = header.split('\t');
+    if fields.next() != Some(MAGIC) {
+        return None;
+    }
+    fields.next()?.parse().ok()
+}
+
+/// Upgrade a document to the current format version, by applying the [`MIGRATIONS`] from its
+/// version onwards in order.
+///
+/// [`document::from_str`](../document/fn.from_str.html) does this on its own, so this is only
+/// needed to upgrade documents without reading them, e.g. to rewrite all stored files.
+///
+/// # Parameters
+/// * `text` - the document text.
+///
+/// # Returns
+/// `Result::Ok` with the upgraded document, which is the given text itself if it already has the
+/// current version, or `Result::Err` if the document cannot be upgraded.
+pub fn migrate(text: &str) -> Result<Cow<'_, str>, MigrationError> {
+    migrate_with(text, MIGRATIONS, VERSION)
+}
+
+// Upgrade a document with the given steps to the target version.
+fn migrate_with<'a>(text: &'a str, steps: &[Migration], target: u32) -> Result<Cow<'a, str>, MigrationError> {
+    let mut version = detect_version(text).ok_or(MigrationError::UnknownFormat)?;
+    if version > target {
+        return Err(MigrationError::NewerVersion(version));
+    }
+
+    let mut doc = Cow::Borrowed(text);
+    while version < target {
+        let step = steps.iter().find(|step| step.from == version).ok_or(MigrationError::MissingStep(version))?;
+        let migrated = (step.apply)(&doc).map_err(|reason| MigrationError::StepFailed(version, reason))?;
+        version += 1;
+        doc = Cow::Owned(with_version(&migrated, version));
+    }
+    Ok(doc)
+}
+
+// Replace the version in the header of a document.
+fn with_version(doc: &str, version: u32) -> String {
+    let body = doc.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    format!("{}\t{}\n{}", MAGIC, version, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+    use crate::migrations::{detect_version, migrate, migrate_with, Migration, MigrationError};
+
+    const STEPS: &[Migration] = &[
+        Migration {
+            from: 2,
+            description: "rename \"per-month\" to \"monthly\"",
+            apply: |doc| Ok(doc.replace("per-month", "monthly")),
+        },
+        Migration {
+            from: 1,
+            description: "rename \"month\" to \"per-month\"",
+            apply: |doc| Ok(doc.replace("\tmonth\n", "\tper-month\n")),
+        },
+        Migration {
+            from: 3,
+            description: "fail",
+            apply: |_| Err("broken".to_owned()),
+        },
+    ];
+
+    #[test]
+    fn detect() {
+        assert_eq!(Some(1), detect_version("rbp-budget\t1\nname\tHome\n"));
+        assert_eq!(Some(7), detect_version("rbp-budget\t7"));
+        assert_eq!(None, detect_version("rbp-budget\tx\n"));
+        assert_eq!(None, detect_version("budget\t1\n"));
+        assert_eq!(None, detect_version(""));
+    }
+
+    #[test]
+    fn steps_apply_in_order() {
+        let doc = migrate_with("rbp-budget\t1\nperiod\tmonth\n", STEPS, 3).unwrap();
+        assert_eq!("rbp-budget\t3\nperiod\tmonthly\n", doc);
+    }
+
+    #[test]
+    fn current_version_is_borrowed() {
+        let text = "rbp-budget\t1\nname\tHome\n";
+        assert!(matches!(migrate(text), Ok(Cow::Borrowed(doc)) if doc == text));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(MigrationError::UnknownFormat), migrate_with("budget", STEPS, 3));
+        assert_eq!(Err(MigrationError::NewerVersion(4)), migrate_with("rbp-budget\t4\n", STEPS, 3));
+        assert_eq!(Err(MigrationError::MissingStep(0)), migrate_with("rbp-budget\t0\n", STEPS, 3));
+        assert_eq!(Err(MigrationError::StepFailed(3, "broken".to_owned())), migrate_with("rbp-budget\t3\n", STEPS, 4));
+    }
+}
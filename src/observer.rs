@@ -0,0 +1,84 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use crate::audit::Change;
+
+/// The event passed to the callbacks subscribed to a budget or a group. It is the same change
+/// that is recorded in the audit log, so it can also be turned into a webhook payload with
+/// [`webhook::payload`](../webhook/fn.payload.html).
+pub type ChangeEvent = Change;
+
+/// Handle of a subscribed callback, used to unsubscribe it again.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Subscription(u64);
+
+// A subscribed callback.
+type Callback = Box<dyn FnMut(&ChangeEvent)>;
+
+// The callbacks subscribed to a budget or group, in subscription order.
+#[derive(Default)]
+pub(crate) struct Observers {
+    next: u64,
+    callbacks: Vec<(Subscription, Callback)>,
+}
+
+impl Observers {
+    pub(crate) fn subscribe(&mut self, callback: Callback) -> Subscription {
+        let subscription = Subscription(self.next);
+        self.next += 1;
+        self.callbacks.push((subscription, callback));
+        subscription
+    }
+
+    // Remove a callback, returning whether it was subscribed.
+    pub(crate) fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let count = self.callbacks.len();
+        self.callbacks.retain(|(other, _)| *other != subscription);
+        self.callbacks.len() < count
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    pub(crate) fn notify(&mut self, event: &ChangeEvent) {
+        for (_, callback) in self.callbacks.iter_mut() {
+            callback(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::boxed::Box;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::audit::Change;
+    use crate::observer::Observers;
+
+    #[test]
+    fn notify_in_subscription_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut observers = Observers::default();
+        for id in 0..3 {
+            let calls = calls.clone();
+            observers.subscribe(Box::new(move |_| calls.borrow_mut().push(id)));
+        }
+
+        observers.notify(&Change::GroupAdded { group: "Housing".to_owned(), index: 0 });
+        assert_eq!(vec![0, 1, 2], *calls.borrow());
+    }
+
+    #[test]
+    fn unsubscribe() {
+        let calls = Rc::new(RefCell::new(0));
+        let mut observers = Observers::default();
+        let counter = calls.clone();
+        let subscription = observers.subscribe(Box::new(move |_| *counter.borrow_mut() += 1));
+
+        assert!(observers.unsubscribe(subscription));
+        assert!(!observers.unsubscribe(subscription));
+        assert!(observers.is_empty());
+        observers.notify(&Change::GroupAdded { group: "Housing".to_owned(), index: 0 });
+        assert_eq!(0, *calls.borrow());
+    }
+}
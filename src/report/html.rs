@@ -0,0 +1,148 @@
+use alloc::string::String;
+use core::fmt::Write;
+use crate::budget::Budget;
+use crate::format::FormatOptions;
+use crate::report::income_and_expenses;
+
+/// The inline style sheet, keeping the document self-contained.
+const STYLE: &str = "body{font-family:sans-serif;max-width:50em;margin:2em auto;color:#222}\
+table{border-collapse:collapse;width:100%;margin-bottom:1.5em}\
+th,td{padding:.3em .6em;border-bottom:1px solid #ddd;text-align:left}\
+td.num,th.num{text-align:right}\
+tr.total td{font-weight:bold;border-top:2px solid #222}\
+.income{color:#1a7f37}.expense{color:#b42318}\
+.bar{background:#eee;width:12em}.bar div{background:#b42318;height:.8em}";
+
+/// Render a budget as a self-contained, styled HTML document.
+///
+/// The document holds a summary table with the monthly income, expenses, and net, a table of the
+/// groups with each group's share of the total expenses drawn as an inline bar, and a table per
+/// group listing its items. No external style sheets, scripts or images are referenced, so the
+/// document can be saved and shared as a single file.
+///
+/// # Parameters
+/// * `budget` - the budget to render.
+/// * `options` - how amounts are formatted, e.g. the currency symbol and separators.
+///
+/// # Returns
+/// The HTML document.
+pub fn render(budget: &Budget, options: &FormatOptions) -> String {
+    let mut doc = String::new();
+    let (income, expenses) = income_and_expenses(budget);
+    let name = escape(budget.name());
+
+    // Writing to a String cannot fail.
+    let _ = write!(doc, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = write!(doc, "<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", name, STYLE);
+    let _ = writeln!(doc, "<h1>{}</h1>", name);
+
+    let _ = writeln!(doc, "<h2>Summary</h2>\n<table>");
+    let _ = writeln!(doc, "<tr><td>Income</td><td class=\"num income\">{}</td></tr>", escape(&options.amount(income)));
+    let _ = writeln!(doc, "<tr><td>Expenses</td><td class=\"num expense\">{}</td></tr>", escape(&options.amount(expenses)));
+    let _ = writeln!(doc, "<tr class=\"total\"><td>Net</td><td class=\"num\">{}</td></tr>", escape(&options.amount(budget.total())));
+    let _ = writeln!(doc, "</table>");
+
+    let _ = writeln!(doc, "<h2>Groups</h2>\n<table>");
+    let _ = writeln!(doc, "<tr><th>Group</th><th class=\"num\">Monthly</th><th>Share of expenses</th></tr>");
+    for (_, group) in budget.enumerate() {
+        let group_expenses: f64 = group.enumerate()
+            .filter(|(_, item)| !item.is_income())
+            .map(|(_, item)| item.monthly_contribution())
+            .sum();
+        let share = if expenses < 0.0 { group_expenses / expenses * 100.0 } else { 0.0 };
+        let _ = writeln!(doc, "<tr><td>{}</td><td class=\"num\">{}</td><td><div class=\"bar\" title=\"{:.1}%\"><div style=\"width:{:.1}%\"></div></div></td></tr>",
+                         escape(group.name()), escape(&options.amount(group.rounded_total(budget.defaults().rounding))), share, share);
+    }
+    let _ = writeln!(doc, "</table>");
+
+    for (_, group) in budget.enumerate() {
+        let _ = writeln!(doc, "<h2>{}</h2>\n<table>", escape(group.name()));
+        let _ = writeln!(doc, "<tr><th>Item</th><th>Period</th><th class=\"num\">Amount</th><th class=\"num\">Monthly</th></tr>");
+        for (_, item) in group.enumerate() {
+            let _ = writeln!(doc, "<tr class=\"{}\"><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                             if item.is_income() { "income" } else { "expense" },
+                             escape(item.name()),
+                             item.period().label(),
+                             escape(&options.amount(item.amount())),
+                             escape(&options.amount(item.monthly_contribution())));
+        }
+        let _ = writeln!(doc, "<tr class=\"total\"><td>Total</td><td></td><td></td><td class=\"num\">{}</td></tr>", escape(&options.amount(group.rounded_total(budget.defaults().rounding))));
+        let _ = writeln!(doc, "</table>");
+    }
+
+    let _ = write!(doc, "</body>\n</html>\n");
+    doc
+}
+
+// Escape the characters with a special meaning in HTML text and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::format::FormatOptions;
+    use crate::report::html::{escape, render};
+
+    fn budget() -> Budget {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing & co");
+        housing.add(BudgetItem::with_expense("Rent", 750.0, Period::Every1Month));
+        let mut food = BudgetGroup::new("Food");
+        food.add(BudgetItem::with_expense("Groceries", 250.0, Period::Every1Month));
+        let mut budget = Budget::new("<Home>");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget.add_group(food);
+        budget
+    }
+
+    #[test]
+    fn render_is_self_contained() {
+        let html = render(&budget(), &FormatOptions::default());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>\n"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn render_content() {
+        let html = render(&budget(), &FormatOptions::default());
+
+        assert!(html.contains("<h1>&lt;Home&gt;</h1>"));
+        assert!(html.contains("<h2>Housing &amp; co</h2>"));
+        assert!(html.contains("<tr class=\"total\"><td>Net</td><td class=\"num\">2,000.00</td></tr>"));
+        assert!(html.contains("<tr class=\"expense\"><td>Rent</td><td>Monthly</td><td class=\"num\">750.00</td><td class=\"num\">-750.00</td></tr>"));
+    }
+
+    #[test]
+    fn render_expense_shares() {
+        let html = render(&budget(), &FormatOptions::default());
+
+        assert!(html.contains("<td>Housing &amp; co</td><td class=\"num\">-750.00</td><td><div class=\"bar\" title=\"75.0%\"><div style=\"width:75.0%\"></div></div></td>"));
+        assert!(html.contains("<td>Income</td><td class=\"num\">3,000.00</td><td><div class=\"bar\" title=\"0.0%\">"));
+    }
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!("&lt;b&gt;Tom &amp; Jerry&#39;s &quot;fund&quot;&lt;/b&gt;", escape("<b>Tom & Jerry's \"fund\"</b>"));
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::string::String;
+use core::fmt::Write;
+use crate::budget::Budget;
+use crate::format::FormatOptions;
+use crate::report::income_and_expenses;
+
+/// Render a full budget as a Markdown document.
+///
+/// The document starts with a summary section holding the monthly income, expenses, and net of
+/// the budget, and a table with the monthly total of every group. It is followed by a section per
+/// group, with a table listing the group's items and its total.
+///
+/// # Parameters
+/// * `budget` - the budget to render.
+/// * `options` - how amounts are formatted, e.g. the currency symbol and separators.
+///
+/// # Returns
+/// The Markdown document.
+pub fn render(budget: &Budget, options: &FormatOptions) -> String {
+    let mut doc = String::new();
+    let (income, expenses) = income_and_expenses(budget);
+
+    // Writing to a String cannot fail.
+    let _ = writeln!(doc, "# {}\n", escape(budget.name()));
+    let _ = writeln!(doc, "## Summary\n");
+    let _ = writeln!(doc, "| | Monthly |");
+    let _ = writeln!(doc, "|---|---:|");
+    let _ = writeln!(doc, "| Income | {} |", options.amount(income));
+    let _ = writeln!(doc, "| Expenses | {} |", options.amount(expenses));
+    let _ = writeln!(doc, "| **Net** | **{}** |", options.amount(budget.total()));
+    let _ = writeln!(doc, "\n| Group | Monthly |");
+    let _ = writeln!(doc, "|---|---:|");
+    for (_, group) in budget.enumerate() {
+        let _ = writeln!(doc, "| {} | {} |", escape(group.name()), options.amount(group.rounded_total(budget.defaults().rounding)));
+    }
+
+    for (_, group) in budget.enumerate() {
+        let _ = writeln!(doc, "\n## {}\n", escape(group.name()));
+        let _ = writeln!(doc, "| Item | Kind | Amount | Period | Monthly |");
+        let _ = writeln!(doc, "|---|---|---:|---|---:|");
+        for (_, item) in group.enumerate() {
+            let _ = writeln!(doc, "| {} | {} | {} | {} | {} |",
+                             escape(item.name()),
+                             if item.is_income() { "Income" } else { "Expense" },
+                             options.amount(item.amount()),
+                             item.period().label(),
+                             options.amount(item.monthly_contribution()));
+        }
+        let _ = writeln!(doc, "| **Total** | | | | **{}** |", options.amount(group.rounded_total(budget.defaults().rounding)));
+    }
+
+    doc
+}
+
+// Escape the characters that would break a table cell or start unintended formatting.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '|' | '*' | '_' | '`' | '\\' | '[' | ']' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(if c == '\n' { ' ' } else { c });
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::format::FormatOptions;
+    use crate::report::markdown::{escape, render};
+
+    #[test]
+    fn render_budget() {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Water", 90.0, Period::Every3Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        let options = FormatOptions { currency_symbol: "€".to_owned(), ..FormatOptions::default() };
+
+        let expected = "# Home\n\
+            \n\
+            ## Summary\n\
+            \n\
+            | | Monthly |\n\
+            |---|---:|\n\
+            | Income | €3,000.00 |\n\
+            | Expenses | -€1,230.00 |\n\
+            | **Net** | **€1,770.00** |\n\
+            \n\
+            | Group | Monthly |\n\
+            |---|---:|\n\
+            | Income | €3,000.00 |\n\
+            | Housing | -€1,230.00 |\n\
+            \n\
+            ## Income\n\
+            \n\
+            | Item | Kind | Amount | Period | Monthly |\n\
+            |---|---|---:|---|---:|\n\
+            | Salary | Income | €3,000.00 | Monthly | €3,000.00 |\n\
+            | **Total** | | | | **€3,000.00** |\n\
+            \n\
+            ## Housing\n\
+            \n\
+            | Item | Kind | Amount | Period | Monthly |\n\
+            |---|---|---:|---|---:|\n\
+            | Rent | Expense | €1,200.00 | Monthly | -€1,200.00 |\n\
+            | Water | Expense | €90.00 | Quarterly | -€30.00 |\n\
+            | **Total** | | | | **-€1,230.00** |\n";
+        assert_eq!(expected, render(&budget, &options));
+    }
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!("TV \\| Internet", escape("TV | Internet"));
+        assert_eq!("\\*Misc\\* line", escape("*Misc*\nline"));
+    }
+}
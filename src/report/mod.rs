@@ -0,0 +1,535 @@
+use alloc::format;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{saturate, saturating_sum, BudgetItem, Period};
+use crate::format::FormatOptions;
+use crate::search;
+
+/// Module for rendering a budget as a Markdown document.
+pub mod markdown;
+
+/// Module for rendering a budget as a self-contained HTML document.
+pub mod html;
+
+/// Module for rendering a budget as plain-text tables for terminals.
+pub mod text;
+
+/// Describe a budget in plain-language sentences.
+///
+/// The sentences cover the size of the budget, the planned monthly income and expenses, the share
+/// of the income that is spent, what is left over, and the largest expense and expense group. They
+/// are meant for frontends that cannot show tables, such as screen readers, chat bots, and voice
+/// assistants. Amounts are written with the budget's default currency.
+///
+/// # Parameters
+/// * `budget` - the budget to describe.
+///
+/// # Returns
+/// The sentences, in reading order.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::report::describe;
+/// let mut group = BudgetGroup::new("Home");
+/// group.add(BudgetItem::with_income("Salary", 2_000.0, Period::Every1Month));
+/// group.add(BudgetItem::with_expense("Rent", 1_240.0, Period::Every1Month));
+/// let mut budget = Budget::new("Mine");
+/// budget.add_group(group);
+///
+/// let sentences = describe(&budget);
+/// assert!(sentences.contains(&"You plan to spend 62% of your income.".to_owned()));
+/// assert!(sentences.contains(&"Your largest expense is Rent at 1,240.00 per month.".to_owned()));
+/// ```
+pub fn describe(budget: &Budget) -> Vec<String> {
+    let options = budget_format(budget);
+    let item_count: usize = budget.enumerate().map(|(_, group)| group.enumerate().len()).sum();
+    if item_count == 0 {
+        return vec![format!("Your budget \"{}\" is empty.", budget.name())];
+    }
+
+    let mut sentences = Vec::new();
+    let group_count = budget.enumerate().len();
+    sentences.push(format!("Your budget \"{}\" has {} {} with {} {}.", budget.name(),
+                           group_count, plural(group_count, "group", "groups"),
+                           item_count, plural(item_count, "item", "items")));
+
+    let (income, expenses) = income_and_expenses(budget);
+    sentences.push(format!("You plan a monthly income of {} and monthly expenses of {}.",
+                           options.amount(income), options.amount(-expenses)));
+    if income > 0.0 {
+        sentences.push(format!("You plan to spend {:.0}% of your income.", -expenses / income * 100.0));
+    } else {
+        sentences.push("You have not planned any income.".to_owned());
+    }
+
+    let net = budget.total();
+    if net > 0.0 {
+        sentences.push(format!("This leaves {} each month.", options.amount(net)));
+    } else if net < 0.0 {
+        sentences.push(format!("This leaves a shortfall of {} each month.", options.amount(-net)));
+    } else {
+        sentences.push("Your income and expenses balance exactly.".to_owned());
+    }
+
+    let largest_item = budget.enumerate()
+        .flat_map(|(_, group)| group.enumerate().map(|(_, item)| item))
+        .filter(|item| !item.is_income())
+        .fold(None, |largest: Option<&BudgetItem>, item| match largest {
+            Some(largest) if largest.monthly_contribution() <= item.monthly_contribution() =>
+                Some(largest),
+            _ => Some(item),
+        });
+    if let Some(item) = largest_item {
+        let per_month = options.amount(-item.monthly_contribution());
+        if item.period() == Period::Every1Month {
+            sentences.push(format!("Your largest expense is {} at {} per month.", item.name(), per_month));
+        } else {
+            sentences.push(format!("Your largest expense is {} at {} {}, or {} per month.", item.name(),
+                                   options.amount(item.amount()), period_phrase(item.period()), per_month));
+        }
+    }
+
+    let largest_group = budget.enumerate()
+        .map(|(_, group)| (group, -group.rounded_total(budget.defaults().rounding)))
+        .filter(|(_, cost)| *cost > 0.0)
+        .fold(None, |largest: Option<(&BudgetGroup, f64)>, next| match largest {
+            Some(largest) if largest.1 >= next.1 => Some(largest),
+            _ => Some(next),
+        });
+    if let (Some((group, cost)), true) = (largest_group, group_count > 1) {
+        sentences.push(format!("Your most expensive group is {} at {} per month.",
+                               group.name(), options.amount(cost)));
+    }
+
+    sentences
+}
+
+/// A table comparing budgets side by side, e.g. a baseline and its what-if scenarios, as returned by
+/// [`compare`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// The names of the compared budgets, one per column.
+    pub budgets: Vec<String>,
+    /// One row per group, in the order of the first budget, followed by the groups that only
+    /// exist in later budgets.
+    pub groups: Vec<ComparisonRow>,
+    /// The row with the monthly totals of the budgets.
+    pub net: ComparisonRow,
+}
+
+/// A row of a [`Comparison`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRow {
+    /// The name of the group, or `"Net"` for the totals.
+    pub label: String,
+    /// The monthly figure of every budget, in the order of the budgets. A group that is missing
+    /// from a budget has a figure of 0.
+    pub values: Vec<f64>,
+    /// The difference of every figure to that of the first budget, so the first delta is always 0.
+    pub deltas: Vec<f64>,
+}
+
+impl ComparisonRow {
+
+    // Build a row from its figures.
+    fn new(label: &str, values: Vec<f64>) -> ComparisonRow {
+        let first = values.first().copied().unwrap_or(0.0);
+        let deltas = values.iter().map(|value| saturate(value - first)).collect();
+        ComparisonRow { label: label.to_owned(), values, deltas }
+    }
+}
+
+/// Compare budgets side by side, e.g. a baseline with the budgets of its scenarios, so frontends
+/// can render an A/B/C view directly. Groups are matched by name, and all figures are monthly,
+/// rounded according to the rounding policy of their budget.
+///
+/// # Parameters
+/// * `budgets` - the budgets to compare, with the one the others are compared against first.
+///
+/// # Returns
+/// The table of per-group and net figures, with their deltas to the first budget.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::report::compare;
+/// let mut housing = BudgetGroup::new("Housing");
+/// housing.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+/// let mut budget = Budget::new("Home");
+/// budget.add_group(housing);
+/// let mut scenario = budget.branch_scenario("Rent increase");
+/// scenario.set_item("Housing", BudgetItem::with_expense("Rent", 1_400.0, Period::Every1Month));
+///
+/// let comparison = compare(&[&budget, &scenario.to_budget()]);
+/// assert_eq!(vec![-1_200.0, -1_400.0], comparison.net.values);
+/// assert_eq!(vec![0.0, -200.0], comparison.net.deltas);
+/// ```
+pub fn compare(budgets: &[&Budget]) -> Comparison {
+    let mut names: Vec<&String> = Vec::new();
+    for budget in budgets {
+        for (_, group) in budget.enumerate() {
+            if !names.contains(&group.name()) {
+                names.push(group.name());
+            }
+        }
+    }
+
+    let groups = names.into_iter()
+        .map(|name| ComparisonRow::new(name, budgets.iter()
+            .map(|budget| saturating_sum(budget.enumerate()
+                .filter(|(_, group)| group.name() == name)
+                .map(|(_, group)| group.rounded_total(budget.defaults().rounding))))
+            .collect()))
+        .collect();
+    Comparison {
+        budgets: budgets.iter().map(|budget| budget.name().clone()).collect(),
+        groups,
+        net: ComparisonRow::new("Net", budgets.iter().map(|budget| budget.total()).collect()),
+    }
+}
+
+/// A problem found in a budget by [`health`], with the figures that triggered it. Its `Display`
+/// describes the problem in a plain-language sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthFinding {
+    /// The expenses exceed the income by the monthly `shortfall`, which `amount` holds formatted
+    /// in the currency of the budget.
+    NegativeNet { shortfall: f64, amount: String },
+    /// The expenses of a group take a larger share of the income than its limit in the
+    /// [rules](struct.HealthRules.html#structfield.group_limits).
+    GroupOverLimit { group: String, share: f64, limit: f64 },
+    /// No item sets money aside for emergencies.
+    NoEmergencyFund,
+    /// A single expense takes a larger share of the income than the
+    /// [rules](struct.HealthRules.html#structfield.item_limit) allow.
+    ItemOverLimit { group: String, item: String, share: f64 },
+}
+
+impl fmt::Display for HealthFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthFinding::NegativeNet { amount, .. } =>
+                write!(f, "Your expenses exceed your income by {} each month.", amount),
+            HealthFinding::GroupOverLimit { group, share, limit } =>
+                write!(f, "{} takes {:.0}% of your income, more than the {:.0}% you allow.", group, share * 100.0, limit * 100.0),
+            HealthFinding::NoEmergencyFund =>
+                write!(f, "You are not setting money aside for emergencies."),
+            HealthFinding::ItemOverLimit { item, share, .. } =>
+                write!(f, "{} alone takes {:.0}% of your income.", item, share * 100.0),
+        }
+    }
+}
+
+/// The thresholds used by [`health_with`] to judge a budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthRules {
+    /// The largest share of the income the expenses of a group may take, by group name, e.g.
+    /// `("Housing", 0.3)` for 30%. Groups without a limit are not checked.
+    pub group_limits: Vec<(String, f64)>,
+    /// The largest share of the income a single expense may take, or `None` to not check items.
+    pub item_limit: Option<f64>,
+    /// The words, any of which in the name of an expense marks it as money set aside for
+    /// emergencies. The case is ignored. An empty list turns the check off.
+    pub emergency_fund_names: Vec<String>,
+}
+
+impl Default for HealthRules {
+    /// No group limits, a limit of 50% of the income per item, and `"emergency"`, `"rainy day"`
+    /// and `"buffer"` as the names of an emergency fund.
+    fn default() -> Self {
+        HealthRules {
+            group_limits: Vec::new(),
+            item_limit: Some(0.5),
+            emergency_fund_names: vec!["emergency".to_owned(), "rainy day".to_owned(), "buffer".to_owned()],
+        }
+    }
+}
+
+/// Check a budget for common problems using the [default rules](struct.HealthRules.html), as
+/// described on [`health_with`].
+///
+/// # Parameters
+/// * `budget` - the budget to check.
+///
+/// # Returns
+/// The problems found, which is empty for a healthy budget.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::report::{health, HealthFinding};
+/// let mut group = BudgetGroup::new("Home");
+/// group.add(BudgetItem::with_income("Salary", 2_000.0, Period::Every1Month));
+/// group.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+/// group.add(BudgetItem::with_expense("Emergency fund", 100.0, Period::Every1Month));
+/// let mut budget = Budget::new("Mine");
+/// budget.add_group(group);
+///
+/// let findings = health(&budget);
+/// assert_eq!(1, findings.len());
+/// assert_eq!("Rent alone takes 60% of your income.", findings[0].to_string());
+/// ```
+pub fn health(budget: &Budget) -> Vec<HealthFinding> {
+    health_with(budget, &HealthRules::default())
+}
+
+/// Check a budget for common problems, so frontends can warn about them.
+///
+/// The checks are, in the order the findings are returned:
+/// * whether the expenses exceed the income;
+/// * whether a group with a limit in the rules spends more than its share of the income;
+/// * whether any expense sets money aside for emergencies;
+/// * whether a single expense takes more than its share of the income.
+///
+/// The shares are not checked for a budget without income, as the shortfall is reported instead.
+///
+/// # Parameters
+/// * `budget` - the budget to check.
+/// * `rules` - the thresholds to check against.
+///
+/// # Returns
+/// The problems found, which is empty for a healthy budget.
+pub fn health_with(budget: &Budget, rules: &HealthRules) -> Vec<HealthFinding> {
+    let mut findings = Vec::new();
+    let summary = budget.summary();
+    if summary.net < 0.0 {
+        let shortfall = -summary.net;
+        findings.push(HealthFinding::NegativeNet { shortfall, amount: budget_format(budget).amount(shortfall) });
+    }
+
+    // The shares are taken of rounded contributions, as the income of the summary is.
+    let rounding = budget.defaults().rounding;
+    let income = summary.income;
+    if income > 0.0 {
+        for (_, group) in budget.enumerate() {
+            let limit = rules.group_limits.iter().find(|(name, _)| name == group.name());
+            if let Some((_, limit)) = limit {
+                let expenses: f64 = group.expenses().map(|item| item.rounded_monthly_contribution(rounding)).sum();
+                let share = -rounding.round(expenses) / income;
+                if share > *limit {
+                    findings.push(HealthFinding::GroupOverLimit { group: group.name().clone(), share, limit: *limit });
+                }
+            }
+        }
+    }
+
+    let expenses = || budget.enumerate()
+        .flat_map(|(_, group)| group.expenses().map(move |item| (group, item)));
+    let has_emergency_fund = expenses().any(|(_, item)| rules.emergency_fund_names.iter()
+        .any(|name| search::contains(name, item.name())));
+    if !rules.emergency_fund_names.is_empty() && !has_emergency_fund {
+        findings.push(HealthFinding::NoEmergencyFund);
+    }
+
+    if let (Some(limit), true) = (rules.item_limit, income > 0.0) {
+        for (group, item) in expenses() {
+            let share = -item.rounded_monthly_contribution(rounding) / income;
+            if share > limit {
+                findings.push(HealthFinding::ItemOverLimit { group: group.name().clone(), item: item.name().clone(), share });
+            }
+        }
+    }
+
+    findings
+}
+
+// The amount formatting of a budget, based on its default currency and locale.
+fn budget_format(budget: &Budget) -> FormatOptions {
+    let defaults = budget.defaults();
+    FormatOptions {
+        currency_symbol: defaults.currency.clone(),
+        ..defaults.locale.map_or_else(FormatOptions::default, |locale| locale.format_options())
+    }
+}
+
+// Pick the singular or plural form of a word.
+fn plural(count: usize, singular: &'static str, plural: &'static str) -> &'static str {
+    if count == 1 { singular } else { plural }
+}
+
+// A period, as written in the middle of a sentence.
+fn period_phrase(period: Period) -> &'static str {
+    match period {
+        Period::Every1Month => "every month",
+        Period::Every2Months => "every 2 months",
+        Period::Every3Months => "every 3 months",
+        Period::Every6Months => "every 6 months",
+        Period::Every12Months => "every year",
+    }
+}
+
+// The monthly income and expense totals of a budget, rounded as the budget total is, so that they
+// add up to it. Expenses are returned as a negative number.
+pub(crate) fn income_and_expenses(budget: &Budget) -> (f64, f64) {
+    let rounding = budget.defaults().rounding;
+    let mut income = 0.0;
+    let mut expenses = 0.0;
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            let contribution = item.rounded_monthly_contribution(rounding);
+            if item.is_income() {
+                income = saturate(income + contribution);
+            } else {
+                expenses = saturate(expenses + contribution);
+            }
+        }
+    }
+    (rounding.round(income), rounding.round(expenses))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::report::{compare, describe, health, health_with, HealthFinding, HealthRules};
+
+    #[test]
+    fn describe_budget() {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 2_500.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Electricity", 50.0, Period::Every1Month));
+        let mut transport = BudgetGroup::new("Transport");
+        transport.add(BudgetItem::with_expense("Car insurance", 6_000.0, Period::Every6Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget.add_group(transport);
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), ..BudgetDefaults::default() });
+
+        assert_eq!(vec![
+            "Your budget \"Home\" has 3 groups with 4 items.",
+            "You plan a monthly income of €2,500.00 and monthly expenses of €1,950.00.",
+            "You plan to spend 78% of your income.",
+            "This leaves €550.00 each month.",
+            "Your largest expense is Car insurance at €6,000.00 every 6 months, or €1,000.00 per month.",
+            "Your most expensive group is Transport at €1,000.00 per month.",
+        ], describe(&budget));
+    }
+
+    #[test]
+    fn describe_shortfall_without_income() {
+        let mut group = BudgetGroup::new("Bills");
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(group);
+
+        assert_eq!(vec![
+            "Your budget \"Home\" has 1 group with 1 item.",
+            "You plan a monthly income of 0.00 and monthly expenses of 900.00.",
+            "You have not planned any income.",
+            "This leaves a shortfall of 900.00 each month.",
+            "Your largest expense is Rent at 900.00 per month.",
+        ], describe(&budget));
+    }
+
+    #[test]
+    fn describe_empty_budget() {
+        assert_eq!(vec!["Your budget \"Home\" is empty."], describe(&Budget::new("Home")));
+    }
+
+    #[test]
+    fn compare_budgets() {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut a = Budget::new("A");
+        a.add_group(housing);
+        let mut b = Budget::new("B");
+        let mut car = BudgetGroup::new("Car");
+        car.add(BudgetItem::with_expense("Fuel", 600.0, Period::Every12Months));
+        b.add_group(car);
+
+        let comparison = compare(&[&a, &b]);
+        assert_eq!(vec!["A", "B"], comparison.budgets);
+        let groups: Vec<(&str, &[f64], &[f64])> = comparison.groups.iter()
+            .map(|row| (row.label.as_str(), row.values.as_slice(), row.deltas.as_slice()))
+            .collect();
+        assert_eq!(vec![
+            ("Housing", &[-900.0, 0.0][..], &[0.0, 900.0][..]),
+            ("Car", &[0.0, -50.0][..], &[0.0, -50.0][..]),
+        ], groups);
+        assert_eq!(vec![0.0, 850.0], comparison.net.deltas);
+        assert!(compare(&[]).groups.is_empty());
+    }
+
+    #[test]
+    fn health_of_budget() {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 1_000.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 600.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Electricity", 100.0, Period::Every1Month));
+        let mut car = BudgetGroup::new("Car");
+        car.add(BudgetItem::with_expense("Insurance", 6_000.0, Period::Every12Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget.add_group(car);
+
+        assert_eq!(vec![
+            HealthFinding::NegativeNet { shortfall: 200.0, amount: "200.00".to_owned() },
+            HealthFinding::NoEmergencyFund,
+            HealthFinding::ItemOverLimit { group: "Housing".to_owned(), item: "Rent".to_owned(), share: 0.6 },
+        ], health(&budget));
+
+        let rules = HealthRules {
+            group_limits: vec![("Housing".to_owned(), 0.75), ("Car".to_owned(), 0.25)],
+            item_limit: None,
+            emergency_fund_names: vec!["insurance".to_owned()],
+        };
+        assert_eq!(vec![
+            HealthFinding::NegativeNet { shortfall: 200.0, amount: "200.00".to_owned() },
+            HealthFinding::GroupOverLimit { group: "Car".to_owned(), share: 0.5, limit: 0.25 },
+        ], health_with(&budget, &rules));
+    }
+
+    #[test]
+    fn health_of_rounded_budget() {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 100.0, Period::Every3Months));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 50.0, Period::Every3Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+
+        let rules = HealthRules {
+            group_limits: vec![("Housing".to_owned(), 0.5)],
+            item_limit: Some(0.5),
+            emergency_fund_names: vec![],
+        };
+        let share = 16.67 / 33.33;
+        assert_eq!(vec![
+            HealthFinding::GroupOverLimit { group: "Housing".to_owned(), share, limit: 0.5 },
+            HealthFinding::ItemOverLimit { group: "Housing".to_owned(), item: "Rent".to_owned(), share },
+        ], health_with(&budget, &rules));
+    }
+
+    #[test]
+    fn health_findings_without_income() {
+        let mut group = BudgetGroup::new("Bills");
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(group);
+        budget.set_defaults(BudgetDefaults { currency: "$".to_owned(), ..BudgetDefaults::default() });
+
+        let findings: Vec<String> = health(&budget).iter().map(|finding| finding.to_string()).collect();
+        assert_eq!(vec![
+            "Your expenses exceed your income by $900.00 each month.",
+            "You are not setting money aside for emergencies.",
+        ], findings);
+        assert!(health(&Budget::new("Empty")).contains(&HealthFinding::NoEmergencyFund));
+    }
+}
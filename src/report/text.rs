@@ -0,0 +1,165 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use crate::budget::Budget;
+use crate::report::budget_format;
+
+/// The narrowest the name column is allowed to get, regardless of the requested width.
+const MIN_NAME_WIDTH: usize = 8;
+
+/// The space between two columns.
+const GAP: &str = "  ";
+
+/// Render a budget as aligned plain-text tables, for terminal frontends.
+///
+/// Every group is rendered as a table of its items, with columns for the name, period, amount
+/// and monthly contribution, followed by the group's subtotal. The report ends with the net
+/// monthly figure of the whole budget. Amounts are written with the budget's default currency.
+///
+/// The name column takes up whatever the other columns leave of the requested width, and names
+/// that do not fit are cut short with `...`. The name column never gets narrower than 8
+/// characters, so lines may exceed very small widths.
+///
+/// # Parameters
+/// * `budget` - the budget to render.
+/// * `width` - the desired width of each line, in characters.
+///
+/// # Returns
+/// The report, with lines separated by `\n`.
+pub fn render(budget: &Budget, width: usize) -> String {
+    let options = budget_format(budget);
+
+    let mut rows = Vec::new();
+    for (_, group) in budget.enumerate() {
+        for (_, item) in group.enumerate() {
+            rows.push([
+                item.name().to_owned(),
+                item.period().label().to_owned(),
+                options.amount(item.amount()),
+                options.amount(item.monthly_contribution()),
+            ]);
+        }
+    }
+    let net = options.amount(budget.total());
+    let subtotals: Vec<String> = budget.enumerate()
+        .map(|(_, group)| options.amount(group.rounded_total(budget.defaults().rounding)))
+        .collect();
+
+    let column = |idx: usize| rows.iter().map(|row| row[idx].chars().count()).max().unwrap_or(0);
+    let period_width = column(1).max("Period".len());
+    let amount_width = column(2).max("Amount".len());
+    let monthly_width = subtotals.iter().chain(Some(&net))
+        .map(|amount| amount.chars().count())
+        .fold(column(3).max("Monthly".len()), usize::max);
+    let fixed = period_width + amount_width + monthly_width + 3 * GAP.len();
+    let name_width = width.saturating_sub(fixed).max(MIN_NAME_WIDTH);
+    let line_width = name_width + fixed;
+
+    let line = |out: &mut String, name: &str, period: &str, amount: &str, monthly: &str| {
+        let _ = writeln!(out, "{:<name_w$}{gap}{:<period_w$}{gap}{:>amount_w$}{gap}{:>monthly_w$}",
+                         fit(name, name_width), period, amount, monthly,
+                         gap = GAP, name_w = name_width, period_w = period_width,
+                         amount_w = amount_width, monthly_w = monthly_width);
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", fit(budget.name(), line_width));
+    let _ = writeln!(out, "{}", "=".repeat(line_width));
+
+    let mut rows = rows.iter();
+    for ((_, group), subtotal) in budget.enumerate().zip(&subtotals) {
+        let _ = writeln!(out);
+        line(&mut out, group.name(), "Period", "Amount", "Monthly");
+        let _ = writeln!(out, "{}", "-".repeat(line_width));
+        for row in rows.by_ref().take(group.enumerate().len()) {
+            line(&mut out, &row[0], &row[1], &row[2], &row[3]);
+        }
+        let _ = writeln!(out, "{}", "-".repeat(line_width));
+        line(&mut out, "Subtotal", "", "", subtotal);
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{}", "=".repeat(line_width));
+    line(&mut out, "Net monthly", "", "", &net);
+    out
+}
+
+// Cut a text short with "..." if it is wider than the given number of characters.
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    let mut fitted: String = text.chars().take(width.saturating_sub(3)).collect();
+    fitted.push_str("...");
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::locale::Locale;
+    use crate::report::text::{fit, render};
+
+    fn budget() -> Budget {
+        let mut income = BudgetGroup::new("Income");
+        income.add(BudgetItem::with_income("Salary", 3_000.0, Period::Every1Month));
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Home insurance and other things", 240.0, Period::Every12Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(income);
+        budget.add_group(housing);
+        budget
+    }
+
+    #[test]
+    fn render_aligned_tables() {
+        let expected = "\
+Home
+====================================================
+\n\
+Income                  Period     Amount    Monthly
+----------------------------------------------------
+Salary                  Monthly  3,000.00   3,000.00
+----------------------------------------------------
+Subtotal                                    3,000.00
+\n\
+Housing                 Period     Amount    Monthly
+----------------------------------------------------
+Home insurance and ...  Yearly     240.00     -20.00
+Rent                    Monthly  1,200.00  -1,200.00
+----------------------------------------------------
+Subtotal                                   -1,220.00
+\n\
+====================================================
+Net monthly                                 1,780.00
+";
+        assert_eq!(expected, render(&budget(), 52));
+    }
+
+    #[test]
+    fn render_every_line_within_width() {
+        for line in render(&budget(), 60).lines() {
+            assert!(line.chars().count() <= 60, "Line too wide: {}", line);
+        }
+    }
+
+    #[test]
+    fn render_with_currency() {
+        let mut budget = budget();
+        budget.set_defaults(BudgetDefaults { currency: "$".to_owned(), ..BudgetDefaults::default() });
+        assert!(render(&budget, 60).ends_with("$1,780.00\n"));
+
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), locale: Some(Locale::DE_DE), ..BudgetDefaults::default() });
+        assert!(render(&budget, 60).ends_with("€1.780,00\n"));
+    }
+
+    #[test]
+    fn fit_text() {
+        assert_eq!("Rent", fit("Rent", 8));
+        assert_eq!("Elect...", fit("Electricity", 8));
+    }
+}
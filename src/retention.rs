@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+use crate::clock::Timestamp;
+use crate::snapshot::Snapshot;
+
+// The number of seconds in a day.
+const DAY: u64 = 86_400;
+
+/// How long the history of a budget is kept by
+/// [`Budget::compact`](../budget/struct.Budget.html#method.compact), so that long-lived budgets do
+/// not grow without bound.
+///
+/// Snapshots are thinned out as they age: recent snapshots are kept per day, older ones per
+/// calendar month, and the oldest are removed. Ages are counted in days back from the current time
+/// of the [audit log](../budget/struct.Budget.html#method.enable_audit).
+///
+/// The default policy keeps daily snapshots for 30 days, monthly snapshots for 2 years, and the
+/// events of the audit log for 2 years.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub struct RetentionPolicy {
+    /// The number of days during which the latest snapshot of every day is kept.
+    pub daily_snapshots: u64,
+    /// The number of days during which the latest snapshot of every calendar month (in UTC) is
+    /// kept. Snapshots older than this are removed.
+    pub monthly_snapshots: u64,
+    /// The number of days during which the events of the audit log are kept. Older events are
+    /// folded into the start of the log, so the budget can no longer be reconstructed from before.
+    pub audit_events: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            daily_snapshots: 30,
+            monthly_snapshots: 730,
+            audit_events: 730,
+        }
+    }
+}
+
+impl RetentionPolicy {
+
+    // Get the point in time before which the events of the audit log are not kept.
+    pub(crate) fn audit_cutoff(&self, now: Timestamp) -> Timestamp {
+        Timestamp(now.seconds().saturating_sub(self.audit_events.saturating_mul(DAY)))
+    }
+
+    // Remove the snapshots the policy no longer keeps. Snapshots without a time are kept.
+    pub(crate) fn thin(&self, snapshots: &mut Vec<Snapshot>, now: Timestamp) {
+        // The snapshots are walked from the latest, so the latest one of every day or month is kept.
+        let mut buckets = Vec::new();
+        let mut keep = Vec::with_capacity(snapshots.len());
+        for snapshot in snapshots.iter().rev() {
+            let time = match snapshot.time() {
+                Some(time) => time.seconds(),
+                None => {
+                    keep.push(true);
+                    continue;
+                }
+            };
+            let age = now.seconds().saturating_sub(time);
+            let bucket = if age <= self.daily_snapshots.saturating_mul(DAY) {
+                Bucket::Day(time / DAY)
+            } else if age <= self.monthly_snapshots.saturating_mul(DAY) {
+                Bucket::Month(year_month(time / DAY))
+            } else {
+                keep.push(false);
+                continue;
+            };
+            keep.push(!buckets.contains(&bucket));
+            buckets.push(bucket);
+        }
+
+        let mut keep = keep.into_iter().rev();
+        snapshots.retain(|_| keep.next().unwrap_or(true));
+    }
+}
+
+// The period a kept snapshot stands for.
+#[derive(Eq, PartialEq)]
+enum Bucket {
+    // Holds the number of days since the UNIX epoch.
+    Day(u64),
+    // Holds the year and the month, starting at 1.
+    Month((u64, u64)),
+}
+
+// Get the year and month (starting at 1) of a day counted from the UNIX epoch, in the Gregorian
+// calendar. The years are counted from March, so that the leap day falls at their end.
+fn year_month(days: u64) -> (u64, u64) {
+    // Days since 0000-03-01, split into eras of 400 years, which all have the same length.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+    (year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::clock::{FixedClock, Timestamp};
+    use crate::retention::{year_month, RetentionPolicy, DAY};
+
+    #[test]
+    fn calendar_months() {
+        assert_eq!((1970, 1), year_month(0));
+        assert_eq!((2000, 2), year_month(11_016));
+        assert_eq!((2024, 2), year_month(19_782));
+        assert_eq!((2024, 3), year_month(19_783));
+        assert_eq!((2024, 12), year_month(20_088));
+    }
+
+    #[test]
+    fn thin_snapshots() {
+        // 2024-01-01, with a snapshot every 12 hours for two years.
+        let clock = Rc::new(FixedClock::new(Timestamp(19_723 * DAY)));
+        let mut budget = Budget::new("Home");
+        budget.snapshot("before auditing");
+        budget.enable_audit(clock.clone());
+        for _ in 0..2 * 730 {
+            budget.snapshot("");
+            clock.advance(DAY / 2);
+        }
+        clock.advance(DAY);
+
+        budget.compact(&RetentionPolicy { daily_snapshots: 10, monthly_snapshots: 365, audit_events: 730 });
+
+        let snapshots = budget.snapshots();
+        assert_eq!("before auditing", snapshots[0].label());
+        // The untimed snapshot, the months of 2025, and the days from 2025-12-22 to 2025-12-30.
+        assert_eq!(1 + 12 + 9, snapshots.len());
+        // Every kept day is the latest snapshot of that day.
+        let last = snapshots.last().unwrap().time().unwrap().seconds();
+        assert_eq!(DAY / 2, last % DAY);
+        assert!(snapshots.windows(2).skip(1).all(|pair| pair[0].time() < pair[1].time()));
+    }
+
+    #[test]
+    fn compact_audit_log() {
+        let clock = Rc::new(FixedClock::new(Timestamp(0)));
+        let mut budget = Budget::new("Home");
+        budget.enable_audit(clock.clone());
+        budget.add_group(BudgetGroup::new("Housing"));
+        clock.advance(20 * DAY);
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        clock.advance(20 * DAY);
+
+        budget.compact(&RetentionPolicy { audit_events: 30, ..RetentionPolicy::default() });
+
+        let log = budget.audit_log().unwrap();
+        assert_eq!(Timestamp(10 * DAY), log.started());
+        assert_eq!(1, log.events().len());
+        assert!(budget.at(Timestamp(5 * DAY)).is_none());
+        let past = budget.at(Timestamp(10 * DAY)).unwrap();
+        assert_eq!(0, past.group("Housing").unwrap().len());
+        assert_eq!(-900.0, budget.at(Timestamp(20 * DAY)).unwrap().total());
+    }
+
+    #[test]
+    fn compact_without_audit() {
+        let mut budget = Budget::new("Home");
+        budget.snapshot("January");
+        budget.compact(&RetentionPolicy::default());
+        assert_eq!(1, budget.snapshots().len());
+    }
+}
@@ -0,0 +1,258 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{saturate, saturating_sum, BudgetItem};
+use crate::snapshot::GroupChange;
+
+/// A what-if variant of a budget, created with
+/// [`Budget::branch_scenario`](../budget/struct.Budget.html#method.branch_scenario).
+///
+/// The scenario borrows the baseline budget, and only stores the items it overrides, e.g. "what
+/// if the rent rises to 1,400". Items are overridden by name within a group, so an override
+/// replaces every item with its name in the group, or adds the item if there is none.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// let mut housing = BudgetGroup::new("Housing");
+/// housing.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+/// let mut budget = Budget::new("Home");
+/// budget.add_group(housing);
+///
+/// let mut scenario = budget.branch_scenario("Rent increase");
+/// scenario.set_item("Housing", BudgetItem::with_expense("Rent", 1_400.0, Period::Every1Month));
+/// assert_eq!(-1_400.0, scenario.total());
+/// assert_eq!(-200.0, scenario.net_change());
+/// ```
+pub struct Scenario<'a> {
+    name: String,
+    baseline: &'a Budget,
+    // The overridden items, by group and item name. `None` removes the items.
+    overrides: Vec<(String, String, Option<BudgetItem>)>,
+}
+
+impl<'a> Scenario<'a> {
+
+    pub(crate) fn new(baseline: &'a Budget, name: &str) -> Scenario<'a> {
+        Scenario { name: name.to_owned(), baseline, overrides: Vec::new() }
+    }
+
+    /// Get a reference to the scenario's name.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Get the budget the scenario is branched from.
+    pub fn baseline(&self) -> &'a Budget {
+        self.baseline
+    }
+
+    /// Override the items with the name of an item in a group, replacing an earlier override of
+    /// the same items.
+    ///
+    /// # Parameters
+    /// * `group` - the name of the group. If the baseline has no group with the name, the group
+    ///   is added to the scenario.
+    /// * `item` - the item taking the place of the items with its name.
+    pub fn set_item(&mut self, group: &str, item: BudgetItem) {
+        let name = item.name().clone();
+        self.set_override(group, name, Some(item));
+    }
+
+    /// Remove the items with a name from a group in the scenario.
+    ///
+    /// # Parameters
+    /// * `group` - the name of the group.
+    /// * `name` - the name of the items.
+    pub fn remove_item(&mut self, group: &str, name: &str) {
+        self.set_override(group, name.to_owned(), None);
+    }
+
+    /// Drop all overrides, so the scenario equals its baseline again.
+    pub fn reset(&mut self) {
+        self.overrides.clear();
+    }
+
+    /// Calculate the total of a group in the scenario, based on a monthly recurring cycle, rounded
+    /// according to the rounding policy of the baseline, as in
+    /// [`BudgetGroup::rounded_total`](../budget_group/struct.BudgetGroup.html#method.rounded_total).
+    ///
+    /// # Parameters
+    /// * `group` - the name of the group.
+    ///
+    /// # Returns
+    /// The total, or 0 if there is no group with the name.
+    pub fn group_total(&self, group: &str) -> f64 {
+        let rounding = self.baseline.defaults().rounding;
+        rounding.round(saturating_sum(self.items(group).iter().map(|item| item.rounded_monthly_contribution(rounding))))
+    }
+
+    /// Calculate the total of the scenario across all groups, based on a monthly recurring cycle,
+    /// rounded like the [total of the baseline](../budget/struct.Budget.html#method.total).
+    pub fn total(&self) -> f64 {
+        let rounding = self.baseline.defaults().rounding;
+        rounding.round(saturating_sum(self.group_names().iter().map(|group| self.group_total(group))))
+    }
+
+    /// Calculate how much the scenario changes the monthly total of the baseline.
+    ///
+    /// # Returns
+    /// The change, which is positive if the scenario leaves more money per month.
+    pub fn net_change(&self) -> f64 {
+        self.baseline.defaults().rounding.round(saturate(self.total() - self.baseline.total()))
+    }
+
+    /// Compare the group totals of the scenario with those of the baseline.
+    ///
+    /// # Returns
+    /// The change of every group, with the baseline total as `before` and the scenario total as
+    /// `after`, in the order of the baseline, followed by the groups only in the scenario.
+    pub fn compare(&self) -> Vec<GroupChange> {
+        self.group_names().into_iter()
+            .map(|group| GroupChange {
+                before: self.baseline.group(&group)
+                    .map(|baseline| baseline.rounded_total(self.baseline.defaults().rounding))
+                    .unwrap_or(0.0),
+                after: self.group_total(&group),
+                group,
+            })
+            .collect()
+    }
+
+    /// Create a separate budget with the overrides applied, e.g. to keep the scenario as the new
+    /// plan or to pass it to a report. The budget gets the name of the scenario and the defaults of
+    /// the baseline.
+    pub fn to_budget(&self) -> Budget {
+        let mut budget = Budget::new(&self.name);
+        for name in self.group_names() {
+            let mut group = BudgetGroup::new(&name);
+            if let Some(baseline) = self.baseline.group(&name) {
+                group.set_order(baseline.order());
+            }
+            for item in self.items(&name) {
+                group.add(item);
+            }
+            budget.add_group(group);
+        }
+        budget.set_defaults(self.baseline.defaults().clone());
+        budget.mark_clean();
+        budget
+    }
+
+    // Store an override, in place of an earlier one for the same items.
+    fn set_override(&mut self, group: &str, name: String, item: Option<BudgetItem>) {
+        self.overrides.retain(|(other_group, other_name, _)| other_group != group || *other_name != name);
+        self.overrides.push((group.to_owned(), name, item));
+    }
+
+    // The names of the groups in the scenario: those of the baseline, followed by those only
+    // named by overrides.
+    fn group_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.baseline.enumerate().map(|(_, group)| group.name().clone()).collect();
+        for (group, _, _) in &self.overrides {
+            if !names.contains(group) {
+                names.push(group.clone());
+            }
+        }
+        names
+    }
+
+    // The items of a group in the scenario.
+    fn items(&self, group: &str) -> Vec<BudgetItem> {
+        let overridden = |name: &String| self.overrides.iter()
+            .any(|(other_group, other_name, _)| other_group == group && other_name == name);
+        let mut items: Vec<BudgetItem> = self.baseline.group(group).into_iter()
+            .flat_map(|group| group.enumerate())
+            .map(|(_, item)| item)
+            .filter(|item| !overridden(item.name()))
+            .cloned()
+            .collect();
+        items.extend(self.overrides.iter()
+            .filter(|(other_group, _, _)| other_group == group)
+            .filter_map(|(_, _, item)| item.clone()));
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+
+    fn budget() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every1Month));
+        housing.add(BudgetItem::with_expense("Water", 30.0, Period::Every1Month));
+        let mut salary = BudgetGroup::new("Salary");
+        salary.add(BudgetItem::with_income("Job", 3_000.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(salary);
+        budget
+    }
+
+    #[test]
+    fn overrides() {
+        let budget = budget();
+        let mut scenario = budget.branch_scenario("Move");
+        scenario.set_item("Housing", BudgetItem::with_expense("Rent", 1_000.0, Period::Every1Month));
+        scenario.set_item("Housing", BudgetItem::with_expense("Rent", 1_100.0, Period::Every1Month));
+        scenario.remove_item("Housing", "Water");
+        scenario.set_item("Car", BudgetItem::with_expense("Fuel", 50.0, Period::Every1Month));
+
+        assert_eq!(-1_100.0, scenario.group_total("Housing"));
+        assert_eq!(1_850.0, scenario.total());
+        assert_eq!(80.0, scenario.net_change());
+        assert_eq!(1_770.0, budget.total());
+
+        scenario.reset();
+        assert_eq!(0.0, scenario.net_change());
+    }
+
+    #[test]
+    fn rounding() {
+        let mut savings = BudgetGroup::new("Savings");
+        for name in &["Dividends", "Interest", "Rent out"] {
+            savings.add(BudgetItem::with_income(name, 10.0, Period::Every3Months));
+        }
+        let mut budget = Budget::new("Home");
+        budget.add_group(savings);
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+
+        let mut scenario = budget.branch_scenario("Same");
+        scenario.set_item("Savings", BudgetItem::with_income("Interest", 10.0, Period::Every3Months));
+        assert_eq!(9.99, scenario.group_total("Savings"));
+        assert_eq!(budget.total(), scenario.total());
+        assert_eq!(0.0, scenario.net_change());
+        assert_eq!(0.0, scenario.compare()[0].difference());
+    }
+
+    #[test]
+    fn compare() {
+        let budget = budget();
+        let mut scenario = budget.branch_scenario("Raise");
+        scenario.set_item("Salary", BudgetItem::with_income("Job", 3_300.0, Period::Every1Month));
+        scenario.set_item("Car", BudgetItem::with_expense("Fuel", 50.0, Period::Every1Month));
+
+        let changes: Vec<(String, f64)> = scenario.compare().into_iter()
+            .map(|change| (change.group.clone(), change.difference()))
+            .collect();
+        assert_eq!(vec![("Housing".to_owned(), 0.0), ("Salary".to_owned(), 300.0), ("Car".to_owned(), -50.0)], changes);
+    }
+
+    #[test]
+    fn to_budget() {
+        let budget = budget();
+        let mut scenario = budget.branch_scenario("Raise");
+        scenario.set_item("Salary", BudgetItem::with_income("Job", 3_300.0, Period::Every1Month));
+
+        let branched = scenario.to_budget();
+        assert_eq!("Raise", branched.name());
+        assert_eq!(2_070.0, branched.total());
+        assert!(!branched.is_dirty());
+    }
+}
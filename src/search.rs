@@ -0,0 +1,82 @@
+use alloc::vec::Vec;
+use crate::budget::Budget;
+
+/// A group or an item matching a search, as returned by
+/// [`Budget::search`](../budget/struct.Budget.html#method.search).
+///
+/// The hit holds indices rather than references, so that it can be passed straight to the methods
+/// changing the budget, e.g. [`Budget::remove_item`](../budget/struct.Budget.html#method.remove_item).
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub struct SearchHit {
+    /// The index of the group, as discovered using
+    /// [`Budget::enumerate`](../budget/struct.Budget.html#method.enumerate).
+    pub group: usize,
+    /// The index of the item in the group, as discovered using
+    /// [`BudgetGroup::enumerate`](../budget_group/struct.BudgetGroup.html#method.enumerate), or
+    /// `None` if the group itself matched.
+    pub item: Option<usize>,
+}
+
+// Find the groups and items whose name is accepted by a matcher, in the order of the budget. A
+// group is followed by its matching items.
+pub(crate) fn search<F: Fn(&str) -> bool>(budget: &Budget, matcher: F) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    for (group_idx, group) in budget.enumerate() {
+        if matcher(group.name()) {
+            hits.push(SearchHit { group: group_idx, item: None });
+        }
+        for (item_idx, item) in group.enumerate() {
+            if matcher(item.name()) {
+                hits.push(SearchHit { group: group_idx, item: Some(item_idx) });
+            }
+        }
+    }
+    hits
+}
+
+// Check whether a name contains a query, ignoring case.
+pub(crate) fn contains(query: &str, name: &str) -> bool {
+    name.to_lowercase().contains(query.trim().to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::search::SearchHit;
+
+    fn budget() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Home insurance", 240.0, Period::Every12Months));
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut car = BudgetGroup::new("Car");
+        car.add(BudgetItem::with_expense("Car insurance", 600.0, Period::Every12Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(car);
+        budget
+    }
+
+    #[test]
+    fn substring_ignoring_case() {
+        let budget = budget();
+        assert_eq!(vec![
+            SearchHit { group: 0, item: Some(0) },
+            SearchHit { group: 1, item: Some(0) },
+        ], budget.search("INSURANCE"));
+        assert_eq!(vec![
+            SearchHit { group: 1, item: None },
+            SearchHit { group: 1, item: Some(0) },
+        ], budget.search(" car "));
+        assert!(budget.search("Gym").is_empty());
+    }
+
+    #[cfg(feature = "fuzzy")]
+    #[test]
+    fn fuzzy() {
+        let budget = budget();
+        assert!(budget.search("insurnce").is_empty());
+        assert_eq!(2, budget.search_fuzzy("insurnce").len());
+    }
+}
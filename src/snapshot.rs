@@ -0,0 +1,194 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::budget::Budget;
+use crate::budget_item::{saturate, saturating_sum, BudgetItem, RoundingPolicy};
+use crate::clock::Timestamp;
+
+/// A frozen copy of the groups and items of a budget, taken with
+/// [`Budget::snapshot`](../budget/struct.Budget.html#method.snapshot), e.g. at the
+/// start of every month.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    label: String,
+    groups: Vec<(String, Vec<BudgetItem>)>,
+    rounding: RoundingPolicy,
+    time: Option<Timestamp>,
+}
+
+impl Snapshot {
+
+    // Copy the groups and items of a budget, and the rounding policy its totals are calculated
+    // with, at the current time of its audit log.
+    pub(crate) fn of(budget: &Budget, label: &str) -> Snapshot {
+        let groups = budget.enumerate()
+            .map(|(_, group)| (group.name().clone(), group.enumerate().map(|(_, item)| item.clone()).collect()))
+            .collect();
+        let time = budget.audit_log().map(|audit| audit.now());
+        Snapshot { label: label.to_owned(), groups, rounding: budget.defaults().rounding, time }
+    }
+
+    /// Get the point in time the snapshot was taken, according to the clock of the
+    /// [audit log](../budget/struct.Budget.html#method.enable_audit).
+    ///
+    /// # Returns
+    /// The time, or `None` if auditing was not enabled when the snapshot was taken.
+    pub fn time(&self) -> Option<Timestamp> {
+        self.time
+    }
+
+    /// Get a reference to the label of the snapshot, e.g. `"January"`.
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+
+    /// Get the rounding policy of the budget at the time of the snapshot, which the totals of the
+    /// snapshot are rounded with.
+    pub fn rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
+    /// Get an iterator over the groups at the time of the snapshot, as the name of each group and
+    /// its items.
+    pub fn groups(&self) -> impl Iterator<Item = (&String, &[BudgetItem])> {
+        self.groups.iter().map(|(name, items)| (name, items.as_slice()))
+    }
+
+    /// Calculate the total of a group at the time of the snapshot, based on a monthly recurring
+    /// cycle, rounded like
+    /// [`BudgetGroup::rounded_total`](../budget_group/struct.BudgetGroup.html#method.rounded_total)
+    /// with the [rounding policy](#method.rounding) of the snapshot.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the group.
+    ///
+    /// # Returns
+    /// The total, or 0 if there was no group with the name.
+    pub fn group_total(&self, name: &str) -> f64 {
+        let rounding = self.rounding;
+        rounding.round(saturating_sum(self.groups.iter()
+            .filter(|(group, _)| group == name)
+            .flat_map(|(_, items)| items.iter())
+            .map(|item| item.rounded_monthly_contribution(rounding))))
+    }
+
+    /// Calculate the total of the budget at the time of the snapshot, based on a monthly recurring
+    /// cycle, rounded like [`Budget::total`](../budget/struct.Budget.html#method.total) with the
+    /// [rounding policy](#method.rounding) of the snapshot.
+    pub fn total(&self) -> f64 {
+        let rounding = self.rounding;
+        rounding.round(saturating_sum(self.groups.iter()
+            .flat_map(|(_, items)| items.iter())
+            .map(|item| item.rounded_monthly_contribution(rounding))))
+    }
+
+    /// Compare the group totals of the snapshot with those of a later one.
+    ///
+    /// # Parameters
+    /// * `later` - the snapshot to compare with.
+    ///
+    /// # Returns
+    /// The change of every group in either snapshot, in the order of this snapshot, followed by
+    /// the groups that only exist in the later one.
+    pub fn compare(&self, later: &Snapshot) -> Vec<GroupChange> {
+        let mut names: Vec<&String> = Vec::new();
+        for (name, _) in self.groups.iter().chain(later.groups.iter()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names.into_iter()
+            .map(|name| GroupChange { group: name.clone(), before: self.group_total(name), after: later.group_total(name) })
+            .collect()
+    }
+}
+
+/// How the total of a group changed between two snapshots. A group that is missing from a
+/// snapshot has a total of 0 in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupChange {
+    /// The name of the group.
+    pub group: String,
+    /// The monthly total in the earlier snapshot.
+    pub before: f64,
+    /// The monthly total in the later snapshot.
+    pub after: f64,
+}
+
+impl GroupChange {
+
+    /// Calculate the change of the total, which is positive if the group contributes more to the
+    /// budget, i.e. more income or fewer expenses.
+    pub fn difference(&self) -> f64 {
+        saturate(self.after - self.before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::snapshot::GroupChange;
+
+    fn budget() -> Budget {
+        let mut housing = BudgetGroup::new("Housing");
+        housing.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(housing);
+        budget.add_group(BudgetGroup::new("Food"));
+        budget
+    }
+
+    #[test]
+    fn snapshots_are_frozen() {
+        let mut budget = budget();
+        budget.snapshot("January");
+        budget.remove_group(0).unwrap();
+
+        let snapshot = budget.find_snapshot("January").unwrap();
+        assert_eq!(-900.0, snapshot.total());
+        assert_eq!(2, snapshot.groups().count());
+        assert_eq!(1, budget.snapshots().len());
+        assert!(budget.find_snapshot("February").is_none());
+    }
+
+    #[test]
+    fn compare() {
+        let mut budget = budget();
+        budget.snapshot("January");
+        budget.add_item(0, BudgetItem::with_expense("Water", 30.0, Period::Every1Month)).unwrap();
+        budget.remove_group(1).unwrap();
+        budget.add_group(BudgetGroup::new("Car"));
+        budget.add_item(1, BudgetItem::with_expense("Fuel", 50.0, Period::Every1Month)).unwrap();
+        budget.snapshot("February");
+
+        let changes = budget.compare_snapshots("January", "February").unwrap();
+        assert_eq!(vec![
+            GroupChange { group: "Housing".to_owned(), before: -900.0, after: -930.0 },
+            GroupChange { group: "Food".to_owned(), before: 0.0, after: 0.0 },
+            GroupChange { group: "Car".to_owned(), before: 0.0, after: -50.0 },
+        ], changes);
+        assert_eq!(-30.0, changes[0].difference());
+        assert!(budget.compare_snapshots("January", "March").is_none());
+    }
+
+    #[test]
+    fn totals_are_rounded() {
+        let mut budget = budget();
+        budget.add_item(0, BudgetItem::with_expense("Water", 100.0, Period::Every3Months)).unwrap();
+        budget.add_item(0, BudgetItem::with_expense("Sewage", 0.25, Period::Every2Months)).unwrap();
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+        budget.snapshot("January");
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfEven, ..BudgetDefaults::default() });
+        budget.snapshot("February");
+
+        let january = budget.find_snapshot("January").unwrap();
+        assert_eq!(RoundingPolicy::HalfUp, january.rounding());
+        assert_eq!(-933.46, january.group_total("Housing"));
+        assert_eq!(-933.46, january.total());
+        assert_eq!(budget.total(), budget.find_snapshot("February").unwrap().total());
+        let changes = budget.compare_snapshots("January", "February").unwrap();
+        assert_eq!(GroupChange { group: "Housing".to_owned(), before: -933.46, after: -933.45 }, changes[0]);
+    }
+}
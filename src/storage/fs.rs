@@ -0,0 +1,269 @@
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use crate::audit::Change;
+use crate::budget::Budget;
+use crate::document;
+use crate::journal;
+use crate::storage::{BudgetRepository, StorageError};
+
+/// The extension of the budget files.
+const EXTENSION: &str = "rbp";
+
+/// Repository keeping every budget as a [document](../../document/index.html) file in a
+/// directory.
+///
+/// The file names are derived from the budget names, with every character other than ASCII
+/// letters, digits, spaces, `-` and `_` percent-encoded, so any budget name makes a valid file
+/// name. Note that on case-insensitive file systems, names differing only in case share a file.
+///
+/// Saving writes to a temporary file first, and then moves it in place, so a failed save never
+/// leaves a half written budget behind.
+///
+/// The journal of a budget is kept next to its file, with a `.log` suffix, and every change is
+/// appended to it on its own line. An entry cut short by a crash is ignored when the journal is
+/// loaded.
+#[derive(Debug, Clone)]
+pub struct FileRepository {
+    dir: PathBuf,
+}
+
+impl FileRepository {
+
+    /// Create a repository for a directory. The directory is created on the first save, if it
+    /// does not exist.
+    ///
+    /// # Parameters
+    /// * `dir` - the directory holding the budget files.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> FileRepository {
+        FileRepository {
+            dir: dir.into()
+        }
+    }
+
+    // The path of the file holding the budget with a name.
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", encode(name), EXTENSION))
+    }
+
+    // The path of the journal of the budget with a name.
+    fn journal_path(&self, name: &str) -> PathBuf {
+        self.path(name).with_extension(format!("{}.log", EXTENSION))
+    }
+}
+
+impl BudgetRepository for FileRepository {
+    fn load(&self, name: &str) -> Result<Budget, StorageError> {
+        match fs::read_to_string(self.path(name)) {
+            Ok(doc) => Ok(document::from_str(&doc)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Err(StorageError::NotFound(name.to_owned())),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&mut self, budget: &Budget) -> Result<(), StorageError> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(budget.name());
+        let temp = path.with_extension(format!("{}.tmp", EXTENSION));
+        fs::write(&temp, document::to_string(budget))?;
+        fs::rename(&temp, &path)?;
+        remove_if_exists(&self.journal_path(budget.name()))?;
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, StorageError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let file_name = entry?.file_name();
+            let name = file_name.to_str()
+                .and_then(|file_name| file_name.strip_suffix(EXTENSION))
+                .and_then(|stem| stem.strip_suffix('.'))
+                .and_then(decode);
+            names.extend(name);
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), StorageError> {
+        match fs::remove_file(self.path(name)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Err(StorageError::NotFound(name.to_owned())),
+            result => {
+                result?;
+                Ok(remove_if_exists(&self.journal_path(name))?)
+            }
+        }
+    }
+
+    fn append_journal(&mut self, name: &str, change: &Change) -> Result<(), StorageError> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(self.journal_path(name))?;
+        // A single write per entry, so a crash can only cut short the last entry.
+        file.write_all(format!("{}\n", journal::encode(change)).as_bytes())?;
+        Ok(())
+    }
+
+    fn load_journal(&self, name: &str) -> Result<Vec<Change>, StorageError> {
+        let text = match fs::read_to_string(self.journal_path(name)) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        // Everything after the last line break is an entry cut short by a crash.
+        let complete = text.rfind('\n').map(|end| &text[..=end]).unwrap_or("");
+        Ok(journal::decode(complete)?)
+    }
+}
+
+// Remove a file, if it exists.
+fn remove_if_exists(path: &PathBuf) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// Percent-encode the bytes of a name that are not safe in file names.
+fn encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b' ' || byte == b'-' || byte == b'_' {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+// Reverse `encode`, or None if the file name was not produced by it.
+fn decode(encoded: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use crate::audit::Change;
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::storage::{BudgetRepository, StorageError};
+    use crate::storage::fs::{decode, encode, FileRepository};
+
+    // A fresh directory for a test, removed again when the test ends.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let dir = std::env::temp_dir().join(format!("rbp_core-{}-{}", test, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = TempDir::new("save_and_load");
+        let mut repository = FileRepository::new(&dir.0);
+        let mut group = BudgetGroup::new("Housing");
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home/2024: \"plan\"");
+        budget.add_group(group);
+
+        repository.save(&budget).unwrap();
+        let loaded = repository.load("Home/2024: \"plan\"").unwrap();
+        assert_eq!(-900.0, loaded.total());
+        assert_eq!(vec!["Home/2024: \"plan\""], repository.list().unwrap());
+    }
+
+    #[test]
+    fn list_and_delete() {
+        let dir = TempDir::new("list_and_delete");
+        let mut repository = FileRepository::new(&dir.0);
+        assert!(repository.list().unwrap().is_empty());
+
+        repository.save(&Budget::new("b")).unwrap();
+        repository.save(&Budget::new("a")).unwrap();
+        fs::write(dir.0.join("notes.txt"), "not a budget").unwrap();
+        assert_eq!(vec!["a", "b"], repository.list().unwrap());
+
+        repository.delete("a").unwrap();
+        assert_eq!(vec!["b"], repository.list().unwrap());
+        assert!(matches!(repository.delete("a"), Err(StorageError::NotFound(_))));
+        assert!(matches!(repository.load("a"), Err(StorageError::NotFound(_))));
+    }
+
+    #[test]
+    fn load_invalid_document() {
+        let dir = TempDir::new("load_invalid_document");
+        fs::create_dir_all(&dir.0).unwrap();
+        fs::write(dir.0.join("a.rbp"), "not a budget").unwrap();
+
+        assert!(matches!(FileRepository::new(&dir.0).load("a"), Err(StorageError::InvalidDocument(_))));
+    }
+
+    #[test]
+    fn journal() {
+        let dir = TempDir::new("journal");
+        let mut repository = FileRepository::new(&dir.0);
+        let mut budget = Budget::new("Home");
+        repository.save(&budget).unwrap();
+        assert!(repository.load_journal("Home").unwrap().is_empty());
+
+        budget.add_group(BudgetGroup::new("Housing"));
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        repository.append_journal("Home", &Change::GroupAdded { group: "Housing".to_owned(), index: 0 }).unwrap();
+        repository.append_journal("Home", &Change::ItemAdded {
+            group: "Housing".to_owned(),
+            item: BudgetItem::with_expense("Rent", 900.0, Period::Every1Month),
+        }).unwrap();
+        let mut file = fs::OpenOptions::new().append(true).open(dir.0.join("Home.rbp.log")).unwrap();
+        file.write_all(b"budget.item.removed\tHous").unwrap();
+
+        assert_eq!(2, repository.load_journal("Home").unwrap().len());
+        assert_eq!(-900.0, repository.recover("Home").unwrap().total());
+        assert_eq!(vec!["Home"], repository.list().unwrap());
+
+        repository.save(&budget).unwrap();
+        assert!(!dir.0.join("Home.rbp.log").exists());
+        repository.append_journal("Home", &Change::GroupAdded { group: "Food".to_owned(), index: 1 }).unwrap();
+        repository.delete("Home").unwrap();
+        assert!(!dir.0.join("Home.rbp.log").exists());
+    }
+
+    #[test]
+    fn encode_names() {
+        assert_eq!("My budget_2-b", encode("My budget_2-b"));
+        assert_eq!("a%2Fb%2E%C3%A9", encode("a/b.é"));
+        assert_eq!(Some("a/b.é".to_owned()), decode("a%2Fb%2E%C3%A9"));
+        assert_eq!(None, decode("a%2"));
+        assert_eq!(None, decode("%FF"));
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::audit::Change;
+use crate::budget::Budget;
+use crate::document;
+use crate::journal;
+use crate::storage::{BudgetRepository, StorageError};
+
+/// Repository keeping budgets in memory, e.g. for tests, or for frontends that persist on their
+/// own terms.
+///
+/// Budgets are kept as [documents](../../document/index.html), so a loaded budget is an
+/// independent copy of the saved one.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryRepository {
+    documents: BTreeMap<String, String>,
+    journals: BTreeMap<String, String>,
+}
+
+impl MemoryRepository {
+
+    /// Create a new, empty repository.
+    pub fn new() -> MemoryRepository {
+        MemoryRepository::default()
+    }
+}
+
+impl BudgetRepository for MemoryRepository {
+    fn load(&self, name: &str) -> Result<Budget, StorageError> {
+        let doc = self.documents.get(name).ok_or_else(|| StorageError::NotFound(name.into()))?;
+        Ok(document::from_str(doc)?)
+    }
+
+    fn save(&mut self, budget: &Budget) -> Result<(), StorageError> {
+        self.documents.insert(budget.name().clone(), document::to_string(budget));
+        self.journals.remove(budget.name());
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, StorageError> {
+        Ok(self.documents.keys().cloned().collect())
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), StorageError> {
+        self.journals.remove(name);
+        self.documents.remove(name).map(|_| ()).ok_or_else(|| StorageError::NotFound(name.into()))
+    }
+
+    fn append_journal(&mut self, name: &str, change: &Change) -> Result<(), StorageError> {
+        let journal = self.journals.entry(name.into()).or_default();
+        journal.push_str(&journal::encode(change));
+        journal.push('\n');
+        Ok(())
+    }
+
+    fn load_journal(&self, name: &str) -> Result<Vec<Change>, StorageError> {
+        match self.journals.get(name) {
+            Some(journal) => Ok(journal::decode(journal)?),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::budget::Budget;
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::storage::{BudgetRepository, StorageError};
+    use crate::storage::memory::MemoryRepository;
+
+    #[test]
+    fn save_and_load() {
+        let mut group = BudgetGroup::new("Housing");
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        let mut budget = Budget::new("Home");
+        budget.add_group(group);
+
+        let mut repository = MemoryRepository::new();
+        repository.save(&budget).unwrap();
+        budget.add_group(BudgetGroup::new("Food"));
+
+        let loaded = repository.load("Home").unwrap();
+        assert_eq!(1, loaded.enumerate().len());
+        assert_eq!(-900.0, loaded.total());
+    }
+
+    #[test]
+    fn list_and_delete() {
+        let mut repository = MemoryRepository::new();
+        repository.save(&Budget::new("b")).unwrap();
+        repository.save(&Budget::new("a")).unwrap();
+        repository.save(&Budget::new("b")).unwrap();
+        assert_eq!(vec!["a", "b"], repository.list().unwrap());
+
+        repository.delete("a").unwrap();
+        assert_eq!(vec!["b"], repository.list().unwrap());
+        assert!(matches!(repository.delete("a"), Err(StorageError::NotFound(name)) if name == "a"));
+        assert!(matches!(repository.load("a"), Err(StorageError::NotFound(_))));
+    }
+
+    #[test]
+    fn journal() {
+        let repository = Rc::new(RefCell::new(MemoryRepository::new()));
+        let mut budget = Budget::new("Home");
+        repository.borrow_mut().save(&budget).unwrap();
+        let journal = repository.clone();
+        budget.subscribe(move |change| journal.borrow_mut().append_journal("Home", change).unwrap());
+
+        budget.add_group(BudgetGroup::new("Housing"));
+        budget.add_item(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month)).unwrap();
+        assert_eq!(2, repository.borrow().load_journal("Home").unwrap().len());
+        let recovered = repository.borrow().recover("Home").unwrap();
+        assert_eq!(-900.0, recovered.total());
+        assert!(recovered.is_dirty());
+
+        repository.borrow_mut().save(&budget).unwrap();
+        assert!(repository.borrow().load_journal("Home").unwrap().is_empty());
+        assert!(!repository.borrow().recover("Home").unwrap().is_dirty());
+    }
+}
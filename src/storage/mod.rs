@@ -0,0 +1,156 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::audit::Change;
+use crate::budget::Budget;
+use crate::document;
+use crate::journal::JournalError;
+
+/// Module holding the repository keeping budgets in memory.
+pub mod memory;
+
+/// Module holding the repository keeping budgets as files in a directory.
+#[cfg(feature = "std")]
+pub mod fs;
+
+/// A place budgets are saved in and loaded from, identified by their names.
+///
+/// Frontends can work against this trait, so the persistence backend can be swapped, e.g. for a
+/// [`MemoryRepository`](memory/struct.MemoryRepository.html) in tests, without touching the rest
+/// of the application.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::storage::BudgetRepository;
+/// use rbp_core::storage::memory::MemoryRepository;
+/// fn save_all(repository: &mut dyn BudgetRepository, budgets: &[Budget]) {
+///     for budget in budgets {
+///         repository.save(budget).unwrap();
+///     }
+/// }
+///
+/// let mut repository = MemoryRepository::new();
+/// save_all(&mut repository, &[Budget::new("Home"), Budget::new("Holiday")]);
+/// assert_eq!(vec!["Holiday", "Home"], repository.list().unwrap());
+/// ```
+pub trait BudgetRepository {
+
+    /// Load a budget.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the budget, which is not [dirty](../budget/struct.Budget.html#method.is_dirty),
+    /// or `Result::Err` if there is no budget with the name or it cannot be loaded.
+    fn load(&self, name: &str) -> Result<Budget, StorageError>;
+
+    /// Save a budget under its name, replacing any budget saved with the same name. The journal of
+    /// the budget is cleared, as the saved budget holds all of its changes.
+    ///
+    /// # Parameters
+    /// * `budget` - the budget to save.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the budget was saved, otherwise `Result::Err`.
+    fn save(&mut self, budget: &Budget) -> Result<(), StorageError>;
+
+    /// List the names of the saved budgets.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the names in alphabetical order, or `Result::Err` if they cannot be
+    /// listed.
+    fn list(&self) -> Result<Vec<String>, StorageError>;
+
+    /// Delete a saved budget, including its journal.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the budget was deleted, or `Result::Err` if there is no budget with the
+    /// name or it cannot be deleted.
+    fn delete(&mut self, name: &str) -> Result<(), StorageError>;
+
+    /// Append a change to the journal of a budget, so that the edits since the last save can be
+    /// [recovered](#method.recover) after a crash. Meant to be called from a callback
+    /// [subscribed](../budget/struct.Budget.html#method.subscribe) to the budget.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the budget.
+    /// * `change` - the change made to the budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` if the change was written, otherwise `Result::Err`.
+    fn append_journal(&mut self, name: &str, change: &Change) -> Result<(), StorageError>;
+
+    /// Load the journal of a budget.
+    ///
+    /// # Parameters
+    /// * `name` - the name of the budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the changes since the last save, which is empty if there are none, or
+    /// `Result::Err` if the journal cannot be read.
+    fn load_journal(&self, name: &str) -> Result<Vec<Change>, StorageError>;
+
+    /// Load a budget with the changes of its journal replayed, as described on
+    /// [`Budget::recover`](../budget/struct.Budget.html#method.recover).
+    ///
+    /// # Parameters
+    /// * `name` - the name of the budget.
+    ///
+    /// # Returns
+    /// `Result::Ok` with the budget, which is [dirty](../budget/struct.Budget.html#method.is_dirty)
+    /// if there were unsaved changes, or `Result::Err` if it cannot be loaded or recovered.
+    fn recover(&self, name: &str) -> Result<Budget, StorageError> {
+        Ok(Budget::recover(self.load(name)?, &self.load_journal(name)?)?)
+    }
+}
+
+/// Error thrown when a repository cannot carry out an operation.
+#[derive(Debug)]
+pub enum StorageError {
+    /// There is no budget saved with the name. Holds the name.
+    NotFound(String),
+    /// A saved budget is not a valid document.
+    InvalidDocument(document::ParseError),
+    /// The journal of a budget cannot be read or replayed.
+    InvalidJournal(JournalError),
+    /// The underlying storage failed.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::NotFound(name) => write!(f, "no budget named \"{}\"", name),
+            StorageError::InvalidDocument(err) => write!(f, "invalid budget document: {}", err),
+            StorageError::InvalidJournal(err) => write!(f, "invalid budget journal: {}", err),
+            #[cfg(feature = "std")]
+            StorageError::Io(err) => write!(f, "storage failed: {}", err),
+        }
+    }
+}
+
+impl core::error::Error for StorageError { }
+
+impl From<document::ParseError> for StorageError {
+    fn from(err: document::ParseError) -> Self {
+        StorageError::InvalidDocument(err)
+    }
+}
+
+impl From<JournalError> for StorageError {
+    fn from(err: JournalError) -> Self {
+        StorageError::InvalidJournal(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
@@ -0,0 +1,174 @@
+use alloc::vec::Vec;
+use crate::budget::Budget;
+use crate::budget_group::BudgetGroup;
+use crate::budget_item::{BudgetItem, Period};
+
+// A template group: its name, and the name and share of the income in percent of each item.
+type GroupTemplate = (&'static str, &'static [(&'static str, u64)]);
+
+const FIFTY_THIRTY_TWENTY: &[GroupTemplate] = &[
+    ("Needs", &[("Rent", 30), ("Groceries", 10), ("Utilities", 5), ("Transport", 5)]),
+    ("Wants", &[("Dining out", 10), ("Entertainment", 10), ("Shopping", 10)]),
+    ("Savings", &[("Emergency fund", 10), ("Retirement", 10)]),
+];
+
+const ZERO_BASED: &[GroupTemplate] = &[
+    ("Housing", &[("Rent", 30), ("Utilities", 5)]),
+    ("Food", &[("Groceries", 12), ("Dining out", 3)]),
+    ("Transport", &[("Fuel", 6), ("Public transport", 4)]),
+    ("Personal", &[("Clothing", 4), ("Entertainment", 6), ("Subscriptions", 2)]),
+    ("Savings", &[("Emergency fund", 10), ("Retirement", 10), ("Unassigned", 8)]),
+];
+
+const STUDENT: &[GroupTemplate] = &[
+    ("Housing", &[("Rent", 40), ("Internet", 3)]),
+    ("Food", &[("Groceries", 20), ("Canteen", 5)]),
+    ("Study", &[("Books", 5), ("Supplies", 2)]),
+    ("Transport", &[("Public transport", 5)]),
+    ("Leisure", &[("Going out", 8), ("Subscriptions", 2)]),
+    ("Savings", &[("Buffer", 5)]),
+];
+
+const FAMILY: &[GroupTemplate] = &[
+    ("Housing", &[("Mortgage", 20), ("Utilities", 6), ("Home insurance", 2)]),
+    ("Food", &[("Groceries", 15)]),
+    ("Children", &[("Childcare", 10), ("Clothing", 3), ("Activities", 2)]),
+    ("Transport", &[("Car", 8), ("Fuel", 4)]),
+    ("Health", &[("Insurance", 4)]),
+    ("Leisure", &[("Holidays", 5), ("Entertainment", 3)]),
+    ("Savings", &[("Emergency fund", 8), ("Education fund", 5)]),
+];
+
+/// Create a budget following the 50/30/20 rule: half of the income for needs, 30% for wants, and
+/// 20% for savings.
+///
+/// # Parameters
+/// * `monthly_net_income` - the monthly income after taxes.
+///
+/// # Returns
+/// A budget with an "Income" group followed by a "Needs", a "Wants" and a "Savings" group, each
+/// with monthly placeholder items sized as a share of the income.
+///
+/// # Panics
+/// If the income is not a finite number greater than 0, the function will panic.
+///
+/// ```
+/// use rbp_core::templates::fifty_thirty_twenty;
+/// let budget = fifty_thirty_twenty(3_000.0);
+/// assert_eq!(-1_500.0, budget.group("Needs").unwrap().total());
+/// assert_eq!(0.0, budget.total());
+/// ```
+pub fn fifty_thirty_twenty(monthly_net_income: f64) -> Budget {
+    build("50/30/20 budget", monthly_net_income, FIFTY_THIRTY_TWENTY)
+}
+
+/// Create a zero-based budget, in which every unit of the income is given a job, so the total is
+/// exactly 0. What is not planned for yet is held by an "Unassigned" savings item.
+///
+/// # Parameters
+/// * `monthly_net_income` - the monthly income after taxes.
+///
+/// # Returns
+/// A budget with an "Income" group followed by groups of monthly placeholder items.
+///
+/// # Panics
+/// If the income is not a finite number greater than 0, the function will panic.
+pub fn zero_based(monthly_net_income: f64) -> Budget {
+    build("Zero-based budget", monthly_net_income, ZERO_BASED)
+}
+
+/// Create a budget for a student, with rent, food and study costs, and a small buffer. Part of
+/// the income is left unplanned for irregular costs.
+///
+/// # Parameters
+/// * `monthly_income` - the monthly income, e.g. from grants and a part-time job.
+///
+/// # Returns
+/// A budget with an "Income" group followed by groups of monthly placeholder items.
+///
+/// # Panics
+/// If the income is not a finite number greater than 0, the function will panic.
+pub fn student(monthly_income: f64) -> Budget {
+    build("Student budget", monthly_income, STUDENT)
+}
+
+/// Create a budget for a family, with housing, children, transport and savings costs. Part of
+/// the income is left unplanned for irregular costs.
+///
+/// # Parameters
+/// * `monthly_net_income` - the monthly income of the household after taxes.
+///
+/// # Returns
+/// A budget with an "Income" group followed by groups of monthly placeholder items.
+///
+/// # Panics
+/// If the income is not a finite number greater than 0, the function will panic.
+pub fn family(monthly_net_income: f64) -> Budget {
+    build("Family budget", monthly_net_income, FAMILY)
+}
+
+// Build a budget from a template. Amounts are whole cents; the last item of a template whose
+// shares add up to 100% gets the remainder, so that rounding does not leave anything over.
+fn build(name: &str, income: f64, template: &[GroupTemplate]) -> Budget {
+    let mut budget = Budget::new(name);
+    let mut income_group = BudgetGroup::new("Income");
+    income_group.add(BudgetItem::with_income("Net income", income, Period::Every1Month));
+    budget.add_group(income_group);
+
+    // Rounded by truncation, as `f64::round` needs the standard library. The income is positive.
+    let income_cents = (income * 100.0 + 0.5) as u64;
+    let mut items: Vec<(&str, &str, u64)> = template.iter()
+        .flat_map(|(group, items)| items.iter().map(move |(item, share)| (*group, *item, income_cents * share / 100)))
+        .collect();
+    let shares: u64 = template.iter().flat_map(|(_, items)| items.iter()).map(|(_, share)| share).sum();
+    let planned: u64 = items.iter().map(|(_, _, cents)| cents).sum();
+    if let (100, Some(last)) = (shares, items.last_mut()) {
+        last.2 += income_cents - planned;
+    }
+
+    for (group_name, _) in template {
+        let mut group = BudgetGroup::new(group_name);
+        for (_, item, cents) in items.iter().filter(|(group, _, cents)| group == group_name && *cents > 0) {
+            group.add(BudgetItem::with_expense(item, *cents as f64 / 100.0, Period::Every1Month));
+        }
+        budget.add_group(group);
+    }
+    budget
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::templates::{family, fifty_thirty_twenty, student, zero_based, FAMILY, FIFTY_THIRTY_TWENTY, STUDENT, ZERO_BASED};
+
+    #[test]
+    fn shares() {
+        let total = |template: &[(&str, &[(&str, u64)])]| -> u64 {
+            template.iter().flat_map(|(_, items)| items.iter()).map(|(_, share)| share).sum()
+        };
+        assert_eq!(100, total(FIFTY_THIRTY_TWENTY));
+        assert_eq!(100, total(ZERO_BASED));
+        assert!(total(STUDENT) < 100);
+        assert!(total(FAMILY) < 100);
+    }
+
+    #[test]
+    fn fifty_thirty_twenty_split() {
+        let budget = fifty_thirty_twenty(2_000.0);
+        assert_eq!(4, budget.enumerate().len());
+        assert_eq!(-1_000.0, budget.group("Needs").unwrap().total());
+        assert_eq!(-600.0, budget.group("Wants").unwrap().total());
+        assert_eq!(-400.0, budget.group("Savings").unwrap().total());
+    }
+
+    #[test]
+    fn zero_based_leaves_nothing() {
+        let budget = zero_based(1_234.57);
+        assert!(budget.total().abs() < 0.005);
+    }
+
+    #[test]
+    fn leftover_for_student_and_family() {
+        assert!((student(1_000.0).total() - 50.0).abs() < 0.005);
+        assert!((family(5_000.0).total() - 250.0).abs() < 0.005);
+    }
+}
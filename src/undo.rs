@@ -0,0 +1,296 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::budget::Budget;
+use crate::budget_group::{BudgetGroup, InvalidIndex, ItemOrder};
+use crate::budget_item::BudgetItem;
+
+/// A reversible change to a budget, applied with
+/// [`Budget::apply`](../budget/struct.Budget.html#method.apply).
+///
+/// Groups and items are addressed by their indices, as discovered using
+/// [`Budget::enumerate`](../budget/struct.Budget.html#method.enumerate) and
+/// [`BudgetGroup::enumerate`](../budget_group/struct.BudgetGroup.html#method.enumerate).
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::undo::Edit;
+/// let mut budget = Budget::new("Home");
+/// budget.apply(Edit::AddGroup(BudgetGroup::new("Housing"))).unwrap();
+/// budget.apply(Edit::AddItem(0, BudgetItem::with_expense("Rent", 900.0, Period::Every1Month))).unwrap();
+/// assert_eq!(-900.0, budget.total());
+///
+/// assert!(budget.undo());
+/// assert_eq!(0.0, budget.total());
+/// assert!(budget.redo());
+/// assert_eq!(-900.0, budget.total());
+/// ```
+pub enum Edit {
+    /// Add a group to the end of the budget.
+    AddGroup(BudgetGroup),
+    /// Insert a group at an index, shifting the later groups.
+    InsertGroup(usize, BudgetGroup),
+    /// Remove the group at an index.
+    RemoveGroup(usize),
+    /// Give the group at an index a new name.
+    RenameGroup(usize, String),
+    /// Add an item to the group at an index.
+    AddItem(usize, BudgetItem),
+    /// Insert an item, given the index of its group and the index it should get in the group. The
+    /// index only matters in a [manually ordered](../budget_group/enum.ItemOrder.html#variant.Manual)
+    /// group; a sorted group puts the item in its sorted place.
+    InsertItem(usize, usize, BudgetItem),
+    /// Remove an item, given the index of its group and its index in the group.
+    RemoveItem(usize, usize),
+    /// Replace an item, given the index of its group and its index in the group.
+    UpdateItem(usize, usize, BudgetItem),
+    /// Move an item to another group, given the index of its group, its index in the group, and
+    /// the index of the other group. As with [`AddItem`](#variant.AddItem), a manually ordered
+    /// group gets the item at its end.
+    MoveItem(usize, usize, usize),
+}
+
+// The undo and redo stacks of a budget. Both hold the edits that reverse the edit done last.
+#[derive(Default)]
+pub(crate) struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Record the reverse of a newly applied edit. This makes the undone edits unreachable.
+    pub(crate) fn applied(&mut self, reverse: Edit) {
+        self.undo.push(reverse);
+        self.redo.clear();
+    }
+
+    // Undo the last edit on a budget, returning whether there was one.
+    pub(crate) fn undo(&mut self, budget: &mut Budget) -> bool {
+        move_edit(&mut self.undo, &mut self.redo, budget)
+    }
+
+    // Redo the last undone edit on a budget, returning whether there was one.
+    pub(crate) fn redo(&mut self, budget: &mut Budget) -> bool {
+        move_edit(&mut self.redo, &mut self.undo, budget)
+    }
+}
+
+// Apply the top edit of one stack, and push its reverse onto the other.
+fn move_edit(from: &mut Vec<Edit>, to: &mut Vec<Edit>, budget: &mut Budget) -> bool {
+    match from.pop().map(|edit| run(budget, edit)) {
+        Some(Ok(reverse)) => {
+            to.push(reverse);
+            true
+        }
+        // The stacks only hold edits that are valid for the state they are applied to, as every
+        // change outside of them clears the history.
+        _ => false,
+    }
+}
+
+// Apply an edit to a budget, returning the edit that reverses it. The budget is unchanged if the
+// edit is invalid.
+pub(crate) fn run(budget: &mut Budget, edit: Edit) -> Result<Edit, InvalidIndex> {
+    match edit {
+        Edit::AddGroup(group) => {
+            budget.add_group(group);
+            Ok(Edit::RemoveGroup(budget.enumerate().len() - 1))
+        }
+        Edit::InsertGroup(idx, group) => {
+            budget.insert_group(idx, group)?;
+            Ok(Edit::RemoveGroup(idx))
+        }
+        Edit::RemoveGroup(idx) => {
+            let group = budget.remove_group(idx)?;
+            Ok(Edit::InsertGroup(idx, group))
+        }
+        Edit::RenameGroup(idx, name) => {
+            let old = budget.enumerate().nth(idx).ok_or(InvalidIndex { })?.1.name().clone();
+            budget.rename_group(idx, &name)?;
+            Ok(Edit::RenameGroup(idx, old))
+        }
+        Edit::AddItem(group_idx, item) => {
+            let added = item.clone();
+            budget.add_item(group_idx, item)?;
+            Ok(Edit::RemoveItem(group_idx, position(budget, group_idx, &added)))
+        }
+        Edit::InsertItem(group_idx, item_idx, item) => {
+            let group = budget.enumerate().nth(group_idx).ok_or(InvalidIndex { })?.1;
+            if item_idx > group.len() {
+                return Err(InvalidIndex { });
+            }
+            let (last, order) = (group.len(), group.order());
+            let added = item.clone();
+            budget.add_item(group_idx, item)?;
+            if order == ItemOrder::Manual && item_idx != last {
+                budget.reorder_item(group_idx, last, item_idx)?;
+            }
+            Ok(Edit::RemoveItem(group_idx, position(budget, group_idx, &added)))
+        }
+        Edit::RemoveItem(group_idx, item_idx) => {
+            let item = budget.remove_item(group_idx, item_idx)?;
+            Ok(Edit::InsertItem(group_idx, item_idx, item))
+        }
+        Edit::UpdateItem(group_idx, item_idx, item) => {
+            let added = item.clone();
+            let old = budget.update_item(group_idx, item_idx, item)?;
+            Ok(Edit::UpdateItem(group_idx, position(budget, group_idx, &added), old))
+        }
+        Edit::MoveItem(from_group, item_idx, to_group) => {
+            let item = budget.enumerate().nth(from_group)
+                .and_then(|(_, group)| group.get(item_idx))
+                .ok_or(InvalidIndex { })?
+                .clone();
+            budget.move_item(from_group, item_idx, to_group)?;
+            Ok(Edit::MoveItem(to_group, position(budget, to_group, &item), from_group))
+        }
+    }
+}
+
+// The index an item ended up at in a group, after it was added and the group was re-ordered. Equal
+// items with equal amounts are interchangeable, so any of them will do.
+fn position(budget: &Budget, group_idx: usize, item: &BudgetItem) -> usize {
+    budget.enumerate().nth(group_idx)
+        .and_then(|(_, group)| group.enumerate().find(|(_, other)| other.identical(item)))
+        .map(|(idx, _)| idx)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::Budget;
+    use crate::budget_group::{BudgetGroup, ItemOrder};
+    use crate::budget_item::{BudgetItem, Period};
+    use crate::undo::Edit;
+
+    fn names(budget: &Budget) -> Vec<String> {
+        budget.enumerate().map(|(_, group)| group.name().clone()).collect()
+    }
+
+    fn expense(name: &str, amount: f64) -> BudgetItem {
+        BudgetItem::with_expense(name, amount, Period::Every1Month)
+    }
+
+    #[test]
+    fn undo_and_redo_every_edit() {
+        let mut budget = Budget::new("Home");
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Food"))).unwrap();
+        budget.apply(Edit::InsertGroup(0, BudgetGroup::new("Housing"))).unwrap();
+        budget.apply(Edit::AddItem(0, expense("Water", 30.0))).unwrap();
+        budget.apply(Edit::AddItem(0, expense("Electricity", 50.0))).unwrap();
+        budget.apply(Edit::UpdateItem(0, 1, expense("Aaa water", 40.0))).unwrap();
+        budget.apply(Edit::RemoveItem(0, 1)).unwrap();
+        budget.apply(Edit::MoveItem(0, 0, 1)).unwrap();
+        budget.apply(Edit::MoveItem(1, 0, 0)).unwrap();
+        budget.apply(Edit::RenameGroup(1, "Groceries".to_owned())).unwrap();
+        budget.apply(Edit::RemoveGroup(0)).unwrap();
+        assert_eq!(vec!["Groceries"], names(&budget));
+
+        for _ in 0..10 {
+            assert!(budget.undo());
+        }
+        assert!(!budget.undo());
+        assert_eq!(0, budget.enumerate().len());
+
+        for _ in 0..6 {
+            assert!(budget.redo());
+        }
+        assert_eq!(vec!["Housing", "Food"], names(&budget));
+        assert_eq!(-40.0, budget.total());
+        budget.undo();
+        assert_eq!(-90.0, budget.total());
+        budget.undo();
+        assert_eq!(-80.0, budget.total());
+    }
+
+    #[test]
+    fn undo_move_item() {
+        let mut budget = Budget::new("Home");
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Housing"))).unwrap();
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Car"))).unwrap();
+        budget.apply(Edit::AddItem(0, expense("Parking", 40.0))).unwrap();
+        budget.apply(Edit::AddItem(0, expense("Rent", 900.0))).unwrap();
+        let total = |budget: &Budget, name| budget.group(name).unwrap().total();
+
+        assert!(budget.apply(Edit::MoveItem(0, 2, 1)).is_err());
+        budget.apply(Edit::MoveItem(0, 0, 1)).unwrap();
+        assert_eq!(-40.0, total(&budget, "Car"));
+        assert!(budget.undo());
+        assert_eq!(-940.0, total(&budget, "Housing"));
+        assert_eq!(0.0, total(&budget, "Car"));
+        assert!(budget.redo());
+        assert_eq!(-40.0, total(&budget, "Car"));
+    }
+
+    #[test]
+    fn undo_remove_in_manual_order() {
+        let mut group = BudgetGroup::new("Food");
+        group.set_order(ItemOrder::Manual);
+        group.add(expense("Lunch", 100.0));
+        group.add(expense("Dinner", 200.0));
+        group.add(expense("Breakfast", 50.0));
+        let mut budget = Budget::new("Home");
+        budget.add_group(group);
+        let items = |budget: &Budget| budget.group("Food").unwrap().iter().map(|item| item.name().clone()).collect::<Vec<_>>();
+
+        budget.apply(Edit::RemoveItem(0, 1)).unwrap();
+        assert_eq!(vec!["Lunch", "Breakfast"], items(&budget));
+        assert!(budget.undo());
+        assert_eq!(vec!["Lunch", "Dinner", "Breakfast"], items(&budget));
+        assert!(budget.redo());
+        assert_eq!(vec!["Lunch", "Breakfast"], items(&budget));
+
+        assert!(budget.apply(Edit::InsertItem(0, 3, expense("Snack", 10.0))).is_err());
+        budget.apply(Edit::InsertItem(0, 0, expense("Snack", 10.0))).unwrap();
+        assert_eq!(vec!["Snack", "Lunch", "Breakfast"], items(&budget));
+        assert!(budget.undo());
+        assert_eq!(vec!["Lunch", "Breakfast"], items(&budget));
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut budget = Budget::new("Home");
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Food"))).unwrap();
+        budget.undo();
+        assert!(budget.can_redo());
+
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Housing"))).unwrap();
+        assert!(!budget.can_redo());
+        assert!(!budget.redo());
+        assert_eq!(vec!["Housing"], names(&budget));
+    }
+
+    #[test]
+    fn invalid_edit_is_not_recorded() {
+        let mut budget = Budget::new("Home");
+        assert!(budget.apply(Edit::RemoveGroup(0)).is_err());
+        assert!(budget.apply(Edit::AddItem(0, expense("Rent", 900.0))).is_err());
+        assert!(!budget.can_undo());
+    }
+
+    #[test]
+    fn direct_changes_clear_history() {
+        let mut budget = Budget::new("Home");
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Food"))).unwrap();
+        budget.apply(Edit::AddGroup(BudgetGroup::new("Housing"))).unwrap();
+        budget.undo();
+
+        budget.add_group(BudgetGroup::new("Car"));
+        assert!(!budget.can_undo());
+        assert!(!budget.can_redo());
+    }
+}
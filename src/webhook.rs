@@ -0,0 +1,223 @@
+use alloc::string::String;
+use core::fmt::Write;
+use crate::audit::Change;
+use crate::clock::Timestamp;
+use crate::json;
+
+/// The version of the payload format produced by [`payload`]. It is only increased by changes
+/// that can break consumers, i.e. when fields are removed, renamed, or change meaning. New fields
+/// may be added without a version change.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Get the stable event type name of a change, as used in the `event` field of a payload.
+///
+/// # Parameters
+/// * `change` - the change to name.
+///
+/// # Returns
+/// One of `budget.group.added`, `budget.group.removed`, `budget.group.renamed`,
+/// `budget.item.added`, `budget.item.removed`, `budget.item.updated`, `budget.item.moved`,
+/// `budget.item.reordered`, `budget.group.order_changed` and `budget.defaults.changed`.
+pub fn event_type(change: &Change) -> &'static str {
+    match change {
+        Change::GroupAdded { .. } => "budget.group.added",
+        Change::GroupRemoved { .. } => "budget.group.removed",
+        Change::GroupRenamed { .. } => "budget.group.renamed",
+        Change::ItemAdded { .. } => "budget.item.added",
+        Change::ItemRemoved { .. } => "budget.item.removed",
+        Change::ItemUpdated { .. } => "budget.item.updated",
+        Change::ItemMoved { .. } => "budget.item.moved",
+        Change::ItemReordered { .. } => "budget.item.reordered",
+        Change::OrderChanged { .. } => "budget.group.order_changed",
+        Change::DefaultsChanged { .. } => "budget.defaults.changed",
+    }
+}
+
+/// Serialize a change into a self-describing JSON payload, ready to be posted to a webhook or put
+/// on a message queue.
+///
+/// The payload is an object with these fields:
+///
+/// * `schema` - the [`SCHEMA_VERSION`] of the payload.
+/// * `event` - the [`event_type`] of the change.
+/// * `time` - the time of the change, in seconds since the UNIX epoch.
+/// * `budget` - the name of the changed budget.
+/// * `data` - the entities involved in the change. Groups are objects with a `name`, and items
+///   are objects with a `name`, a `kind` (`income` or `expense`), an `amount`, and a `period`
+///   (`monthly`, `bimonthly`, `quarterly`, `half-yearly` or `yearly`). Group changes have a
+///   `group`, except renames, which have the group `before` and `after` the change. Item changes
+///   have the `group` holding the item, and the `item`, except updates, which have the item
+///   `before` and `after` the change, and moves, which have the `item` and the group `before` and
+///   `after` the move. Added groups also have the `index` of the group in the budget, and
+///   reordered items the index `to` which they were moved. Order changes have the `group` and its
+///   new `order` (`sorted` or `manual`). Defaults
+///   changes have the defaults `before` and `after` the change, which are objects with a
+///   `currency`, a `period`, a `rounding` (`none`, `half-up` or `half-even`), and a `locale`,
+///   which is `null` or an object with a `decimal_separator` and a `thousands_separator`, the
+///   latter `null` if there is none.
+///
+/// # Parameters
+/// * `budget` - the name of the changed budget.
+/// * `time` - the time of the change.
+/// * `change` - the change to serialize.
+///
+/// # Returns
+/// The JSON text.
+///
+/// ```
+/// use rbp_core::audit::Change;
+/// use rbp_core::clock::Timestamp;
+/// use rbp_core::webhook::payload;
+/// let change = Change::GroupAdded { group: "Housing".to_owned(), index: 0 };
+/// assert_eq!(
+///     r#"{"schema":1,"event":"budget.group.added","time":60,"budget":"Home","data":{"group":{"name":"Housing"},"index":0}}"#,
+///     payload("Home", Timestamp(60), &change));
+/// ```
+pub fn payload(budget: &str, time: Timestamp, change: &Change) -> String {
+    let mut out = String::new();
+    // Writing to a String cannot fail.
+    let _ = write!(out, "{{\"schema\":{},\"event\":", SCHEMA_VERSION);
+    json::string(&mut out, event_type(change));
+    let _ = write!(out, ",\"time\":{},\"budget\":", time.seconds());
+    json::string(&mut out, budget);
+
+    out.push_str(",\"data\":{");
+    match change {
+        Change::GroupAdded { group, index } => {
+            write_group(&mut out, "group", group);
+            let _ = write!(out, ",\"index\":{}", index);
+        }
+        Change::GroupRemoved { group } => {
+            write_group(&mut out, "group", group);
+        }
+        Change::GroupRenamed { from, to } => {
+            write_group(&mut out, "before", from);
+            out.push(',');
+            write_group(&mut out, "after", to);
+        }
+        Change::ItemAdded { group, item } | Change::ItemRemoved { group, item } => {
+            write_group(&mut out, "group", group);
+            out.push_str(",\"item\":");
+            json::item(&mut out, item);
+        }
+        Change::ItemUpdated { group, from, to } => {
+            write_group(&mut out, "group", group);
+            out.push_str(",\"before\":");
+            json::item(&mut out, from);
+            out.push_str(",\"after\":");
+            json::item(&mut out, to);
+        }
+        Change::ItemMoved { from, to, item } => {
+            write_group(&mut out, "before", from);
+            out.push(',');
+            write_group(&mut out, "after", to);
+            out.push_str(",\"item\":");
+            json::item(&mut out, item);
+        }
+        Change::ItemReordered { group, item, to } => {
+            write_group(&mut out, "group", group);
+            out.push_str(",\"item\":");
+            json::item(&mut out, item);
+            let _ = write!(out, ",\"to\":{}", to);
+        }
+        Change::OrderChanged { group, order } => {
+            write_group(&mut out, "group", group);
+            out.push_str(",\"order\":");
+            json::string(&mut out, order.keyword());
+        }
+        Change::DefaultsChanged { from, to } => {
+            out.push_str("\"before\":");
+            json::defaults(&mut out, from);
+            out.push_str(",\"after\":");
+            json::defaults(&mut out, to);
+        }
+    }
+    out.push_str("}}");
+    out
+}
+
+// Append a field holding a group snapshot.
+fn write_group(out: &mut String, key: &str, name: &str) {
+    json::string(out, key);
+    out.push_str(":{\"name\":");
+    json::string(out, name);
+    out.push('}');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::Change;
+    use crate::budget::BudgetDefaults;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::clock::Timestamp;
+    use crate::locale::Locale;
+    use crate::webhook::{event_type, payload};
+
+    #[test]
+    fn event_types() {
+        assert_eq!("budget.group.renamed", event_type(&Change::GroupRenamed { from: "a".to_owned(), to: "b".to_owned() }));
+        assert_eq!("budget.group.removed", event_type(&Change::GroupRemoved { group: "a".to_owned() }));
+    }
+
+    #[test]
+    fn group_payloads() {
+        let renamed = Change::GroupRenamed { from: "Housing".to_owned(), to: "Home".to_owned() };
+        assert_eq!(concat!(
+            "{\"schema\":1,\"event\":\"budget.group.renamed\",\"time\":5,\"budget\":\"My \\\"budget\\\"\",",
+            "\"data\":{\"before\":{\"name\":\"Housing\"},\"after\":{\"name\":\"Home\"}}}"
+        ), payload("My \"budget\"", Timestamp(5), &renamed));
+    }
+
+    #[test]
+    fn defaults_payload() {
+        let changed = Change::DefaultsChanged {
+            from: BudgetDefaults::default(),
+            to: BudgetDefaults {
+                currency: "DKK".to_owned(),
+                period: Period::Every12Months,
+                rounding: RoundingPolicy::HalfUp,
+                locale: Some(Locale::DE_DE),
+            },
+        };
+        assert_eq!(concat!(
+            "{\"schema\":1,\"event\":\"budget.defaults.changed\",\"time\":5,\"budget\":\"Home\",",
+            "\"data\":{\"before\":{\"currency\":\"\",\"period\":\"monthly\",\"rounding\":\"none\",\"locale\":null},",
+            "\"after\":{\"currency\":\"DKK\",\"period\":\"yearly\",\"rounding\":\"half-up\",",
+            "\"locale\":{\"decimal_separator\":\",\",\"thousands_separator\":\".\"}}}}"
+        ), payload("Home", Timestamp(5), &changed));
+    }
+
+    #[test]
+    fn item_payloads() {
+        let rent = BudgetItem::with_expense("Rent", 900.0, Period::Every1Month);
+        let removed = Change::ItemRemoved { group: "Housing".to_owned(), item: rent.clone() };
+        assert_eq!(concat!(
+            "{\"schema\":1,\"event\":\"budget.item.removed\",\"time\":5,\"budget\":\"Home\",",
+            "\"data\":{\"group\":{\"name\":\"Housing\"},",
+            "\"item\":{\"name\":\"Rent\",\"kind\":\"expense\",\"amount\":900,\"period\":\"monthly\"}}}"
+        ), payload("Home", Timestamp(5), &removed));
+
+        let updated = Change::ItemUpdated {
+            group: "Housing".to_owned(),
+            from: rent,
+            to: BudgetItem::with_expense("Rent", 2_850.0, Period::Every3Months),
+        };
+        assert_eq!(concat!(
+            "{\"schema\":1,\"event\":\"budget.item.updated\",\"time\":5,\"budget\":\"Home\",",
+            "\"data\":{\"group\":{\"name\":\"Housing\"},",
+            "\"before\":{\"name\":\"Rent\",\"kind\":\"expense\",\"amount\":900,\"period\":\"monthly\"},",
+            "\"after\":{\"name\":\"Rent\",\"kind\":\"expense\",\"amount\":2850,\"period\":\"quarterly\"}}}"
+        ), payload("Home", Timestamp(5), &updated));
+
+        let moved = Change::ItemMoved {
+            from: "Housing".to_owned(),
+            to: "Car".to_owned(),
+            item: BudgetItem::with_expense("Parking", 40.0, Period::Every1Month),
+        };
+        assert_eq!(concat!(
+            "{\"schema\":1,\"event\":\"budget.item.moved\",\"time\":5,\"budget\":\"Home\",",
+            "\"data\":{\"before\":{\"name\":\"Housing\"},\"after\":{\"name\":\"Car\"},",
+            "\"item\":{\"name\":\"Parking\",\"kind\":\"expense\",\"amount\":40,\"period\":\"monthly\"}}}"
+        ), payload("Home", Timestamp(5), &moved));
+    }
+}
@@ -0,0 +1,106 @@
+use alloc::string::String;
+use crate::budget::Budget;
+use crate::report::income_and_expenses;
+
+/// The version of the widget structs in this module. It is only increased by changes that can
+/// break consumers, i.e. when fields are removed, renamed, or change meaning, so that a widget
+/// extension can tell whether it understands the data handed to it by its app.
+pub const WIDGET_VERSION: u32 = 1;
+
+/// The monthly surplus of a budget, for a home-screen widget or a watch complication.
+///
+/// ```
+/// use rbp_core::budget::Budget;
+/// use rbp_core::budget_group::BudgetGroup;
+/// use rbp_core::budget_item::{BudgetItem, Period};
+/// use rbp_core::widgets::SurplusWidget;
+/// let mut group = BudgetGroup::new("Home");
+/// group.add(BudgetItem::with_income("Salary", 2_000.0, Period::Every1Month));
+/// group.add(BudgetItem::with_expense("Rent", 1_200.0, Period::Every12Months));
+/// let mut budget = Budget::new("Mine");
+/// budget.add_group(group);
+///
+/// let widget = SurplusWidget::compute(&budget);
+/// assert_eq!(1_900.0, widget.surplus);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurplusWidget {
+    /// The [`WIDGET_VERSION`] the widget was computed with.
+    pub version: u32,
+    /// The currency of the amounts, from the budget's defaults.
+    pub currency: String,
+    /// The planned monthly income.
+    pub income: f64,
+    /// The planned monthly expenses, as a positive number.
+    pub expenses: f64,
+    /// What is left of the income each month, which is negative for a shortfall.
+    pub surplus: f64,
+}
+
+impl SurplusWidget {
+
+    /// Compute the widget from a budget, in a single pass over its items. The amounts are rounded
+    /// according to the rounding policy of the budget's defaults, so the surplus is the
+    /// [total](../budget/struct.Budget.html#method.total) of the budget.
+    ///
+    /// # Parameters
+    /// * `budget` - the budget to summarize.
+    pub fn compute(budget: &Budget) -> SurplusWidget {
+        let (income, expenses) = income_and_expenses(budget);
+        SurplusWidget {
+            version: WIDGET_VERSION,
+            currency: budget.defaults().currency.clone(),
+            income,
+            expenses: -expenses,
+            surplus: budget.defaults().rounding.round(income + expenses),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, BudgetDefaults};
+    use crate::budget_group::BudgetGroup;
+    use crate::budget_item::{BudgetItem, Period, RoundingPolicy};
+    use crate::widgets::{SurplusWidget, WIDGET_VERSION};
+
+    #[test]
+    fn surplus() {
+        let mut group = BudgetGroup::new("Home");
+        group.add(BudgetItem::with_income("Salary", 1_000.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Rent", 900.0, Period::Every1Month));
+        group.add(BudgetItem::with_expense("Insurance", 600.0, Period::Every6Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(group);
+        budget.set_defaults(BudgetDefaults { currency: "€".to_owned(), ..BudgetDefaults::default() });
+
+        assert_eq!(SurplusWidget {
+            version: WIDGET_VERSION,
+            currency: "€".to_owned(),
+            income: 1_000.0,
+            expenses: 1_000.0,
+            surplus: 0.0,
+        }, SurplusWidget::compute(&budget));
+    }
+
+    #[test]
+    fn rounding() {
+        let mut group = BudgetGroup::new("Home");
+        group.add(BudgetItem::with_income("Salary", 1_000.0, Period::Every3Months));
+        group.add(BudgetItem::with_expense("Rent", 500.0, Period::Every3Months));
+        let mut budget = Budget::new("Home");
+        budget.add_group(group);
+        budget.set_defaults(BudgetDefaults { rounding: RoundingPolicy::HalfUp, ..BudgetDefaults::default() });
+
+        let widget = SurplusWidget::compute(&budget);
+        assert_eq!((333.33, 166.67), (widget.income, widget.expenses));
+        assert_eq!(budget.total(), widget.surplus);
+    }
+
+    #[test]
+    fn empty_budget() {
+        let widget = SurplusWidget::compute(&Budget::new("Home"));
+        assert_eq!(0.0, widget.surplus);
+        assert_eq!("", widget.currency);
+    }
+}